@@ -0,0 +1,82 @@
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Duration;
+use iced::Subscription;
+use crate::app::state::{Message, MinecraftLauncher};
+
+const FOCUS_REQUEST: &[u8; 5] = b"focus";
+
+static INSTANCE_LOCK: OnceLock<File> = OnceLock::new();
+static FOCUS_LISTENER: OnceLock<TcpListener> = OnceLock::new();
+
+pub fn acquire_single_instance() -> bool {
+    let Some(config_dir) = MinecraftLauncher::get_config_dir() else {
+        return true;
+    };
+    let Ok(lock) = OpenOptions::new().create(true).truncate(false).write(true).open(config_dir.join("instance.lock")) else {
+        return true;
+    };
+    
+    match lock.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => {
+            focus_running_instance(&config_dir);
+            return false;
+        }
+        Err(TryLockError::Error(_)) => return true,
+    }
+    let _ = INSTANCE_LOCK.set(lock);
+    
+    if let Ok(listener) = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+        if let Ok(addr) = listener.local_addr() {
+            let _ = std::fs::write(config_dir.join("instance.port"), addr.port().to_string());
+        }
+        let _ = FOCUS_LISTENER.set(listener);
+    }
+    true
+}
+
+fn focus_running_instance(config_dir: &Path) {
+    let port = std::fs::read_to_string(config_dir.join("instance.port"))
+        .ok()
+        .and_then(|port| port.trim().parse::<u16>().ok());
+    if let Some(port) = port {
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        if let Ok(mut stream) = TcpStream::connect_timeout(&addr, Duration::from_secs(1)) {
+            let _ = stream.write_all(FOCUS_REQUEST);
+        }
+    }
+}
+
+pub fn focus_requests() -> Subscription<Message> {
+    let Some(listener) = FOCUS_LISTENER.get().and_then(|listener| listener.try_clone().ok()) else {
+        return Subscription::none();
+    };
+    
+    Subscription::run_with_id(
+        "instance-focus",
+        iced::stream::channel(4, move |mut output| async move {
+            use iced::futures::SinkExt;
+            use tokio::io::AsyncReadExt;
+            
+            if listener.set_nonblocking(true).is_err() {
+                return;
+            }
+            let Ok(listener) = tokio::net::TcpListener::from_std(listener) else {
+                return;
+            };
+            loop {
+                if let Ok((mut stream, _)) = listener.accept().await {
+                    let mut request = [0u8; 5];
+                    let read = tokio::time::timeout(Duration::from_secs(2), stream.read_exact(&mut request)).await;
+                    if matches!(read, Ok(Ok(_))) && &request == FOCUS_REQUEST {
+                        let _ = output.send(Message::FocusRequested).await;
+                    }
+                }
+            }
+        })
+    )
+}
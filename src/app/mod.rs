@@ -1,3 +1,4 @@
+mod instance;
 mod state;
 mod styles;
 mod utils;
@@ -7,88 +8,262 @@ mod view;
 mod views;
 
 pub use state::*;
-pub use utils::{load_gif_frames, load_avatar_frames, load_icon, check_for_updates, fetch_server_status};
+pub use instance::acquire_single_instance;
+pub use utils::{connect_discord, detect_32bit_runtime, detect_legacy_data_dir, load_gif_frames, load_avatar_frames, load_icon, check_for_updates, fetch_server_status, cleanup_stale_files, load_content_versions, load_installed_mods, load_resourcepacks};
 
 use iced::Task;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
-use std::sync::Mutex;
-use std::path::PathBuf;
-use discord_rich_presence::{DiscordIpc, DiscordIpcClient};
+use std::sync::{Mutex, OnceLock};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use crate::minecraft::{ContentVersions, GameVersion, InstallerConfig, detect_shaderpacks, detect_worlds, get_versioned_game_directory, is_newer_version, set_bandwidth_limit, set_proxy_url, set_timeouts, validate_content_source_url};
 
 impl MinecraftLauncher {
     pub fn new() -> (Self, Task<Message>) {
-        let settings = Self::load_settings().unwrap_or_default();
+        let stored_settings = Self::load_settings();
+        let first_run = stored_settings.is_none();
+        let settings = stored_settings.unwrap_or_default();
         let play_stats = Self::load_play_stats().unwrap_or_default();
-        let gif_frames = load_gif_frames();
-        let avatar_frames = load_avatar_frames();
+        let (gif_frames, gif_stats) = load_gif_frames();
+        let (avatar_frames, avatar_stats) = load_avatar_frames();
         
-        let discord_client = Self::init_discord();
+        let discord_client = Arc::new(Mutex::new(None));
+        let config_warning = Self::config_dir_warning();
+        set_proxy_url(&settings.proxy_url);
+        set_timeouts(settings.connect_timeout_secs, settings.download_timeout_secs);
+        set_bandwidth_limit(settings.download_limit_mb);
+        let game_dir = get_versioned_game_directory(settings.selected_version);
+        let loader_version = settings.fabric_loader_override.clone()
+            .unwrap_or_else(|| settings.selected_version.fabric_loader_version().to_string());
+        let new_changelog_entries = match &settings.last_seen_version {
+            _ if first_run => 0,
+            Some(seen) => CHANGELOG.iter().filter(|(ver, _)| is_newer_version(ver, seen)).count(),
+            None => CHANGELOG.iter().filter(|(ver, _)| *ver == CURRENT_VERSION).count(),
+        };
         
         (
             Self {
                 nickname: settings.nickname,
-                ram_gb: settings.ram_gb,
+                account_mode: settings.account_mode,
+                ram_mb: settings.ram_mb,
                 selected_version: settings.selected_version,
                 shader_quality: settings.shader_quality,
+                optimized_jvm_flags: settings.optimized_jvm_flags,
+                use_system_java: settings.use_system_java,
+                env_vars: settings.env_vars.clone(),
+                garbage_collector: settings.garbage_collector,
+                quick_play: settings.quick_play,
+                available_worlds: detect_worlds(&game_dir),
+                sync_shaderpacks: settings.sync_shaderpacks,
+                sync_resourcepacks: settings.sync_resourcepacks,
+                mods_sync_policy: settings.mods_sync_policy,
+                shaderpacks_sync_policy: settings.shaderpacks_sync_policy,
+                resourcepacks_sync_policy: settings.resourcepacks_sync_policy,
+                shaderpack: settings.shaderpack,
+                available_shaderpacks: detect_shaderpacks(&game_dir),
+                resourcepacks: load_resourcepacks(&game_dir),
+                java_status: JavaStatus::for_version(settings.selected_version, settings.use_system_java),
+                prefetch_content: settings.prefetch_content,
+                prefetch: PrefetchState::Pending,
+                fast_launch: settings.fast_launch,
+                content_outdated: false,
+                content_changed: None,
+                glow_intensity: settings.glow_intensity.clamp(0.0, 1.0),
+                proxy_url: settings.proxy_url,
+                proxy_check: None,
+                proxy_checking: false,
+                content_source_url: settings.content_source_url,
+                fabric_loader_input: settings.fabric_loader_override.clone().unwrap_or_default(),
+                fabric_loader_override: settings.fabric_loader_override,
+                fabric_loader_checking: false,
+                fabric_loader_error: None,
+                connect_timeout_secs: settings.connect_timeout_secs,
+                download_timeout_secs: settings.download_timeout_secs,
+                download_limit_mb: settings.download_limit_mb,
+                download_limit_input: settings.download_limit_mb.map(|mb| mb.to_string()).unwrap_or_default(),
+                notifications_enabled: settings.notifications_enabled,
+                notification_sound: settings.notification_sound,
                 launch_state: LaunchState::CheckingUpdate,
-                active_tab: Tab::Dashboard,
+                active_tab: settings.active_tab,
                 game_running: Arc::new(AtomicBool::new(false)),
+                game_process: Arc::new(Mutex::new(None)),
+                kill_requested: false,
+                offline_launch: false,
+                custom_server: String::new(),
+                launch_server: None,
+                repairing: false,
+                verifying: false,
+                verify_report: None,
                 gif_frames,
                 avatar_frames,
+                animation_stats: [gif_stats, avatar_stats],
                 current_frame: 0,
                 update_checked: false,
                 play_stats,
                 current_session_seconds: 0,
-                discord_client,
+                discord_client: discord_client.clone(),
+                discord_link: DiscordLink::Connecting,
+                discord_failures: 0,
+                discord_retry_at: None,
                 game_start_time: None,
                 server_status: ServerStatus::default(),
                 crash_count: 0,
                 show_crash_dialog: false,
-                show_changelog: false,
+                crash_dialog_enabled: settings.crash_dialog_enabled,
+                show_changelog: new_changelog_entries > 0,
+                new_changelog_entries,
+                last_seen_version: if first_run { Some(CURRENT_VERSION.to_string()) } else { settings.last_seen_version.clone() },
                 crash_log: None,
+                kick_reason: None,
+                pending_update: None,
+                update_download: None,
+                mandatory_update: None,
+                config_warning,
+                memory_warning: None,
+                data_migration: None,
+                dismissed_data_dir: settings.dismissed_data_dir.clone(),
+                backup_status: None,
+                installed_mods: Vec::new(),
+                duplicate_mods: Vec::new(),
+                duplicate_mods_error: None,
+                shader_notice: None,
+                content_versions: ContentVersions::default(),
+                show_mod_list: false,
+                show_diagnostics: false,
+                runtime_32bit: false,
+                diagnostics: None,
+                show_log_files: false,
+                log_files: None,
+                confirm_log_cleanup: false,
+                log_cleanup_status: None,
+                show_player_list: false,
+                player_heads: HashMap::new(),
             },
             Task::batch([
                 Task::perform(check_for_updates(), Message::UpdateStatus),
                 Task::perform(fetch_server_status(), Message::ServerStatusUpdate),
+                Task::future(cleanup_stale_files()).discard(),
+                Task::perform(load_installed_mods(settings.selected_version, loader_version), Message::ModsScanned),
+                Task::perform(load_content_versions(settings.selected_version), Message::ContentVersionsComputed),
+                Task::perform(detect_32bit_runtime(settings.selected_version, settings.use_system_java), Message::RuntimeArchDetected),
+                Task::perform(detect_legacy_data_dir(), Message::LegacyDataDirFound),
+                Task::perform(connect_discord(discord_client), Message::DiscordConnectAttempted),
             ]),
         )
     }
     
-    fn init_discord() -> Arc<Mutex<Option<DiscordIpcClient>>> {
-        let client = DiscordIpcClient::new(DISCORD_CLIENT_ID)
-            .ok()
-            .and_then(|mut c| {
-                c.connect().ok()?;
-                Some(c)
-            });
-        Arc::new(Mutex::new(client))
+    pub fn installer_config(&self, version: GameVersion) -> InstallerConfig {
+        let content_source = match validate_content_source_url(&self.content_source_url) {
+            Ok(()) => self.content_source_url.as_str(),
+            Err(_) => "",
+        };
+        InstallerConfig::for_version(version)
+            .with_loader_version(&self.fabric_loader_version(version))
+            .with_sync_policies(self.mods_sync_policy, self.shaderpacks_sync_policy, self.resourcepacks_sync_policy)
+            .with_content_source(content_source)
+            .with_system_java(self.use_system_java)
+    }
+
+    pub fn fabric_loader_version(&self, version: GameVersion) -> String {
+        self.fabric_loader_override.clone().unwrap_or_else(|| version.fabric_loader_version().to_string())
+    }
+
+    pub fn current_settings(&self) -> LauncherSettings {
+        LauncherSettings { 
+            schema_version: SETTINGS_SCHEMA_VERSION,
+            nickname: self.nickname.clone(), 
+            account_mode: self.account_mode,
+            ram_mb: self.ram_mb,
+            ram_gb: None,
+            selected_version: self.selected_version,
+            shader_quality: self.shader_quality,
+            optimized_jvm_flags: self.optimized_jvm_flags,
+            use_system_java: self.use_system_java,
+            env_vars: self.env_vars.clone(),
+            dismissed_data_dir: self.dismissed_data_dir.clone(),
+            garbage_collector: self.garbage_collector,
+            quick_play: self.quick_play.clone(),
+            active_tab: self.active_tab.clone(),
+            sync_shaderpacks: self.sync_shaderpacks,
+            sync_resourcepacks: self.sync_resourcepacks,
+            mods_sync_policy: self.mods_sync_policy,
+            shaderpacks_sync_policy: self.shaderpacks_sync_policy,
+            resourcepacks_sync_policy: self.resourcepacks_sync_policy,
+            shaderpack: self.shaderpack.clone(),
+            prefetch_content: self.prefetch_content,
+            fast_launch: self.fast_launch,
+            glow_intensity: self.glow_intensity,
+            proxy_url: self.proxy_url.clone(),
+            content_source_url: self.content_source_url.clone(),
+            fabric_loader_override: self.fabric_loader_override.clone(),
+            connect_timeout_secs: self.connect_timeout_secs,
+            download_timeout_secs: self.download_timeout_secs,
+            download_limit_mb: self.download_limit_mb,
+            notifications_enabled: self.notifications_enabled,
+            notification_sound: self.notification_sound,
+            last_seen_version: self.last_seen_version.clone(),
+            crash_dialog_enabled: self.crash_dialog_enabled,
+        }
     }
 
     pub fn save_settings(&self) {
         if let Some(config_dir) = Self::get_config_dir() {
-            let settings = LauncherSettings { 
-                nickname: self.nickname.clone(), 
-                ram_gb: self.ram_gb,
-                selected_version: self.selected_version,
-                shader_quality: self.shader_quality,
-            };
-            if let Ok(json) = serde_json::to_string_pretty(&settings) {
-                let _ = std::fs::write(config_dir.join("settings.json"), json);
+            if let Ok(json) = serde_json::to_string_pretty(&self.current_settings()) {
+                let _ = write_atomic(&config_dir.join("settings.json"), json.as_bytes());
             }
         }
     }
 
     pub fn load_settings() -> Option<LauncherSettings> {
         let config_dir = Self::get_config_dir()?;
-        let content = std::fs::read_to_string(config_dir.join("settings.json")).ok()?;
-        serde_json::from_str(&content).ok()
+        let path = config_dir.join("settings.json");
+        let content = std::fs::read_to_string(&path).ok()?;
+        let settings = LauncherSettings::from_json(&content);
+        if settings.is_none() {
+            let _ = std::fs::copy(&path, config_dir.join("settings.json.bak"));
+        }
+        settings
     }
 
     pub fn get_config_dir() -> Option<PathBuf> {
-        let config_dir = directories::ProjectDirs::from("com", "bystep", "launcher")?.config_dir().to_path_buf();
-        std::fs::create_dir_all(&config_dir).ok()?;
-        Some(config_dir)
+        static CONFIG_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+        CONFIG_DIR.get_or_init(|| {
+            Self::primary_config_dir()
+                .filter(|dir| is_writable_dir(dir))
+                .or_else(|| Self::fallback_config_dir().filter(|dir| is_writable_dir(dir)))
+        }).clone()
+    }
+
+    fn primary_config_dir() -> Option<PathBuf> {
+        directories::ProjectDirs::from("com", "bystep", "launcher")
+            .map(|dirs| dirs.config_dir().to_path_buf())
+    }
+
+    fn fallback_config_dir() -> Option<PathBuf> {
+        let exe = std::env::current_exe().ok()?;
+        Some(exe.parent()?.join("config"))
+    }
+
+    fn config_dir_warning() -> Option<String> {
+        let primary = Self::primary_config_dir();
+        let used = Self::get_config_dir();
+        if used.is_some() && used == primary {
+            return None;
+        }
+        let attempted = primary
+            .map(|dir| dir.display().to_string())
+            .unwrap_or_else(|| "не определена".to_string());
+        Some(match used {
+            Some(dir) => format!(
+                "Нет доступа к папке настроек ({}), настройки сохраняются в {}",
+                attempted, dir.display()
+            ),
+            None => format!(
+                "Не удалось получить доступ к папке настроек ({}), изменения не сохранятся",
+                attempted
+            ),
+        })
     }
 
     pub fn get_game_data_dir() -> Option<PathBuf> {
@@ -99,7 +274,7 @@ impl MinecraftLauncher {
     pub fn save_play_stats(&self) {
         if let Some(config_dir) = Self::get_config_dir() {
             if let Ok(json) = serde_json::to_string_pretty(&self.play_stats) {
-                let _ = std::fs::write(config_dir.join("playtime.json"), json);
+                let _ = write_atomic(&config_dir.join("playtime.json"), json.as_bytes());
             }
         }
     }
@@ -110,3 +285,19 @@ impl MinecraftLauncher {
         serde_json::from_str(&content).ok()
     }
 }
+
+pub fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path)
+}
+
+fn is_writable_dir(dir: &Path) -> bool {
+    if std::fs::create_dir_all(dir).is_err() {
+        return false;
+    }
+    let probe = dir.join(".write-test");
+    let writable = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
+}
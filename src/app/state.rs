@@ -1,18 +1,30 @@
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Datelike, Days, NaiveDate, TimeZone};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::process::Child;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::Mutex;
 use discord_rich_presence::DiscordIpcClient;
+use iced::task;
 use iced::widget::image;
-use crate::minecraft::{GameVersion, ShaderQuality};
+use iced::window;
+use crate::minecraft::{ContentVersions, DownloadKind, DuplicateMod, GameVersion, GarbageCollector, LogFile, ModInfo, QuickPlay, ShaderQuality, SyncPolicy, VerifyReport, DEFAULT_CONNECT_TIMEOUT_SECS, DEFAULT_DOWNLOAD_TIMEOUT_SECS, find_java, get_game_directory};
 
 pub const SERVER_ADDRESS: &str = "144.31.169.7:25565";
 pub const CURRENT_VERSION: &str = "1.1.3";
 pub const GITHUB_RELEASES_API: &str = "https://api.github.com/repos/PRISSET/Launcher/releases/latest";
 pub const INSTALLER_NAME: &str = "ByStep-Launcher-Setup.exe";
 pub const DISCORD_CLIENT_ID: &str = "1454405559120822426";
+pub const SETTINGS_SCHEMA_VERSION: u32 = 3;
+pub const MAX_RAM_32BIT_MB: u32 = 1536;
+pub const CRASH_DIALOG_THRESHOLD: u32 = 2;
+pub const MEMORY_HEADROOM_MB: u32 = 512;
+pub const MIN_SUGGESTED_RAM_MB: u32 = 1024;
+pub const DISCORD_RETRY_MISSING: std::time::Duration = std::time::Duration::from_secs(120);
+pub const DISCORD_RETRY_BASE: std::time::Duration = std::time::Duration::from_secs(5);
+pub const DISCORD_RETRY_MAX: std::time::Duration = std::time::Duration::from_secs(300);
 
 pub const CHANGELOG: &[(&str, &str)] = &[
     ("1.1.3", "Детальный прогресс установки, исправлен rate limit"),
@@ -28,41 +40,275 @@ pub const CHANGELOG: &[(&str, &str)] = &[
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LauncherSettings {
+    #[serde(default)]
+    pub schema_version: u32,
+    #[serde(default)]
     pub nickname: String,
-    pub ram_gb: u32,
+    #[serde(default)]
+    pub account_mode: AccountMode,
+    #[serde(default = "default_ram_mb")]
+    pub ram_mb: u32,
+    #[serde(default, skip_serializing)]
+    pub ram_gb: Option<u32>,
     #[serde(default)]
     pub selected_version: GameVersion,
     #[serde(default)]
     pub shader_quality: ShaderQuality,
+    #[serde(default)]
+    pub optimized_jvm_flags: bool,
+    #[serde(default)]
+    pub garbage_collector: GarbageCollector,
+    #[serde(default)]
+    pub quick_play: QuickPlay,
+    #[serde(default, deserialize_with = "deserialize_tab")]
+    pub active_tab: Tab,
+    #[serde(default = "default_true")]
+    pub sync_shaderpacks: bool,
+    #[serde(default = "default_true")]
+    pub sync_resourcepacks: bool,
+    #[serde(default = "default_mirror_policy")]
+    pub mods_sync_policy: SyncPolicy,
+    #[serde(default = "default_mirror_policy")]
+    pub shaderpacks_sync_policy: SyncPolicy,
+    #[serde(default)]
+    pub resourcepacks_sync_policy: SyncPolicy,
+    #[serde(default)]
+    pub shaderpack: Option<String>,
+    #[serde(default)]
+    pub prefetch_content: bool,
+    #[serde(default)]
+    pub fast_launch: bool,
+    #[serde(default = "default_glow_intensity")]
+    pub glow_intensity: f32,
+    #[serde(default)]
+    pub proxy_url: String,
+    #[serde(default)]
+    pub content_source_url: String,
+    #[serde(default)]
+    pub fabric_loader_override: Option<String>,
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u32,
+    #[serde(default = "default_download_timeout_secs")]
+    pub download_timeout_secs: u32,
+    #[serde(default)]
+    pub download_limit_mb: Option<u32>,
+    #[serde(default)]
+    pub notifications_enabled: bool,
+    #[serde(default = "default_true")]
+    pub notification_sound: bool,
+    #[serde(default)]
+    pub last_seen_version: Option<String>,
+    #[serde(default = "default_true")]
+    pub crash_dialog_enabled: bool,
+    #[serde(default)]
+    pub use_system_java: bool,
+    #[serde(default)]
+    pub env_vars: Vec<(String, String)>,
+    #[serde(default)]
+    pub dismissed_data_dir: Option<String>,
 }
 
 impl Default for LauncherSettings {
     fn default() -> Self {
         Self {
+            schema_version: SETTINGS_SCHEMA_VERSION,
             nickname: String::new(),
-            ram_gb: 4,
+            account_mode: AccountMode::default(),
+            ram_mb: default_ram_mb(),
+            ram_gb: None,
             selected_version: GameVersion::default(),
             shader_quality: ShaderQuality::default(),
+            optimized_jvm_flags: false,
+            garbage_collector: GarbageCollector::default(),
+            quick_play: QuickPlay::default(),
+            active_tab: Tab::default(),
+            sync_shaderpacks: true,
+            sync_resourcepacks: true,
+            mods_sync_policy: default_mirror_policy(),
+            shaderpacks_sync_policy: default_mirror_policy(),
+            resourcepacks_sync_policy: SyncPolicy::default(),
+            shaderpack: None,
+            prefetch_content: false,
+            fast_launch: false,
+            glow_intensity: default_glow_intensity(),
+            proxy_url: String::new(),
+            content_source_url: String::new(),
+            fabric_loader_override: None,
+            notifications_enabled: false,
+            notification_sound: true,
+            last_seen_version: None,
+            crash_dialog_enabled: true,
+            use_system_java: false,
+            env_vars: Vec::new(),
+            dismissed_data_dir: None,
+            connect_timeout_secs: default_connect_timeout_secs(),
+            download_timeout_secs: default_download_timeout_secs(),
+            download_limit_mb: None,
         }
     }
 }
 
+impl LauncherSettings {
+    pub fn from_json(content: &str) -> Option<Self> {
+        let mut value: serde_json::Value = serde_json::from_str(content).ok()?;
+        let stored = value.as_object_mut()?;
+        migrate_schema(stored);
+
+        let mut merged = serde_json::to_value(Self::default()).ok()?;
+        let target = merged.as_object_mut()?;
+        for (key, field) in stored.iter() {
+            let previous = target.insert(key.clone(), field.clone());
+            if serde_json::from_value::<Self>(serde_json::Value::Object(target.clone())).is_err() {
+                match previous {
+                    Some(previous) => target.insert(key.clone(), previous),
+                    None => target.remove(key),
+                };
+            }
+        }
+
+        serde_json::from_value::<Self>(merged).ok().map(Self::migrate)
+    }
+
+    pub fn migrate(mut self) -> Self {
+        if let Some(gb) = self.ram_gb.take() {
+            self.ram_mb = gb * 1024;
+        }
+        self.nickname = crate::app::utils::normalize_nickname(&self.nickname);
+        self.schema_version = SETTINGS_SCHEMA_VERSION;
+        self
+    }
+}
+
+fn migrate_schema(stored: &mut serde_json::Map<String, serde_json::Value>) {
+    let version = stored.get("schema_version").and_then(|v| v.as_u64()).unwrap_or(0);
+
+    if version < 1 {
+        if let Some(enabled) = stored.remove("shaders_enabled").and_then(|v| v.as_bool()) {
+            stored.entry("shader_quality").or_insert_with(|| {
+                serde_json::Value::from(if enabled { "On" } else { "Off" })
+            });
+        }
+    }
+
+    if version < 2 {
+        if let Some(quality) = stored.get("shader_quality").and_then(|v| v.as_str()) {
+            if quality != "Off" && quality != "On" {
+                stored.insert("shader_quality".into(), serde_json::Value::from("On"));
+            }
+        }
+        if let Some(ram_mb) = stored.get("ram_mb").and_then(|v| v.as_f64()) {
+            stored.insert("ram_mb".into(), serde_json::Value::from(ram_mb.max(0.0) as u32));
+        }
+        if let Some(ram_gb) = stored.get("ram_gb").and_then(|v| v.as_f64()) {
+            stored.remove("ram_gb");
+            stored.insert("ram_mb".into(), serde_json::Value::from((ram_gb.max(0.0) * 1024.0) as u32));
+        }
+    }
+
+    if version < 3 && stored.get("shader_quality").and_then(|v| v.as_str()) == Some("On") {
+        stored.insert("shader_quality".into(), serde_json::Value::from("High"));
+    }
+}
+
+fn default_ram_mb() -> u32 {
+    4096
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_glow_intensity() -> f32 {
+    1.0
+}
+
+fn default_mirror_policy() -> SyncPolicy {
+    SyncPolicy::Mirror
+}
+
+fn default_connect_timeout_secs() -> u32 {
+    DEFAULT_CONNECT_TIMEOUT_SECS
+}
+
+fn default_download_timeout_secs() -> u32 {
+    DEFAULT_DOWNLOAD_TIMEOUT_SECS
+}
+
+fn deserialize_tab<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Tab, D::Error> {
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).unwrap_or_default())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PlayTimeStats {
     pub daily: HashMap<String, u64>,
     pub total_seconds: u64,
+    #[serde(default)]
+    pub last_launch: Option<LastLaunch>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastLaunch {
+    pub timestamp: i64,
+    pub version: GameVersion,
+    pub loader_version: String,
+}
+
+impl PlayTimeStats {
+    pub fn today() -> NaiveDate {
+        Self::local_date(&chrono::Local::now())
+    }
+
+    pub fn local_date<Tz: TimeZone>(time: &DateTime<Tz>) -> NaiveDate {
+        time.date_naive()
+    }
+
+    pub fn record_at<Tz: TimeZone>(&mut self, time: &DateTime<Tz>, seconds: u64) {
+        self.record(Self::local_date(time), seconds);
+    }
+
+    fn day_key(date: NaiveDate) -> String {
+        date.format("%Y-%m-%d").to_string()
+    }
+
+    pub fn record(&mut self, date: NaiveDate, seconds: u64) {
+        *self.daily.entry(Self::day_key(date)).or_insert(0) += seconds;
+        self.total_seconds += seconds;
+    }
+
+    pub fn seconds_on(&self, date: NaiveDate) -> u64 {
+        self.daily.get(&Self::day_key(date)).copied().unwrap_or(0)
+    }
+
+    pub fn seconds_in_last_days(&self, today: NaiveDate, days: u64) -> u64 {
+        (0..days)
+            .filter_map(|days_ago| today.checked_sub_days(Days::new(days_ago)))
+            .map(|date| self.seconds_on(date))
+            .sum()
+    }
+
+    pub fn seconds_in_month(&self, today: NaiveDate) -> u64 {
+        self.daily.iter()
+            .filter_map(|(key, &secs)| {
+                let date = NaiveDate::parse_from_str(key, "%Y-%m-%d").ok()?;
+                (date.year() == today.year() && date.month() == today.month()).then_some(secs)
+            })
+            .sum()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum LaunchState {
     CheckingUpdate,
-    UpdateAvailable { version: String, download_url: String },
+    UpdateAvailable(UpdateInfo),
     Updating { progress: String },
+    UpdateReady,
     Idle,
     Installing { step: String, progress: f32 },
     Launching,
     Playing,
     Error(String),
+    NetworkError { message: String, can_play_offline: bool },
 }
 
 impl PartialEq for LaunchState {
@@ -77,10 +323,78 @@ pub struct ServerStatus {
     pub players_online: u32,
     pub players_max: u32,
     pub player_names: Vec<String>,
+    pub version_name: Option<String>,
+    pub latency_ms: Option<u32>,
+    pub enforces_secure_chat: bool,
+}
+
+impl ServerStatus {
+    pub fn requires_online_mode(&self) -> bool {
+        self.online && self.enforces_secure_chat
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrefetchState {
+    Pending,
+    Ready(GameVersion),
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemoryWarning {
+    pub available_mb: u32,
+    pub suggested_mb: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiscordLink {
+    Connecting,
+    Connected,
+    Missing,
+    Unavailable(String),
 }
 
 #[derive(Debug, Clone, PartialEq)]
+pub enum JavaStatus {
+    Ready(PathBuf),
+    Missing,
+    Installing { step: String },
+    Failed(String),
+}
+
+impl JavaStatus {
+    pub fn for_version(version: GameVersion, use_system_java: bool) -> Self {
+        match find_java(&get_game_directory(), version, use_system_java) {
+            Ok(path) => JavaStatus::Ready(path),
+            Err(_) => JavaStatus::Missing,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ResourcepackEntry {
+    pub name: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShaderpackChoice {
+    Disabled,
+    Auto,
+    Pack(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum AccountMode {
+    #[default]
+    Offline,
+    Online,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub enum Tab {
+    #[default]
     Dashboard,
     Statistics,
     Settings,
@@ -89,57 +403,344 @@ pub enum Tab {
 #[derive(Debug, Clone)]
 pub enum Message {
     NicknameChanged(String),
+    AccountModeToggled,
     RamChanged(u32),
     VersionChanged(GameVersion),
     ShaderQualityChanged(ShaderQuality),
+    OptimizedJvmFlagsToggled(bool),
+    GarbageCollectorChanged(GarbageCollector),
+    UseSystemJavaToggled(bool),
+    AddEnvVar,
+    RemoveEnvVar(usize),
+    EnvVarNameChanged(usize, String),
+    EnvVarValueChanged(usize, String),
+    QuickPlayToggled(bool),
+    QuickPlaySelected(QuickPlay),
+    SyncShaderpacksToggled(bool),
+    SyncResourcepacksToggled(bool),
+    SyncPolicyChanged(DownloadKind, SyncPolicy),
+    ShaderpackSelected(ShaderpackChoice),
+    PrefetchContentToggled(bool),
+    FastLaunchToggled(bool),
+    NotificationsToggled(bool),
+    NotificationSoundToggled(bool),
+    ExportSettings,
+    SettingsExported(Result<bool, String>),
+    ImportSettings,
+    SettingsImported(Result<Option<Box<(LauncherSettings, PlayTimeStats)>>, String>),
+    GlowIntensityChanged(f32),
+    PrefetchComplete(GameVersion, Result<(), String>),
+    ResourcepackToggled(String, bool),
+    InstallJava,
+    JavaInstallProgress(String),
+    JavaInstalled(Result<(), String>),
+    ResourcepackMoved(String, bool),
+    ProxyUrlChanged(String),
+    CheckProxy,
+    ProxyChecked(Result<(), String>),
+    ContentSourceUrlChanged(String),
+    FabricLoaderInputChanged(String),
+    ApplyFabricLoader,
+    FabricLoaderValidated(Result<String, String>),
+    ResetFabricLoader,
+    ConnectTimeoutChanged(u32),
+    DownloadLimitChanged(String),
+    DownloadTimeoutChanged(u32),
     LaunchGame,
+    CustomServerChanged(String),
+    ConnectToCustomServer,
+    LaunchIgnoringMemory,
+    LowerRamAndLaunch,
+    DismissMemoryWarning,
     SwitchTab(Tab),
     InstallProgress(String, f32),
+    GameLaunching,
     LaunchComplete(Result<(), String>),
+    LaunchNetworkError(String, bool),
+    LaunchOffline,
+    ContentChangedInSession(Vec<DownloadKind>),
+    DismissContentChanged,
     GameExited,
     GameCrashed,
     NextFrame,
     CheckUpdate,
     UpdateStatus(UpdateResult),
     PlayTimeTick,
+    DiscordRetryTick,
+    DiscordConnectAttempted(DiscordLink),
     ServerStatusUpdate(ServerStatus),
     AcceptUpdate,
     DeclineUpdate,
+    ApplyUpdate,
     ReinstallGame,
     DismissCrashDialog,
+    LegacyDataDirFound(Option<(PathBuf, u64)>),
+    MigrateDataDir,
+    DataMigrationProgress(f32),
+    DataMigrated(Result<(), String>),
+    DismissDataMigration,
+    DisableCrashDialog,
+    CrashDialogToggled(bool),
+    ServerRejected(String),
+    DismissKickDialog,
     ToggleChangelog,
     CopyCrashLog,
+    FocusRequested,
+    ToggleDiagnostics,
+    ToggleLogFiles,
+    LogFilesListed(Vec<LogFile>),
+    CleanOldLogs,
+    ConfirmCleanOldLogs,
+    CancelCleanOldLogs,
+    OldLogsCleaned(usize, u64),
+    DiagnosticsCollected(String),
+    CopyDiagnostics,
     GameCrashedWithLog(String),
+    ModsScanned(Vec<ModInfo>),
+    DuplicateModsFound(Vec<DuplicateMod>),
+    RemoveDuplicateMods,
+    DuplicateModsRemoved(Result<usize, String>),
+    DismissDuplicateMods,
+    ShaderpackMissing(Option<String>),
+    ServerListUpdateFailed(String),
+    DismissShaderNotice,
+    ContentVersionsComputed(ContentVersions),
+    RuntimeArchDetected(bool),
+    ToggleModList,
+    TogglePlayerList,
+    PlayerHeadLoaded(String, Option<Vec<u8>>),
+    CloseRequested(window::Id),
+    KillGame,
+    CancelLaunch,
+    RepairFabric,
+    RepairProgress(String, f32),
+    RepairComplete(Result<(), String>),
+    VerifyFiles,
+    VerifyProgress(String, f32),
+    VerifyComplete(Result<VerifyReport, String>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsBackup {
+    pub launcher_version: String,
+    pub settings: serde_json::Value,
+    #[serde(default)]
+    pub playtime: PlayTimeStats,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataMigration {
+    Available { from: PathBuf, size: u64 },
+    Moving { from: PathBuf, progress: f32 },
+    Failed(String),
+    Done,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameStats {
+    pub frames: usize,
+    pub decoded: usize,
+    pub width: u32,
+    pub height: u32,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub download_url: String,
+    pub size: u64,
+    pub sha256: Option<String>,
+    pub mandatory: bool,
 }
 
 #[derive(Debug, Clone)]
 pub enum UpdateResult {
     NoUpdate,
-    UpdateAvailable(String, String),
+    UpdateAvailable(UpdateInfo),
     Downloading(String),
     Downloaded(PathBuf),
+    VerificationFailed(String),
     Error(String),
 }
 
 pub struct MinecraftLauncher {
     pub nickname: String,
-    pub ram_gb: u32,
+    pub account_mode: AccountMode,
+    pub ram_mb: u32,
     pub selected_version: GameVersion,
     pub shader_quality: ShaderQuality,
+    pub optimized_jvm_flags: bool,
+    pub use_system_java: bool,
+    pub env_vars: Vec<(String, String)>,
+    pub garbage_collector: GarbageCollector,
+    pub quick_play: QuickPlay,
+    pub available_worlds: Vec<String>,
+    pub sync_shaderpacks: bool,
+    pub sync_resourcepacks: bool,
+    pub mods_sync_policy: SyncPolicy,
+    pub shaderpacks_sync_policy: SyncPolicy,
+    pub resourcepacks_sync_policy: SyncPolicy,
+    pub shaderpack: Option<String>,
+    pub available_shaderpacks: Vec<String>,
+    pub resourcepacks: Vec<ResourcepackEntry>,
+    pub java_status: JavaStatus,
+    pub prefetch_content: bool,
+    pub prefetch: PrefetchState,
+    pub fast_launch: bool,
+    pub content_outdated: bool,
+    pub content_changed: Option<Vec<DownloadKind>>,
+    pub glow_intensity: f32,
+    pub proxy_url: String,
+    pub proxy_check: Option<Result<(), String>>,
+    pub proxy_checking: bool,
+    pub content_source_url: String,
+    pub fabric_loader_override: Option<String>,
+    pub fabric_loader_input: String,
+    pub fabric_loader_checking: bool,
+    pub fabric_loader_error: Option<String>,
+    pub connect_timeout_secs: u32,
+    pub download_timeout_secs: u32,
+    pub download_limit_mb: Option<u32>,
+    pub download_limit_input: String,
+    pub notifications_enabled: bool,
+    pub notification_sound: bool,
     pub launch_state: LaunchState,
     pub active_tab: Tab,
     pub game_running: Arc<AtomicBool>,
+    pub game_process: Arc<Mutex<Option<Child>>>,
+    pub kill_requested: bool,
+    pub offline_launch: bool,
+    pub custom_server: String,
+    pub launch_server: Option<String>,
+    pub repairing: bool,
+    pub verifying: bool,
+    pub verify_report: Option<Result<VerifyReport, String>>,
     pub gif_frames: Vec<image::Handle>,
     pub avatar_frames: Vec<image::Handle>,
+    pub animation_stats: [FrameStats; 2],
     pub current_frame: usize,
     pub update_checked: bool,
     pub play_stats: PlayTimeStats,
     pub current_session_seconds: u64,
     pub discord_client: Arc<Mutex<Option<DiscordIpcClient>>>,
+    pub discord_link: DiscordLink,
+    pub discord_failures: u32,
+    pub discord_retry_at: Option<std::time::Instant>,
     pub game_start_time: Option<i64>,
     pub server_status: ServerStatus,
     pub crash_count: u32,
     pub show_crash_dialog: bool,
+    pub crash_dialog_enabled: bool,
     pub show_changelog: bool,
+    pub new_changelog_entries: usize,
+    pub last_seen_version: Option<String>,
     pub crash_log: Option<String>,
+    pub kick_reason: Option<String>,
+    pub pending_update: Option<PathBuf>,
+    pub update_download: Option<task::Handle>,
+    pub mandatory_update: Option<UpdateInfo>,
+    pub config_warning: Option<String>,
+    pub memory_warning: Option<MemoryWarning>,
+    pub data_migration: Option<DataMigration>,
+    pub dismissed_data_dir: Option<String>,
+    pub backup_status: Option<Result<String, String>>,
+    pub installed_mods: Vec<ModInfo>,
+    pub duplicate_mods: Vec<DuplicateMod>,
+    pub duplicate_mods_error: Option<String>,
+    pub shader_notice: Option<String>,
+    pub content_versions: ContentVersions,
+    pub show_mod_list: bool,
+    pub show_diagnostics: bool,
+    pub runtime_32bit: bool,
+    pub diagnostics: Option<String>,
+    pub show_log_files: bool,
+    pub log_files: Option<Vec<LogFile>>,
+    pub confirm_log_cleanup: bool,
+    pub log_cleanup_status: Option<String>,
+    pub show_player_list: bool,
+    pub player_heads: HashMap<String, Option<image::Handle>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, FixedOffset};
+    use chrono_tz::Europe::Berlin;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn v1_settings_keep_nickname_and_ram() {
+        let v1 = r#"{
+            "nickname": "Steve",
+            "ram_gb": 6.0,
+            "shaders_enabled": true
+        }"#;
+
+        let settings = LauncherSettings::from_json(v1).unwrap();
+
+        assert_eq!(settings.nickname, "Steve");
+        assert_eq!(settings.ram_mb, 6 * 1024);
+        assert_eq!(settings.ram_gb, None);
+        assert_eq!(settings.shader_quality, ShaderQuality::High);
+        assert_eq!(settings.selected_version, GameVersion::default());
+        assert_eq!(settings.schema_version, SETTINGS_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn v1_settings_with_fractional_ram_round_down_to_megabytes() {
+        let settings = LauncherSettings::from_json(r#"{"nickname": "Alex", "ram_gb": 2.5}"#).unwrap();
+
+        assert_eq!(settings.nickname, "Alex");
+        assert_eq!(settings.ram_mb, 2560);
+    }
+
+    #[test]
+    fn session_across_midnight_is_split_between_days() {
+        let moscow = FixedOffset::east_opt(3 * 3600).unwrap();
+        let start = moscow.with_ymd_and_hms(2025, 12, 31, 23, 59, 0).unwrap();
+        let mut stats = PlayTimeStats::default();
+
+        for tick in 0..120 {
+            stats.record_at(&(start + Duration::seconds(tick)), 1);
+        }
+
+        assert_eq!(stats.seconds_on(date(2025, 12, 31)), 60);
+        assert_eq!(stats.seconds_on(date(2026, 1, 1)), 60);
+        assert_eq!(stats.total_seconds, 120);
+        assert_eq!(stats.seconds_in_month(date(2026, 1, 1)), 60);
+        assert_eq!(stats.seconds_in_last_days(date(2026, 1, 1), 7), 120);
+    }
+
+    #[test]
+    fn weekly_sum_spans_spring_dst_change() {
+        let mut stats = PlayTimeStats::default();
+        for day in 26..=31 {
+            let noon = Berlin.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap();
+            stats.record_at(&noon, 3600);
+        }
+        let after_change = Berlin.with_ymd_and_hms(2026, 4, 1, 0, 30, 0).unwrap();
+        stats.record_at(&after_change, 3600);
+
+        assert_eq!(stats.seconds_in_last_days(date(2026, 4, 1), 7), 7 * 3600);
+        assert_eq!(stats.seconds_in_last_days(date(2026, 3, 31), 7), 6 * 3600);
+    }
+
+    #[test]
+    fn long_autumn_dst_day_keeps_all_ticks_on_one_date() {
+        let start = Berlin.with_ymd_and_hms(2026, 10, 25, 0, 0, 0).unwrap();
+        let mut stats = PlayTimeStats::default();
+
+        let mut time = start;
+        while PlayTimeStats::local_date(&time) == date(2026, 10, 25) {
+            stats.record_at(&time, 60);
+            time += Duration::minutes(1);
+        }
+
+        assert_eq!(stats.seconds_on(date(2026, 10, 25)), 25 * 3600);
+        assert_eq!(stats.seconds_on(date(2026, 10, 26)), 0);
+    }
 }
@@ -1,4 +1,4 @@
-use iced::{Border, Color, Theme, widget::{slider, text_input}};
+use iced::{Border, Color, Shadow, Theme, Vector, widget::{checkbox, pick_list, slider, text_input}};
 
 pub const ACCENT: Color = Color { r: 0.85, g: 0.15, b: 0.15, a: 1.0 };
 pub const BG_SIDEBAR: Color = Color { r: 0.05, g: 0.05, b: 0.07, a: 0.98 };
@@ -40,3 +40,54 @@ pub fn slider_style(_: &Theme, _: slider::Status) -> slider::Style {
         },
     }
 }
+
+pub fn checkbox_style(_: &Theme, status: checkbox::Status) -> checkbox::Style {
+    let (is_checked, hovered) = match status {
+        checkbox::Status::Active { is_checked } => (is_checked, false),
+        checkbox::Status::Hovered { is_checked } => (is_checked, true),
+        checkbox::Status::Disabled { is_checked } => (is_checked, false),
+    };
+    checkbox::Style {
+        background: iced::Background::Color(
+            if is_checked { ACCENT } else { Color { r: 0.0, g: 0.0, b: 0.0, a: 0.3 } }
+        ),
+        icon_color: Color::WHITE,
+        border: Border {
+            radius: 4.0.into(),
+            width: 1.0,
+            color: if is_checked || hovered { ACCENT } else { Color { r: 1.0, g: 1.0, b: 1.0, a: 0.15 } },
+        },
+        text_color: Some(TEXT_PRIMARY),
+    }
+}
+
+pub fn pick_list_style(_theme: &iced::Theme, _status: pick_list::Status) -> pick_list::Style {
+    pick_list::Style {
+        text_color: TEXT_PRIMARY,
+        placeholder_color: TEXT_SECONDARY,
+        handle_color: TEXT_SECONDARY,
+        background: iced::Background::Color(Color { r: 0.08, g: 0.08, b: 0.1, a: 0.95 }),
+        border: Border { radius: 8.0.into(), width: 0.5, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.15 } },
+    }
+}
+
+pub fn menu_style(_theme: &iced::Theme) -> iced::overlay::menu::Style {
+    iced::overlay::menu::Style {
+        text_color: TEXT_PRIMARY,
+        background: iced::Background::Color(Color { r: 0.08, g: 0.08, b: 0.1, a: 0.98 }),
+        border: Border { radius: 8.0.into(), width: 0.5, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.15 } },
+        selected_text_color: Color::WHITE,
+        selected_background: iced::Background::Color(ACCENT),
+    }
+}
+
+pub fn glow(color: Color, blur_radius: f32, intensity: f32) -> Shadow {
+    if intensity <= 0.0 {
+        return Shadow::default();
+    }
+    Shadow {
+        color: Color { a: color.a * intensity, ..color },
+        offset: Vector::new(0.0, 0.0),
+        blur_radius: blur_radius * intensity,
+    }
+}
@@ -1,15 +1,26 @@
-use iced::{Subscription, time};
+use iced::{Subscription, time, window};
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
-use crate::app::state::{Message, MinecraftLauncher, SERVER_ADDRESS};
-use crate::app::utils::fetch_server_status;
-use crate::minecraft::{MinecraftInstaller, get_versioned_game_directory, build_launch_command, configure_shaders};
+use crate::app::state::{JavaStatus, LaunchState, Message, MinecraftLauncher, PrefetchState, SERVER_ADDRESS};
+use crate::app::instance::focus_requests;
+use crate::app::utils::{fetch_server_status, mod_dirs, resolve_server_address};
+use crate::minecraft::{GameVersion, InstallStep, QuickPlay, list_log_files, InstallerConfig, MinecraftInstaller, ShaderQuality, get_versioned_game_directory, build_launch_command, configure_shaders, create_servers_dat, LaunchOptions, find_duplicate_mods, is_folder_access_error, is_network_error, is_rate_limited};
+
+const KICK_CHECK_WINDOW: std::time::Duration = std::time::Duration::from_secs(180);
+const CONTENT_SUMMARY_LINGER: Duration = Duration::from_secs(3);
+const CRASH_LOG_MAX_BYTES: usize = 5000;
+const LATEST_LOG_MAX_LINES: usize = 100;
+const GAME_WINDOW_TIMEOUT: Duration = Duration::from_secs(90);
+const GAME_WINDOW_LOG_MARKER: &str = "Backend library:";
 
 impl MinecraftLauncher {
     pub fn subscription(&self) -> Subscription<Message> {
         let gif_timer = time::every(Duration::from_millis(50)).map(|_| Message::NextFrame);
         let play_timer = time::every(Duration::from_secs(1)).map(|_| Message::PlayTimeTick);
+        let close_requests = window::close_requests().map(Message::CloseRequested);
+        let instance_focus = focus_requests();
+        let discord_retry = time::every(Duration::from_secs(5)).map(|_| Message::DiscordRetryTick);
         let server_status_timer = Subscription::run_with_id(
             "server-status",
             iced::stream::channel(10, |mut output| async move {
@@ -24,16 +35,33 @@ impl MinecraftLauncher {
         
         if self.game_running.load(Ordering::SeqCst) {
             let nickname = self.nickname.clone();
-            let ram_gb = self.ram_gb;
             let selected_version = self.selected_version;
             let shader_quality = self.shader_quality;
+            let launch_options = LaunchOptions {
+                ram_mb: self.ram_mb,
+                optimized_flags: self.optimized_jvm_flags,
+                gc: self.garbage_collector,
+                quick_play: self.quick_play.clone(),
+                env_vars: self.env_vars.clone(),
+                loader_version: self.fabric_loader_version(selected_version),
+                use_system_java: self.use_system_java,
+            };
+            let launch_server = self.launch_server.clone();
+            let sync_shaderpacks = self.sync_shaderpacks;
+            let sync_resourcepacks = self.sync_resourcepacks;
+            let game_process = self.game_process.clone();
+            let offline_launch = self.offline_launch;
+            let shaderpack = self.shaderpack.clone();
+            let prefetched = self.prefetch == PrefetchState::Ready(selected_version);
+            let fast_launch = self.fast_launch && !self.content_outdated;
+            let installer_config = self.installer_config(selected_version);
             
             let game_sub = Subscription::run_with_id(
                 "game-launcher",
                 iced::stream::channel(100, move |mut output| async move {
                     use iced::futures::SinkExt;
                     
-                    let _ = output.send(Message::InstallProgress("Подготовка...".into(), 0.05)).await;
+                    let _ = output.send(Message::InstallProgress(InstallStep::Preparing.to_string(), 0.05)).await;
                     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
                     
                     let game_dir = get_versioned_game_directory(selected_version);
@@ -42,14 +70,24 @@ impl MinecraftLauncher {
                         return;
                     }
                     
-                    let installer = MinecraftInstaller::new(game_dir.clone(), selected_version);
+                    let installer = MinecraftInstaller::with_config(game_dir.clone(), selected_version, installer_config.clone());
                     
-                    let _ = output.send(Message::InstallProgress("Проверка установки...".into(), 0.05)).await;
+                    let _ = output.send(Message::InstallProgress(InstallStep::CheckingInstall.to_string(), 0.05)).await;
                     
                     let is_installed = installer.is_installed().await;
                     
+                    if offline_launch && !is_installed {
+                        let _ = output.send(Message::LaunchComplete(Err("Игра не установлена, офлайн-запуск невозможен".into()))).await;
+                        return;
+                    }
+                    
                     if !is_installed {
-                        let _ = output.send(Message::InstallProgress(format!("Установка {}...", selected_version.display_name()), 0.08)).await;
+                        let install_step = if installer.stale_fabric_versions().is_empty() {
+                            InstallStep::Installing(selected_version)
+                        } else {
+                            InstallStep::UpdatingLoader(installer_config.loader_version.clone())
+                        };
+                        let _ = output.send(Message::InstallProgress(install_step.to_string(), 0.08)).await;
                         
                         let progress_sender = Arc::new(tokio::sync::Mutex::new(output.clone()));
                         let progress_sender_clone = progress_sender.clone();
@@ -66,101 +104,187 @@ impl MinecraftLauncher {
                         
                         match installer_with_progress.install_simple().await {
                             Ok(()) => {
-                                let _ = output.send(Message::InstallProgress("Установка завершена!".into(), 0.85)).await;
+                                let _ = output.send(Message::InstallProgress(InstallStep::Complete.to_string(), 0.85)).await;
                             }
                             Err(e) => {
-                                let _ = output.send(Message::LaunchComplete(Err(e.to_string()))).await;
+                                if is_network_error(&e) {
+                                    let _ = output.send(Message::LaunchNetworkError(e.to_string(), false)).await;
+                                } else {
+                                    let _ = output.send(Message::LaunchComplete(Err(e.to_string()))).await;
+                                }
                                 return;
                             }
                         }
+                    } else if offline_launch {
+                        let _ = output.send(Message::InstallProgress("Офлайн-запуск, синхронизация пропущена".into(), 0.90)).await;
                     } else {
                         let _ = output.send(Message::InstallProgress("Игра установлена".into(), 0.80)).await;
                     }
                     
-                    let _ = output.send(Message::InstallProgress("Проверка модов...".into(), 0.80)).await;
-                    
-                    let progress_sender = Arc::new(tokio::sync::Mutex::new(output.clone()));
-                    let progress_sender_clone = progress_sender.clone();
+                    if is_installed && !offline_launch {
+                        match MinecraftInstaller::with_config(game_dir.clone(), selected_version, installer_config.clone()).apply_staged_content() {
+                            Ok(applied) if !applied.is_empty() => {
+                                let _ = output.send(Message::InstallProgress("Применены обновления, скачанные в прошлой сессии".into(), 0.80)).await;
+                            }
+                            Ok(_) => {}
+                            Err(e) => eprintln!("Failed to apply staged content: {}", e),
+                        }
+                    }
                     
-                    let installer_for_mods = MinecraftInstaller::new(game_dir.clone(), selected_version)
-                        .with_progress(move |msg, progress| {
-                            let sender = progress_sender_clone.clone();
-                            let message = msg.to_string();
-                            tokio::spawn(async move {
-                                use iced::futures::SinkExt;
-                                let mut sender = sender.lock().await;
-                                let _ = sender.send(Message::InstallProgress(message, progress)).await;
-                            });
-                        });
+                    let background_sync = fast_launch && is_installed && !offline_launch && !prefetched;
                     
-                    if let Err(e) = installer_for_mods.download_mods().await {
-                        let _ = output.send(Message::InstallProgress(format!("Моды: {}", e), 0.85)).await;
+                    if prefetched && !offline_launch {
+                        let _ = output.send(Message::InstallProgress("Файлы подготовлены заранее".into(), 0.90)).await;
+                    } else if background_sync {
+                        let _ = output.send(Message::InstallProgress("Быстрый запуск, обновления скачаются в фоне".into(), 0.90)).await;
                     }
                     
-                    let _ = output.send(Message::InstallProgress("Проверка шейдеров...".into(), 0.86)).await;
+                    if !offline_launch && !prefetched && !background_sync {
+                        let _ = output.send(Message::InstallProgress(InstallStep::SyncingContent.to_string(), 0.80)).await;
                     
-                    let progress_sender_clone2 = progress_sender.clone();
-                    let installer_for_shaders = MinecraftInstaller::new(game_dir.clone(), selected_version)
-                        .with_progress(move |msg, progress| {
-                            let sender = progress_sender_clone2.clone();
-                            let message = msg.to_string();
-                            tokio::spawn(async move {
-                                use iced::futures::SinkExt;
-                                let mut sender = sender.lock().await;
-                                let _ = sender.send(Message::InstallProgress(message, progress)).await;
+                        let progress_sender = Arc::new(tokio::sync::Mutex::new(output.clone()));
+                        let progress_sender_clone = progress_sender.clone();
+                    
+                        let installer_for_mods = MinecraftInstaller::with_config(game_dir.clone(), selected_version, installer_config.clone())
+                            .with_progress(move |msg, progress| {
+                                let sender = progress_sender_clone.clone();
+                                let message = msg.to_string();
+                                tokio::spawn(async move {
+                                    use iced::futures::SinkExt;
+                                    let mut sender = sender.lock().await;
+                                    let _ = sender.send(Message::InstallProgress(message, progress)).await;
+                                });
                             });
-                        });
+                        let installer_for_packs = MinecraftInstaller::with_config(game_dir.clone(), selected_version, installer_config.clone());
                     
-                    if let Err(e) = installer_for_shaders.download_shaderpacks(shader_quality).await {
-                        let _ = output.send(Message::InstallProgress(format!("Шейдеры: {}", e), 0.88)).await;
-                    }
+                        let (mods_result, shaders_result, resources_result) = tokio::join!(
+                            installer_for_mods.download_mods(),
+                            async {
+                                if sync_shaderpacks {
+                                    Some(installer_for_packs.download_shaderpacks(shader_quality).await)
+                                } else {
+                                    None
+                                }
+                            },
+                            async {
+                                if sync_resourcepacks {
+                                    Some(installer_for_packs.download_resourcepacks().await)
+                                } else {
+                                    None
+                                }
+                            },
+                        );
                     
-                    let _ = output.send(Message::InstallProgress("Проверка текстурпаков...".into(), 0.90)).await;
+                        if let Err(e) = &mods_result {
+                            if is_network_error(e) {
+                                let _ = output.send(Message::LaunchNetworkError(e.to_string(), true)).await;
+                                return;
+                            }
+                            if is_folder_access_error(e) {
+                                let _ = output.send(Message::LaunchComplete(Err(e.to_string()))).await;
+                                return;
+                            }
+                        }
                     
-                    let progress_sender_clone3 = progress_sender.clone();
-                    let installer_for_resources = MinecraftInstaller::new(game_dir.clone(), selected_version)
-                        .with_progress(move |msg, progress| {
-                            let sender = progress_sender_clone3.clone();
-                            let message = msg.to_string();
-                            tokio::spawn(async move {
-                                use iced::futures::SinkExt;
-                                let mut sender = sender.lock().await;
-                                let _ = sender.send(Message::InstallProgress(message, progress)).await;
-                            });
-                        });
+                        let results = [
+                            ("моды", Some(mods_result)),
+                            ("шейдеры", shaders_result),
+                            ("текстуры", resources_result),
+                        ];
+                        let any_failed = results.iter().any(|(_, result)| matches!(result, Some(Err(_))));
+                        let _ = output.send(Message::InstallProgress(content_sync_summary(&results), 0.94)).await;
+                        tokio::time::sleep(if any_failed { CONTENT_SUMMARY_LINGER } else { Duration::from_millis(500) }).await;
+                    }
                     
-                    if let Err(e) = installer_for_resources.download_resourcepacks().await {
-                        let _ = output.send(Message::InstallProgress(format!("Текстуры: {}", e), 0.92)).await;
+                    let duplicates = find_duplicate_mods(&mod_dirs(selected_version));
+                    if !duplicates.is_empty() {
+                        let names = duplicates.iter().map(|d| d.name.clone()).collect::<Vec<_>>().join(", ");
+                        let _ = output.send(Message::DuplicateModsFound(duplicates)).await;
+                        let _ = output.send(Message::LaunchComplete(Err(format!("Найдены дубликаты модов ({}), Fabric не запустится. Удалите старые версии", names)))).await;
+                        return;
                     }
                     
-                    let _ = output.send(Message::InstallProgress("Настройка шейдеров...".into(), 0.94)).await;
-                    let _ = configure_shaders(&game_dir, shader_quality, selected_version);
+                    let _ = output.send(Message::InstallProgress(InstallStep::ConfiguringShaders.to_string(), 0.94)).await;
+                    if let Ok(true) = configure_shaders(&game_dir, shader_quality, shaderpack.as_deref(), selected_version) {
+                        let _ = output.send(Message::ShaderpackMissing(shaderpack.clone())).await;
+                    }
                     
                     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                    let _ = output.send(Message::InstallProgress("Запуск игры...".into(), 0.96)).await;
+                    let _ = output.send(Message::InstallProgress(InstallStep::Launching.to_string(), 0.96)).await;
+                    
+                    let target_server = launch_server.as_deref().unwrap_or(SERVER_ADDRESS);
+                    let server_address = if offline_launch {
+                        target_server.to_string()
+                    } else {
+                        let (server_host, server_port) = resolve_server_address(target_server).await;
+                        format!("{}:{}", server_host, server_port)
+                    };
+                    if launch_server.is_none() {
+                        if let Err(e) = create_servers_dat(&game_dir, &server_address) {
+                            let _ = output.send(Message::ServerListUpdateFailed(format!("{:#}", e))).await;
+                        }
+                    }
                     
-                    let cmd_result = build_launch_command(&game_dir, &nickname, ram_gb, Some(SERVER_ADDRESS), selected_version);
+                    let cmd_result = match &launch_server {
+                        Some(_) => build_launch_command(&game_dir, &nickname, Some(&server_address), selected_version, &LaunchOptions { quick_play: QuickPlay::Server, ..launch_options.clone() }),
+                        None => build_launch_command(&game_dir, &nickname, Some(&server_address), selected_version, &launch_options),
+                    };
                     
                     match cmd_result {
                         Ok(mut cmd) => {
+                            let launched_at = std::time::SystemTime::now();
+                            let _ = output.send(Message::GameLaunching).await;
                             match cmd.spawn() {
-                                Ok(mut child) => {
-                                    let _ = output.send(Message::InstallProgress("Игра запущена!".into(), 1.0)).await;
-                                    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                                    let _ = output.send(Message::LaunchComplete(Ok(()))).await;
+                                Ok(child) => {
+                                    if let Ok(mut guard) = game_process.lock() {
+                                        *guard = Some(child);
+                                    }
+                                    if wait_for_game_window(&game_dir, &game_process, launched_at).await {
+                                        let _ = output.send(Message::LaunchComplete(Ok(()))).await;
+                                    }
                                     
+                                    if background_sync {
+                                        let mut output = output.clone();
+                                        let game_dir = game_dir.clone();
+                                        let installer_config = installer_config.clone();
+                                        tokio::spawn(async move {
+                                            let installer = MinecraftInstaller::with_config(game_dir, selected_version, installer_config);
+                                            match installer.stage_content_updates(shader_quality, sync_shaderpacks, sync_resourcepacks).await {
+                                                Ok(staged) if !staged.is_empty() => {
+                                                    let _ = output.send(Message::ContentChangedInSession(staged)).await;
+                                                }
+                                                Ok(_) => {}
+                                                Err(e) => eprintln!("Background content download failed: {}", e),
+                                            }
+                                        });
+                                    }
+                                    
+                                    let started = std::time::Instant::now();
                                     let game_dir_clone = game_dir.clone();
-                                    let exit_status = tokio::task::spawn_blocking(move || {
-                                        child.wait()
-                                    }).await;
+                                    let exit_status = loop {
+                                        let polled = match game_process.lock() {
+                                            Ok(mut guard) => guard.as_mut().map(|child| child.try_wait()),
+                                            Err(_) => None,
+                                        };
+                                        match polled {
+                                            Some(Ok(Some(status))) => break Some(status),
+                                            Some(Ok(None)) => {
+                                                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                                            }
+                                            _ => break None,
+                                        }
+                                    };
+                                    if let Ok(mut guard) = game_process.lock() {
+                                        *guard = None;
+                                    }
                                     
                                     let crashed = match &exit_status {
-                                        Ok(Ok(status)) => !status.success(),
-                                        _ => true,
+                                        Some(status) => !status.success(),
+                                        None => true,
                                     };
                                     
                                     if crashed {
-                                        let crash_log = read_crash_log(&game_dir_clone);
+                                        let crash_log = read_crash_log(&game_dir_clone, launched_at);
                                         if let Some(log) = crash_log {
                                             let _ = output.send(Message::GameCrashedWithLog(log)).await;
                                         } else {
@@ -168,6 +292,11 @@ impl MinecraftLauncher {
                                         }
                                     } else {
                                         let _ = output.send(Message::GameExited).await;
+                                        if started.elapsed() < KICK_CHECK_WINDOW {
+                                            if let Some(reason) = read_kick_reason(&game_dir_clone, launched_at) {
+                                                let _ = output.send(Message::ServerRejected(reason)).await;
+                                            }
+                                        }
                                     }
                                 }
                                 Err(e) => {
@@ -181,40 +310,207 @@ impl MinecraftLauncher {
                     }
                 })
             );
-            Subscription::batch([gif_timer, game_sub, play_timer, server_status_timer])
+            Subscription::batch([gif_timer, game_sub, play_timer, server_status_timer, close_requests, instance_focus, discord_retry])
+        } else if self.repairing {
+            Subscription::batch([gif_timer, server_status_timer, close_requests, instance_focus, discord_retry, self.repair_subscription()])
+        } else if self.verifying {
+            Subscription::batch([gif_timer, server_status_timer, close_requests, instance_focus, discord_retry, self.verify_subscription()])
+        } else if self.should_prefetch() {
+            Subscription::batch([gif_timer, server_status_timer, close_requests, instance_focus, discord_retry, self.prefetch_subscription()])
         } else {
-            Subscription::batch([gif_timer, server_status_timer])
+            Subscription::batch([gif_timer, server_status_timer, close_requests, instance_focus, discord_retry])
         }
     }
+
+    pub fn should_prefetch(&self) -> bool {
+        self.prefetch_content
+            && self.prefetch == PrefetchState::Pending
+            && self.launch_state == LaunchState::Idle
+            && !self.nickname.is_empty()
+            && !matches!(self.java_status, JavaStatus::Installing { .. })
+    }
+
+    fn repair_subscription(&self) -> Subscription<Message> {
+        let version = self.selected_version;
+        let installer_config = self.installer_config(version);
+        
+        Subscription::run_with_id(
+            ("repair-fabric", version),
+            iced::stream::channel(100, move |mut output| async move {
+                use iced::futures::SinkExt;
+                
+                let progress_sender = Arc::new(tokio::sync::Mutex::new(output.clone()));
+                let installer = MinecraftInstaller::with_config(get_versioned_game_directory(version), version, installer_config)
+                    .with_progress(move |msg, progress| {
+                        let sender = progress_sender.clone();
+                        let message = msg.to_string();
+                        tokio::spawn(async move {
+                            use iced::futures::SinkExt;
+                            let mut sender = sender.lock().await;
+                            let _ = sender.send(Message::RepairProgress(message, progress)).await;
+                        });
+                    });
+                
+                let result = installer.repair_fabric().await.map_err(|e| e.to_string());
+                let _ = output.send(Message::RepairComplete(result)).await;
+            })
+        )
+    }
+
+    fn verify_subscription(&self) -> Subscription<Message> {
+        let version = self.selected_version;
+        let installer_config = self.installer_config(version);
+        
+        Subscription::run_with_id(
+            ("verify-files", version),
+            iced::stream::channel(100, move |mut output| async move {
+                use iced::futures::SinkExt;
+                
+                let progress_sender = Arc::new(tokio::sync::Mutex::new(output.clone()));
+                let installer = MinecraftInstaller::with_config(get_versioned_game_directory(version), version, installer_config)
+                    .with_progress(move |msg, progress| {
+                        let sender = progress_sender.clone();
+                        let message = msg.to_string();
+                        tokio::spawn(async move {
+                            use iced::futures::SinkExt;
+                            let mut sender = sender.lock().await;
+                            let _ = sender.send(Message::VerifyProgress(message, progress)).await;
+                        });
+                    });
+                
+                let result = installer.verify_files().await.map_err(|e| e.to_string());
+                let _ = output.send(Message::VerifyComplete(result)).await;
+            })
+        )
+    }
+
+    fn prefetch_subscription(&self) -> Subscription<Message> {
+        let version = self.selected_version;
+        let installer_config = self.installer_config(version);
+        let shader_quality = self.shader_quality;
+        let sync_shaderpacks = self.sync_shaderpacks;
+        let sync_resourcepacks = self.sync_resourcepacks;
+        
+        Subscription::run_with_id(
+            ("prefetch", version, shader_quality, sync_shaderpacks, sync_resourcepacks),
+            iced::stream::channel(1, move |mut output| async move {
+                use iced::futures::SinkExt;
+                let result = prefetch_content(version, installer_config, shader_quality, sync_shaderpacks, sync_resourcepacks).await;
+                let _ = output.send(Message::PrefetchComplete(version, result.map_err(|e| e.to_string()))).await;
+            })
+        )
+    }
 }
 
-fn read_crash_log(game_dir: &std::path::Path) -> Option<String> {
-    let crash_reports_dir = game_dir.join("crash-reports");
-    let mut latest_crash: Option<(std::time::SystemTime, std::path::PathBuf)> = None;
+async fn prefetch_content(version: GameVersion, installer_config: InstallerConfig, shader_quality: ShaderQuality, sync_shaderpacks: bool, sync_resourcepacks: bool) -> anyhow::Result<()> {
+    let game_dir = get_versioned_game_directory(version);
+    std::fs::create_dir_all(&game_dir)?;
     
-    if crash_reports_dir.exists() {
-        if let Ok(entries) = std::fs::read_dir(&crash_reports_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().map_or(false, |ext| ext == "txt") {
-                    if let Ok(metadata) = path.metadata() {
-                        if let Ok(modified) = metadata.modified() {
-                            if latest_crash.as_ref().map_or(true, |(t, _)| modified > *t) {
-                                latest_crash = Some((modified, path));
-                            }
-                        }
-                    }
+    let installer = MinecraftInstaller::with_config(game_dir, version, installer_config);
+    if !installer.is_installed().await {
+        installer.install_simple().await?;
+    }
+    installer.download_mods().await?;
+    if sync_shaderpacks {
+        installer.download_shaderpacks(shader_quality).await?;
+    }
+    if sync_resourcepacks {
+        installer.download_resourcepacks().await?;
+    }
+    Ok(())
+}
+
+fn content_sync_summary(results: &[(&str, Option<anyhow::Result<()>>)]) -> String {
+    results.iter()
+        .filter_map(|(label, result)| {
+            let result = result.as_ref()?;
+            Some(match result {
+                Ok(()) => format!("{} ✓", label),
+                Err(e) => format!("{} ✗ ({})", label, sync_error_label(e)),
+            })
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn sync_error_label(error: &anyhow::Error) -> String {
+    if is_rate_limited(error) {
+        "rate limit".to_string()
+    } else if is_network_error(error) {
+        "нет сети".to_string()
+    } else {
+        error.to_string().chars().take(40).collect()
+    }
+}
+
+fn read_kick_reason(game_dir: &std::path::Path, since: std::time::SystemTime) -> Option<String> {
+    let latest_log = game_dir.join("logs").join("latest.log");
+    if !modified_since(&latest_log, since) {
+        return None;
+    }
+    let content = std::fs::read_to_string(latest_log).ok()?;
+    let line = content.lines().rev().find(|line| {
+        let lower = line.to_lowercase();
+        lower.contains("disconnect") || lower.contains("kicked")
+    })?;
+    let lower = line.to_lowercase();
+    
+    let reason = if lower.contains("whitelist") {
+        "Вас нет в белом списке сервера. Обратитесь к администрации"
+    } else if lower.contains("authentication servers") || lower.contains("failed to verify username") || lower.contains("invalid session") {
+        "Сервер работает в online-mode и не пускает офлайн-аккаунты"
+    } else if lower.contains("banned") {
+        "Ваш аккаунт заблокирован на сервере"
+    } else if lower.contains("outdated") || lower.contains("incompatible") {
+        "Версия игры не совпадает с версией сервера"
+    } else {
+        return None;
+    };
+    Some(reason.to_string())
+}
+
+async fn wait_for_game_window(game_dir: &std::path::Path, game_process: &std::sync::Mutex<Option<std::process::Child>>, launched_at: std::time::SystemTime) -> bool {
+    let latest_log = game_dir.join("logs").join("latest.log");
+    let started = std::time::Instant::now();
+    
+    while started.elapsed() < GAME_WINDOW_TIMEOUT {
+        let exited = match game_process.lock() {
+            Ok(mut guard) => guard.as_mut().is_none_or(|child| !matches!(child.try_wait(), Ok(None))),
+            Err(_) => true,
+        };
+        if exited {
+            return false;
+        }
+        if modified_since(&latest_log, launched_at) {
+            if let Ok(bytes) = std::fs::read(&latest_log) {
+                if String::from_utf8_lossy(&bytes).contains(GAME_WINDOW_LOG_MARKER) {
+                    return true;
                 }
             }
         }
+        tokio::time::sleep(Duration::from_millis(500)).await;
     }
+    true
+}
+
+fn modified_since(path: &std::path::Path, since: std::time::SystemTime) -> bool {
+    path.metadata()
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| modified >= since)
+}
+
+fn read_crash_log(game_dir: &std::path::Path, since: std::time::SystemTime) -> Option<String> {
+    let latest_crash = list_log_files(game_dir)
+        .into_iter()
+        .find(|file| file.crash_report && file.modified >= since);
     
-    if let Some((_, path)) = latest_crash {
-        if let Ok(content) = std::fs::read_to_string(&path) {
-            let truncated = if content.len() > 5000 {
-                format!("{}...\n[Лог обрезан]", &content[..5000])
+    if let Some(crash) = latest_crash {
+        if let Ok(bytes) = std::fs::read(&crash.path) {
+            let content = String::from_utf8_lossy(&bytes);
+            let truncated = if content.len() > CRASH_LOG_MAX_BYTES {
+                format!("{}...\n[Лог обрезан]", head_at_char_boundary(&content, CRASH_LOG_MAX_BYTES))
             } else {
-                content
+                content.into_owned()
             };
             return Some(truncated);
         }
@@ -222,13 +518,74 @@ fn read_crash_log(game_dir: &std::path::Path) -> Option<String> {
     
     let logs_dir = game_dir.join("logs");
     let latest_log = logs_dir.join("latest.log");
-    if latest_log.exists() {
-        if let Ok(content) = std::fs::read_to_string(&latest_log) {
+    if modified_since(&latest_log, since) {
+        if let Ok(bytes) = std::fs::read(&latest_log) {
+            let content = String::from_utf8_lossy(&bytes);
             let lines: Vec<&str> = content.lines().collect();
-            let last_lines: Vec<&str> = lines.iter().rev().take(100).rev().cloned().collect();
-            return Some(last_lines.join("\n"));
+            let last_lines: Vec<&str> = lines.iter().rev().take(LATEST_LOG_MAX_LINES).rev().cloned().collect();
+            let joined = last_lines.join("\n");
+            if joined.len() > CRASH_LOG_MAX_BYTES {
+                return Some(format!("[Лог обрезан]\n...{}", tail_at_char_boundary(&joined, CRASH_LOG_MAX_BYTES)));
+            }
+            return Some(joined);
         }
     }
     
     None
 }
+
+fn head_at_char_boundary(content: &str, max_bytes: usize) -> &str {
+    let end = content.char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(content.len()))
+        .take_while(|&i| i <= max_bytes)
+        .last()
+        .unwrap_or(0);
+    &content[..end]
+}
+
+fn tail_at_char_boundary(content: &str, max_bytes: usize) -> &str {
+    let min_start = content.len().saturating_sub(max_bytes);
+    let start = content.char_indices()
+        .map(|(i, _)| i)
+        .find(|&i| i >= min_start)
+        .unwrap_or(content.len());
+    &content[start..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cyrillic_log() -> String {
+        format!("[{}]", "ж".repeat(3000))
+    }
+
+    #[test]
+    fn head_stops_before_split_cyrillic_char() {
+        let content = cyrillic_log();
+        assert!(!content.is_char_boundary(CRASH_LOG_MAX_BYTES));
+
+        let head = head_at_char_boundary(&content, CRASH_LOG_MAX_BYTES);
+        assert_eq!(head.len(), CRASH_LOG_MAX_BYTES - 1);
+        assert!(content.starts_with(head));
+        assert!(head.ends_with('ж'));
+    }
+
+    #[test]
+    fn tail_starts_after_split_cyrillic_char() {
+        let content = cyrillic_log();
+        assert!(!content.is_char_boundary(content.len() - CRASH_LOG_MAX_BYTES));
+
+        let tail = tail_at_char_boundary(&content, CRASH_LOG_MAX_BYTES);
+        assert_eq!(tail.len(), CRASH_LOG_MAX_BYTES - 1);
+        assert!(content.ends_with(tail));
+        assert!(tail.starts_with('ж'));
+    }
+
+    #[test]
+    fn short_content_is_returned_whole() {
+        assert_eq!(head_at_char_boundary("Привет", CRASH_LOG_MAX_BYTES), "Привет");
+        assert_eq!(tail_at_char_boundary("Привет", CRASH_LOG_MAX_BYTES), "Привет");
+    }
+}
@@ -1,57 +1,459 @@
 use iced::Task;
+use iced::widget::image;
+use std::sync::Arc;
 use std::sync::atomic::Ordering;
 use discord_rich_presence::{activity, DiscordIpc};
-use crate::app::state::{LaunchState, Message, MinecraftLauncher, UpdateResult};
-use crate::app::utils::{check_for_updates, download_and_run_update};
+use crate::app::state::{Tab, AccountMode, LastLaunch, DataMigration, DiscordLink, MemoryWarning, MEMORY_HEADROOM_MB, MIN_SUGGESTED_RAM_MB, DISCORD_RETRY_BASE, DISCORD_RETRY_MAX, DISCORD_RETRY_MISSING, JavaStatus, LauncherSettings, LaunchState, Message, MinecraftLauncher, PrefetchState, SettingsBackup, ShaderpackChoice, UpdateResult, CRASH_DIALOG_THRESHOLD, CURRENT_VERSION, MAX_RAM_32BIT_MB};
+use crate::app::utils::{available_memory_mb, validate_server_address, check_for_updates, clean_old_logs, connect_discord, download_and_run_update, collect_diagnostics, detect_32bit_runtime, load_duplicate_mods, remove_duplicate_mods, export_settings_backup, fetch_player_head, import_settings_backup, load_content_versions, load_installed_mods, load_log_files, load_resourcepacks, normalize_nickname, send_notification};
+use crate::minecraft::{DownloadKind, InstallStep, MinecraftInstaller, KEEP_LOG_FILES, QuickPlay, ShaderQuality, check_connectivity, detect_shaderpacks, detect_worlds, get_game_directory, get_versioned_game_directory, migrate_data_dir, set_bandwidth_limit, set_proxy_url, set_timeouts, validate_content_source_url, validate_fabric_loader_version, write_enabled_resourcepacks};
+
+const MAX_CACHED_HEADS: usize = 64;
 
 impl MinecraftLauncher {
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::NicknameChanged(nickname) => {
-                self.nickname = nickname;
+                self.nickname = normalize_nickname(&nickname);
+                self.save_settings();
+            }
+            Message::AccountModeToggled => {
+                self.account_mode = match self.account_mode {
+                    AccountMode::Offline => AccountMode::Online,
+                    AccountMode::Online => AccountMode::Offline,
+                };
                 self.save_settings();
             }
             Message::RamChanged(ram) => {
-                self.ram_gb = ram;
+                self.ram_mb = if self.runtime_32bit { ram.min(MAX_RAM_32BIT_MB) } else { ram };
                 self.save_settings();
             }
             Message::VersionChanged(version) => {
                 self.selected_version = version;
+                self.prefetch = PrefetchState::Pending;
                 self.save_settings();
+                self.refresh_content_packs();
+                if !matches!(self.java_status, JavaStatus::Installing { .. }) {
+                    self.java_status = JavaStatus::for_version(version, self.use_system_java);
+                }
+                return Task::batch([
+                    self.rescan_mods(),
+                    Task::perform(detect_32bit_runtime(version, self.use_system_java), Message::RuntimeArchDetected),
+                ]);
             }
             Message::ShaderQualityChanged(quality) => {
                 self.shader_quality = quality;
+                self.prefetch = PrefetchState::Pending;
+                self.save_settings();
+            }
+            Message::OptimizedJvmFlagsToggled(enabled) => {
+                self.optimized_jvm_flags = enabled;
+                self.save_settings();
+            }
+            Message::QuickPlayToggled(enabled) => {
+                self.quick_play = if enabled { QuickPlay::Server } else { QuickPlay::Menu };
+                self.save_settings();
+            }
+            Message::QuickPlaySelected(target) => {
+                self.quick_play = target;
+                self.save_settings();
+            }
+            Message::GarbageCollectorChanged(gc) => {
+                self.garbage_collector = gc;
+                self.save_settings();
+            }
+            Message::UseSystemJavaToggled(enabled) => {
+                self.use_system_java = enabled;
+                self.save_settings();
+                if !matches!(self.java_status, JavaStatus::Installing { .. }) {
+                    self.java_status = JavaStatus::for_version(self.selected_version, self.use_system_java);
+                }
+                return Task::perform(detect_32bit_runtime(self.selected_version, self.use_system_java), Message::RuntimeArchDetected);
+            }
+            Message::AddEnvVar => {
+                self.env_vars.push((String::new(), String::new()));
+                self.save_settings();
+            }
+            Message::RemoveEnvVar(index) => {
+                if index < self.env_vars.len() {
+                    self.env_vars.remove(index);
+                    self.save_settings();
+                }
+            }
+            Message::EnvVarNameChanged(index, name) => {
+                if let Some(entry) = self.env_vars.get_mut(index) {
+                    entry.0 = name;
+                    self.save_settings();
+                }
+            }
+            Message::EnvVarValueChanged(index, value) => {
+                if let Some(entry) = self.env_vars.get_mut(index) {
+                    entry.1 = value;
+                    self.save_settings();
+                }
+            }
+            Message::SyncShaderpacksToggled(enabled) => {
+                self.sync_shaderpacks = enabled;
+                self.prefetch = PrefetchState::Pending;
+                self.save_settings();
+            }
+            Message::SyncResourcepacksToggled(enabled) => {
+                self.sync_resourcepacks = enabled;
+                self.prefetch = PrefetchState::Pending;
+                self.save_settings();
+            }
+            Message::SyncPolicyChanged(kind, policy) => {
+                match kind {
+                    DownloadKind::Mods => self.mods_sync_policy = policy,
+                    DownloadKind::Shaderpacks => self.shaderpacks_sync_policy = policy,
+                    DownloadKind::Resourcepacks => self.resourcepacks_sync_policy = policy,
+                    DownloadKind::Game | DownloadKind::Fabric => return Task::none(),
+                }
+                self.prefetch = PrefetchState::Pending;
+                self.save_settings();
+            }
+            Message::GlowIntensityChanged(intensity) => {
+                self.glow_intensity = intensity;
+                self.save_settings();
+            }
+            Message::NotificationsToggled(enabled) => {
+                self.notifications_enabled = enabled;
+                self.save_settings();
+            }
+            Message::NotificationSoundToggled(enabled) => {
+                self.notification_sound = enabled;
+                self.save_settings();
+            }
+            Message::ExportSettings => {
+                let backup = SettingsBackup {
+                    launcher_version: CURRENT_VERSION.to_string(),
+                    settings: serde_json::to_value(self.current_settings()).unwrap_or_default(),
+                    playtime: self.play_stats.clone(),
+                };
+                self.backup_status = None;
+                return Task::perform(export_settings_backup(backup), Message::SettingsExported);
+            }
+            Message::SettingsExported(result) => {
+                self.backup_status = match result {
+                    Ok(true) => Some(Ok("Настройки экспортированы".into())),
+                    Ok(false) => None,
+                    Err(e) => Some(Err(e)),
+                };
+            }
+            Message::ImportSettings => {
+                if self.game_running.load(Ordering::SeqCst) {
+                    return Task::none();
+                }
+                self.backup_status = None;
+                return Task::perform(import_settings_backup(), Message::SettingsImported);
+            }
+            Message::SettingsImported(result) => {
+                match result {
+                    Ok(Some(backup)) => {
+                        let (settings, play_stats) = *backup;
+                        self.play_stats = play_stats;
+                        self.save_play_stats();
+                        self.backup_status = Some(Ok("Настройки восстановлены".into()));
+                        return self.apply_settings(settings);
+                    }
+                    Ok(None) => {}
+                    Err(e) => self.backup_status = Some(Err(e)),
+                }
+            }
+            Message::PrefetchContentToggled(enabled) => {
+                self.prefetch_content = enabled;
+                self.prefetch = PrefetchState::Pending;
+                self.save_settings();
+            }
+            Message::FastLaunchToggled(enabled) => {
+                self.fast_launch = enabled;
+                self.save_settings();
+            }
+            Message::PrefetchComplete(version, result) => {
+                if version == self.selected_version {
+                    self.prefetch = match result {
+                        Ok(()) => PrefetchState::Ready(version),
+                        Err(e) => {
+                            eprintln!("Prefetch failed: {}", e);
+                            PrefetchState::Failed
+                        }
+                    };
+                    self.refresh_content_packs();
+                    self.java_status = JavaStatus::for_version(version, self.use_system_java);
+                }
+            }
+            Message::ShaderpackSelected(choice) => {
+                match choice {
+                    ShaderpackChoice::Disabled => self.shader_quality = ShaderQuality::Off,
+                    ShaderpackChoice::Auto => {
+                        if self.shader_quality == ShaderQuality::Off {
+                            self.shader_quality = ShaderQuality::default();
+                        }
+                        self.shaderpack = None;
+                    }
+                    ShaderpackChoice::Pack(name) => {
+                        if self.shader_quality == ShaderQuality::Off {
+                            self.shader_quality = ShaderQuality::default();
+                        }
+                        self.shaderpack = Some(name);
+                    }
+                }
+                self.prefetch = PrefetchState::Pending;
+                self.save_settings();
+            }
+            Message::ResourcepackToggled(name, enabled) => {
+                if let Some(index) = self.resourcepacks.iter().position(|pack| pack.name == name) {
+                    let mut pack = self.resourcepacks.remove(index);
+                    pack.enabled = enabled;
+                    let enabled_count = self.resourcepacks.iter().filter(|p| p.enabled).count();
+                    self.resourcepacks.insert(enabled_count, pack);
+                    self.save_resourcepacks();
+                }
+            }
+            Message::ResourcepackMoved(name, up) => {
+                let enabled_count = self.resourcepacks.iter().filter(|p| p.enabled).count();
+                if let Some(index) = self.resourcepacks.iter().position(|pack| pack.name == name && pack.enabled) {
+                    let target = if up { index.checked_sub(1) } else { Some(index + 1).filter(|&i| i < enabled_count) };
+                    if let Some(target) = target {
+                        self.resourcepacks.swap(index, target);
+                        self.save_resourcepacks();
+                    }
+                }
+            }
+            Message::InstallJava => {
+                if matches!(self.java_status, JavaStatus::Missing | JavaStatus::Failed(_)) && !self.game_running.load(Ordering::SeqCst) {
+                    let version = self.selected_version;
+                    let installer_config = self.installer_config(version);
+                    self.java_status = JavaStatus::Installing { step: InstallStep::DownloadingJava(version.java_version()).to_string() };
+                    return Task::stream(iced::stream::channel(20, move |mut output| async move {
+                        use iced::futures::SinkExt;
+                        
+                        let progress_sender = Arc::new(tokio::sync::Mutex::new(output.clone()));
+                        let installer = MinecraftInstaller::with_config(get_versioned_game_directory(version), version, installer_config)
+                            .with_progress(move |msg, _| {
+                                let sender = progress_sender.clone();
+                                let message = msg.to_string();
+                                tokio::spawn(async move {
+                                    use iced::futures::SinkExt;
+                                    let mut sender = sender.lock().await;
+                                    let _ = sender.send(Message::JavaInstallProgress(message)).await;
+                                });
+                            });
+                        
+                        let result = installer.ensure_java().await.map_err(|e| e.to_string());
+                        let _ = output.send(Message::JavaInstalled(result)).await;
+                    }));
+                }
+            }
+            Message::JavaInstallProgress(step) => {
+                if matches!(self.java_status, JavaStatus::Installing { .. }) {
+                    self.java_status = JavaStatus::Installing { step };
+                }
+            }
+            Message::JavaInstalled(result) => {
+                self.java_status = match result {
+                    Ok(()) => JavaStatus::for_version(self.selected_version, self.use_system_java),
+                    Err(e) => JavaStatus::Failed(e),
+                };
+                return Task::perform(detect_32bit_runtime(self.selected_version, self.use_system_java), Message::RuntimeArchDetected);
+            }
+            Message::ProxyUrlChanged(url) => {
+                self.proxy_url = url;
+                self.proxy_check = None;
+                set_proxy_url(&self.proxy_url);
+                self.save_settings();
+            }
+            Message::CheckProxy => {
+                if !self.proxy_checking {
+                    self.proxy_checking = true;
+                    self.proxy_check = None;
+                    let proxy_url = self.proxy_url.clone();
+                    return Task::perform(
+                        async move { check_connectivity(&proxy_url).await.map_err(|e| e.to_string()) },
+                        Message::ProxyChecked,
+                    );
+                }
+            }
+            Message::ProxyChecked(result) => {
+                self.proxy_checking = false;
+                self.proxy_check = Some(result);
+            }
+            Message::ContentSourceUrlChanged(url) => {
+                self.content_source_url = url;
+                if validate_content_source_url(&self.content_source_url).is_ok() {
+                    self.prefetch = PrefetchState::Pending;
+                    self.save_settings();
+                }
+            }
+            Message::FabricLoaderInputChanged(input) => {
+                self.fabric_loader_input = input;
+                self.fabric_loader_error = None;
+            }
+            Message::ApplyFabricLoader => {
+                let loader_version = self.fabric_loader_input.trim().to_string();
+                if loader_version.is_empty() {
+                    return self.update(Message::ResetFabricLoader);
+                }
+                if !self.fabric_loader_checking {
+                    self.fabric_loader_checking = true;
+                    self.fabric_loader_error = None;
+                    return Task::perform(
+                        async move {
+                            validate_fabric_loader_version(&loader_version).await
+                                .map(|()| loader_version)
+                                .map_err(|e| e.to_string())
+                        },
+                        Message::FabricLoaderValidated
+                    );
+                }
+            }
+            Message::FabricLoaderValidated(result) => {
+                self.fabric_loader_checking = false;
+                match result {
+                    Ok(loader_version) => {
+                        self.fabric_loader_input = loader_version.clone();
+                        self.fabric_loader_override = Some(loader_version);
+                        self.prefetch = PrefetchState::Pending;
+                        self.save_settings();
+                        return self.rescan_mods();
+                    }
+                    Err(e) => self.fabric_loader_error = Some(e),
+                }
+            }
+            Message::ResetFabricLoader => {
+                self.fabric_loader_input.clear();
+                self.fabric_loader_error = None;
+                if self.fabric_loader_override.take().is_some() {
+                    self.prefetch = PrefetchState::Pending;
+                    self.save_settings();
+                    return self.rescan_mods();
+                }
+            }
+            Message::DownloadLimitChanged(input) => {
+                let trimmed = input.trim();
+                let limit = if trimmed.is_empty() {
+                    Some(None)
+                } else {
+                    trimmed.parse::<u32>().ok().filter(|mb| *mb > 0).map(Some)
+                };
+                self.download_limit_input = input;
+                if let Some(limit) = limit {
+                    self.download_limit_mb = limit;
+                    set_bandwidth_limit(self.download_limit_mb);
+                    self.save_settings();
+                }
+            }
+            Message::ConnectTimeoutChanged(secs) => {
+                self.connect_timeout_secs = secs;
+                set_timeouts(self.connect_timeout_secs, self.download_timeout_secs);
+                self.save_settings();
+            }
+            Message::DownloadTimeoutChanged(secs) => {
+                self.download_timeout_secs = secs;
+                set_timeouts(self.connect_timeout_secs, self.download_timeout_secs);
                 self.save_settings();
             }
             Message::LaunchGame => {
-                if !self.nickname.is_empty() && matches!(self.launch_state, LaunchState::Idle | LaunchState::Error(_)) {
+                self.launch_server = None;
+                self.request_launch();
+            }
+            Message::CustomServerChanged(address) => {
+                self.custom_server = address;
+            }
+            Message::ConnectToCustomServer => {
+                let address = self.custom_server.trim().to_string();
+                if !address.is_empty() && validate_server_address(&address).is_ok() {
+                    self.launch_server = Some(address);
+                    self.request_launch();
+                }
+            }
+            Message::LaunchIgnoringMemory => {
+                self.memory_warning = None;
+                if !self.block_for_mandatory_update() && self.can_start_launch() {
+                    self.start_launch();
+                }
+            }
+            Message::LowerRamAndLaunch => {
+                if let Some(warning) = self.memory_warning.take() {
+                    self.ram_mb = self.ram_mb.min(warning.suggested_mb);
+                    self.save_settings();
+                }
+                if !self.block_for_mandatory_update() && self.can_start_launch() {
+                    self.start_launch();
+                }
+            }
+            Message::DismissMemoryWarning => {
+                self.memory_warning = None;
+            }
+            Message::LaunchOffline => {
+                if self.block_for_mandatory_update() {
+                    return Task::none();
+                }
+                if !self.nickname.is_empty() && matches!(self.launch_state, LaunchState::NetworkError { can_play_offline: true, .. }) {
+                    self.offline_launch = true;
                     self.launch_state = LaunchState::Installing { 
-                        step: "Подготовка...".into(), 
+                        step: "Запуск без синхронизации...".into(), 
                         progress: 0.0 
                     };
                     self.game_running.store(true, Ordering::SeqCst);
                 }
             }
             Message::SwitchTab(tab) => {
+                if tab == Tab::Settings {
+                    self.refresh_content_packs();
+                }
                 self.active_tab = tab;
+                self.save_settings();
             }
             Message::InstallProgress(step, progress) => {
-                self.launch_state = LaunchState::Installing { step, progress };
+                if self.game_running.load(Ordering::SeqCst) {
+                    self.launch_state = LaunchState::Installing { step, progress };
+                }
+            }
+            Message::GameLaunching => {
+                if self.game_running.load(Ordering::SeqCst) {
+                    self.launch_state = LaunchState::Launching;
+                }
             }
             Message::LaunchComplete(result) => {
                 match result {
                     Ok(_) => {
                         self.launch_state = LaunchState::Playing;
-                        self.game_start_time = Some(std::time::SystemTime::now()
+                        self.content_outdated = false;
+                        let now = std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
-                            .as_secs() as i64);
+                            .as_secs() as i64;
+                        self.game_start_time = Some(now);
+                        self.play_stats.last_launch = Some(LastLaunch {
+                            timestamp: now,
+                            version: self.selected_version,
+                            loader_version: self.fabric_loader_version(self.selected_version),
+                        });
+                        self.save_play_stats();
                         self.update_discord_presence("Играет на сервере", &format!("Игрок: {}", self.nickname));
+                        self.notify("Игра запущена", "Minecraft готов, можно возвращаться");
+                        self.refresh_content_packs();
+                        self.java_status = JavaStatus::for_version(self.selected_version, self.use_system_java);
+                        return self.rescan_mods();
                     }
                     Err(e) => self.launch_state = LaunchState::Error(e),
                 }
             }
+            Message::LaunchNetworkError(message, can_play_offline) => {
+                self.game_running.store(false, Ordering::SeqCst);
+                self.launch_state = LaunchState::NetworkError { message, can_play_offline };
+            }
+            Message::ContentChangedInSession(kinds) => {
+                self.content_outdated = true;
+                self.notify("Обновился контент сервера", "Обновления скачаны и применятся при следующем запуске");
+                self.content_changed = Some(kinds);
+            }
+            Message::DismissContentChanged => {
+                self.content_changed = None;
+            }
             Message::GameExited => {
+                self.kill_requested = false;
+                self.prefetch = PrefetchState::Pending;
                 self.launch_state = LaunchState::Idle;
                 self.game_running.store(false, Ordering::SeqCst);
                 self.save_play_stats();
@@ -59,25 +461,36 @@ impl MinecraftLauncher {
                 self.game_start_time = None;
                 self.crash_count = 0;
                 self.update_discord_presence("В лаунчере", "Выбирает настройки");
+                self.prompt_pending_update();
             }
             Message::GameCrashed => {
+                if self.kill_requested {
+                    return self.update(Message::GameExited);
+                }
                 self.launch_state = LaunchState::Idle;
                 self.game_running.store(false, Ordering::SeqCst);
                 self.current_session_seconds = 0;
                 self.game_start_time = None;
                 self.crash_count += 1;
-                self.show_crash_dialog = true;
+                self.show_crash_dialog = self.crash_dialog_enabled && self.crash_count >= CRASH_DIALOG_THRESHOLD;
+                self.notify("Игра вылетела", "Minecraft завершился с ошибкой");
                 self.update_discord_presence("В лаунчере", "Выбирает настройки");
+                self.prompt_pending_update();
             }
             Message::GameCrashedWithLog(log) => {
+                if self.kill_requested {
+                    return self.update(Message::GameExited);
+                }
                 self.launch_state = LaunchState::Idle;
                 self.game_running.store(false, Ordering::SeqCst);
                 self.current_session_seconds = 0;
                 self.game_start_time = None;
                 self.crash_count += 1;
                 self.crash_log = Some(log);
-                self.show_crash_dialog = true;
+                self.show_crash_dialog = self.crash_dialog_enabled && self.crash_count >= CRASH_DIALOG_THRESHOLD;
+                self.notify("Игра вылетела", "Minecraft завершился с ошибкой, лог доступен в лаунчере");
                 self.update_discord_presence("В лаунчере", "Выбирает настройки");
+                self.prompt_pending_update();
             }
             Message::ReinstallGame => {
                 self.show_crash_dialog = false;
@@ -88,85 +501,603 @@ impl MinecraftLauncher {
                 }
                 self.launch_state = LaunchState::Idle;
             }
+            Message::LegacyDataDirFound(found) => {
+                if let Some((from, size)) = found {
+                    if self.dismissed_data_dir.as_deref() != Some(from.to_string_lossy().as_ref()) {
+                        self.data_migration = Some(DataMigration::Available { from, size });
+                    }
+                }
+            }
+            Message::MigrateDataDir => {
+                if let Some(DataMigration::Available { from, .. }) = &self.data_migration {
+                    if self.game_running.load(Ordering::SeqCst) {
+                        return Task::none();
+                    }
+                    let from = from.clone();
+                    self.data_migration = Some(DataMigration::Moving { from: from.clone(), progress: 0.0 });
+                    return Task::stream(iced::stream::channel(20, move |mut output| async move {
+                        use iced::futures::SinkExt;
+                        
+                        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+                        let task = tokio::task::spawn_blocking(move || {
+                            let mut last_percent = None;
+                            migrate_data_dir(&from, &get_game_directory(), |done, total| {
+                                let percent = done * 100 / total.max(1);
+                                if last_percent != Some(percent) {
+                                    last_percent = Some(percent);
+                                    let _ = tx.send(percent);
+                                }
+                            })
+                        });
+                        
+                        while let Some(percent) = rx.recv().await {
+                            let _ = output.send(Message::DataMigrationProgress(percent as f32 / 100.0)).await;
+                        }
+                        
+                        let result = match task.await {
+                            Ok(result) => result.map_err(|e| e.to_string()),
+                            Err(e) => Err(e.to_string()),
+                        };
+                        let _ = output.send(Message::DataMigrated(result)).await;
+                    }));
+                }
+            }
+            Message::DataMigrationProgress(value) => {
+                if let Some(DataMigration::Moving { progress, .. }) = &mut self.data_migration {
+                    *progress = value;
+                }
+            }
+            Message::DataMigrated(result) => {
+                match result {
+                    Ok(()) => {
+                        self.data_migration = Some(DataMigration::Done);
+                        self.refresh_content_packs();
+                        if !matches!(self.java_status, JavaStatus::Installing { .. }) {
+                            self.java_status = JavaStatus::for_version(self.selected_version, self.use_system_java);
+                        }
+                        self.prefetch = PrefetchState::Pending;
+                        return self.rescan_mods();
+                    }
+                    Err(e) => self.data_migration = Some(DataMigration::Failed(e)),
+                }
+            }
+            Message::DismissDataMigration => {
+                match self.data_migration.take() {
+                    Some(DataMigration::Available { from, .. }) => {
+                        self.dismissed_data_dir = Some(from.to_string_lossy().into_owned());
+                        self.save_settings();
+                    }
+                    Some(moving @ DataMigration::Moving { .. }) => self.data_migration = Some(moving),
+                    _ => {}
+                }
+            }
             Message::DismissCrashDialog => {
                 self.show_crash_dialog = false;
                 self.crash_log = None;
             }
+            Message::DisableCrashDialog => {
+                self.show_crash_dialog = false;
+                self.crash_log = None;
+                self.crash_dialog_enabled = false;
+                self.save_settings();
+            }
+            Message::CrashDialogToggled(enabled) => {
+                self.crash_dialog_enabled = enabled;
+                self.save_settings();
+            }
+            Message::ServerRejected(reason) => {
+                self.kick_reason = Some(reason);
+            }
+            Message::DismissKickDialog => {
+                self.kick_reason = None;
+            }
             Message::CopyCrashLog => {
                 if let Some(log) = &self.crash_log {
                     let _ = arboard::Clipboard::new().and_then(|mut cb| cb.set_text(log.clone()));
                 }
             }
+            Message::RuntimeArchDetected(is_32bit) => {
+                self.runtime_32bit = is_32bit;
+                if is_32bit && self.ram_mb > MAX_RAM_32BIT_MB {
+                    self.ram_mb = MAX_RAM_32BIT_MB;
+                    self.save_settings();
+                }
+            }
+            Message::ToggleDiagnostics => {
+                self.show_diagnostics = !self.show_diagnostics;
+                if self.show_diagnostics {
+                    self.diagnostics = None;
+                    return Task::perform(collect_diagnostics(self.selected_version, self.use_system_java, self.ram_mb, self.animation_stats), Message::DiagnosticsCollected);
+                }
+            }
+            Message::ToggleLogFiles => {
+                self.show_log_files = !self.show_log_files;
+                self.confirm_log_cleanup = false;
+                self.log_cleanup_status = None;
+                if self.show_log_files {
+                    self.log_files = None;
+                    return Task::perform(load_log_files(self.selected_version), Message::LogFilesListed);
+                }
+            }
+            Message::LogFilesListed(files) => {
+                if self.show_log_files {
+                    self.log_files = Some(files);
+                }
+            }
+            Message::CleanOldLogs => {
+                self.confirm_log_cleanup = true;
+                self.log_cleanup_status = None;
+            }
+            Message::CancelCleanOldLogs => {
+                self.confirm_log_cleanup = false;
+            }
+            Message::ConfirmCleanOldLogs => {
+                self.confirm_log_cleanup = false;
+                let protect_since = self.game_running.load(Ordering::SeqCst).then(|| {
+                    self.game_start_time
+                        .map(|start| std::time::UNIX_EPOCH + std::time::Duration::from_secs(start.max(0) as u64))
+                        .unwrap_or_else(std::time::SystemTime::now)
+                });
+                return Task::perform(clean_old_logs(self.selected_version, KEEP_LOG_FILES, protect_since), |(removed, reclaimed)| Message::OldLogsCleaned(removed, reclaimed));
+            }
+            Message::OldLogsCleaned(removed, reclaimed) => {
+                self.log_cleanup_status = Some(if removed == 0 {
+                    "Нечего удалять".to_string()
+                } else {
+                    format!("Удалено файлов: {} ({:.1} МБ)", removed, reclaimed as f64 / 1_048_576.0)
+                });
+                if self.show_log_files {
+                    return Task::perform(load_log_files(self.selected_version), Message::LogFilesListed);
+                }
+            }
+            Message::DiagnosticsCollected(report) => {
+                if self.show_diagnostics {
+                    self.diagnostics = Some(report);
+                }
+            }
+            Message::CopyDiagnostics => {
+                if let Some(report) = &self.diagnostics {
+                    let _ = arboard::Clipboard::new().and_then(|mut cb| cb.set_text(report.clone()));
+                }
+            }
             Message::NextFrame => {
                 if !self.gif_frames.is_empty() {
                     self.current_frame = (self.current_frame + 1) % self.gif_frames.len();
                 }
             }
             Message::CheckUpdate => {
+                if self.game_running.load(Ordering::SeqCst) || self.update_download.is_some() {
+                    return Task::none();
+                }
                 self.launch_state = LaunchState::CheckingUpdate;
                 return Task::perform(check_for_updates(), Message::UpdateStatus);
             }
             Message::UpdateStatus(result) => {
                 self.update_checked = true;
+                if !matches!(result, UpdateResult::Downloading(_)) {
+                    self.update_download = None;
+                }
                 match result {
                     UpdateResult::NoUpdate => {
                         self.launch_state = LaunchState::Idle;
                         self.update_discord_presence("В лаунчере", "Выбирает настройки");
                     }
-                    UpdateResult::UpdateAvailable(version, url) => {
-                        self.launch_state = LaunchState::UpdateAvailable { 
-                            version: version.clone(),
-                            download_url: url,
-                        };
+                    UpdateResult::UpdateAvailable(info) => {
+                        self.notify("Доступно обновление", &format!("Версия {} готова к установке", info.version));
+                        self.mandatory_update = info.mandatory.then(|| info.clone());
+                        self.launch_state = LaunchState::UpdateAvailable(info);
                     }
                     UpdateResult::Downloading(msg) => {
                         self.launch_state = LaunchState::Updating { progress: msg };
                     }
                     UpdateResult::Downloaded(path) => {
-                        let _ = std::process::Command::new(path).spawn();
-                        std::process::exit(0);
+                        self.pending_update = Some(path);
+                        if self.game_running.load(Ordering::SeqCst) {
+                            self.launch_state = LaunchState::Playing;
+                        } else {
+                            self.launch_state = LaunchState::UpdateReady;
+                        }
+                    }
+                    UpdateResult::VerificationFailed(e) => {
+                        self.launch_state = LaunchState::Error(format!("Обновление отклонено: {}", e));
                     }
                     UpdateResult::Error(e) => {
-                        self.launch_state = LaunchState::Idle;
+                        self.launch_state = match &self.mandatory_update {
+                            Some(info) => LaunchState::UpdateAvailable(info.clone()),
+                            None => LaunchState::Idle,
+                        };
                         eprintln!("Update error: {}", e);
                     }
                 }
             }
             Message::AcceptUpdate => {
-                if let LaunchState::UpdateAvailable { version, download_url } = self.launch_state.clone() {
+                if self.update_download.is_some() {
+                    return Task::none();
+                }
+                if let LaunchState::UpdateAvailable(info) = self.launch_state.clone() {
                     self.launch_state = LaunchState::Updating { 
-                        progress: format!("Скачивание v{}...", version) 
+                        progress: format!("Скачивание v{}...", info.version) 
                     };
-                    return Task::perform(download_and_run_update(download_url), Message::UpdateStatus);
+                    let (download, handle) = Task::perform(download_and_run_update(info), Message::UpdateStatus).abortable();
+                    self.update_download = Some(handle.abort_on_drop());
+                    return download;
+                }
+            }
+            Message::ApplyUpdate => {
+                if self.game_running.load(Ordering::SeqCst) {
+                    return Task::none();
+                }
+                if let Some(path) = self.pending_update.take() {
+                    let _ = std::process::Command::new(path).spawn();
+                    std::process::exit(0);
                 }
             }
             Message::DeclineUpdate => {
+                if let Some(download) = self.update_download.take() {
+                    download.abort();
+                }
+                if let Some(info) = &self.mandatory_update {
+                    if matches!(self.launch_state, LaunchState::Updating { .. }) {
+                        self.launch_state = LaunchState::UpdateAvailable(info.clone());
+                    }
+                    return Task::none();
+                }
                 self.launch_state = LaunchState::Idle;
                 self.update_discord_presence("В лаунчере", "Выбирает настройки");
             }
             Message::PlayTimeTick => {
                 if matches!(self.launch_state, LaunchState::Playing) {
                     self.current_session_seconds += 1;
-                    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
-                    *self.play_stats.daily.entry(today).or_insert(0) += 1;
-                    self.play_stats.total_seconds += 1;
-                    if self.current_session_seconds % 60 == 0 {
+                    self.play_stats.record_at(&chrono::Local::now(), 1);
+                    if self.current_session_seconds.is_multiple_of(60) {
                         self.save_play_stats();
                     }
                 }
             }
+            Message::DiscordRetryTick => {
+                let disconnected = self.discord_client.try_lock().is_ok_and(|guard| guard.is_none());
+                let due = self.discord_retry_at.is_none_or(|at| std::time::Instant::now() >= at);
+                if disconnected && due && self.discord_link != DiscordLink::Connecting {
+                    self.discord_link = DiscordLink::Connecting;
+                    return Task::perform(connect_discord(self.discord_client.clone()), Message::DiscordConnectAttempted);
+                }
+            }
+            Message::DiscordConnectAttempted(link) => {
+                match &link {
+                    DiscordLink::Connected => {
+                        self.discord_failures = 0;
+                        self.discord_retry_at = None;
+                    }
+                    DiscordLink::Missing => {
+                        if self.discord_failures == 0 {
+                            eprintln!("Discord IPC: Discord is not running");
+                        }
+                        self.discord_failures = self.discord_failures.max(1);
+                        self.discord_retry_at = Some(std::time::Instant::now() + DISCORD_RETRY_MISSING);
+                    }
+                    DiscordLink::Unavailable(e) => {
+                        self.discord_failures += 1;
+                        let delay = DISCORD_RETRY_BASE
+                            .saturating_mul(1 << self.discord_failures.min(8).saturating_sub(1))
+                            .min(DISCORD_RETRY_MAX);
+                        eprintln!("Discord IPC unavailable (attempt {}), retrying in {}s: {}", self.discord_failures, delay.as_secs(), e);
+                        self.discord_retry_at = Some(std::time::Instant::now() + delay);
+                    }
+                    DiscordLink::Connecting => {}
+                }
+                self.discord_link = link;
+                if self.discord_link == DiscordLink::Connected {
+                    self.refresh_discord_presence();
+                }
+            }
             Message::ServerStatusUpdate(status) => {
                 self.server_status = status;
+                return self.request_player_heads();
+            }
+            Message::TogglePlayerList => {
+                self.show_player_list = !self.show_player_list;
+                return self.request_player_heads();
+            }
+            Message::PlayerHeadLoaded(name, bytes) => {
+                if self.player_heads.contains_key(&name) {
+                    self.player_heads.insert(name, bytes.map(image::Handle::from_bytes));
+                }
             }
             Message::ToggleChangelog => {
                 self.show_changelog = !self.show_changelog;
+                if !self.show_changelog && self.new_changelog_entries > 0 {
+                    self.new_changelog_entries = 0;
+                    self.last_seen_version = Some(CURRENT_VERSION.to_string());
+                    self.save_settings();
+                }
+            }
+            Message::ContentVersionsComputed(versions) => {
+                self.content_versions = versions;
+            }
+            Message::ModsScanned(mods) => {
+                self.installed_mods = mods;
+            }
+            Message::DuplicateModsFound(duplicates) => {
+                self.duplicate_mods = duplicates;
+                self.duplicate_mods_error = None;
+            }
+            Message::RemoveDuplicateMods => {
+                if !self.game_running.load(Ordering::SeqCst) && !self.duplicate_mods.is_empty() {
+                    return Task::perform(
+                        remove_duplicate_mods(self.selected_version, self.duplicate_mods.clone()),
+                        Message::DuplicateModsRemoved
+                    );
+                }
+            }
+            Message::DuplicateModsRemoved(result) => {
+                if let Err(e) = result {
+                    self.duplicate_mods_error = Some(e);
+                }
+                return self.rescan_mods();
+            }
+            Message::DismissDuplicateMods => {
+                self.duplicate_mods.clear();
+                self.duplicate_mods_error = None;
+            }
+            Message::ShaderpackMissing(pack) => {
+                self.shader_notice = Some(match pack {
+                    Some(name) => format!("Шейдерпак {} не найден в папке shaderpacks, шейдеры выключены для этого запуска", name),
+                    None => "Шейдеры включены, но ни один шейдерпак не скачан. Шейдеры выключены для этого запуска".to_string(),
+                });
+            }
+            Message::ServerListUpdateFailed(error) => {
+                self.shader_notice = Some(format!("Не удалось добавить сервер в список серверов игры: {}", error));
+            }
+            Message::DismissShaderNotice => {
+                self.shader_notice = None;
+            }
+            Message::ToggleModList => {
+                self.show_mod_list = !self.show_mod_list;
+            }
+            Message::CancelLaunch => {
+                if matches!(self.launch_state, LaunchState::Installing { .. }) {
+                    self.game_running.store(false, Ordering::SeqCst);
+                    self.repairing = false;
+                    self.verifying = false;
+                    self.launch_state = LaunchState::Idle;
+                }
+            }
+            Message::RepairFabric => {
+                if !self.game_running.load(Ordering::SeqCst) && !self.repairing && !self.verifying && matches!(self.launch_state, LaunchState::Idle | LaunchState::Error(_)) {
+                    self.repairing = true;
+                    self.prefetch = PrefetchState::Pending;
+                    self.launch_state = LaunchState::Installing { 
+                        step: "Восстановление Fabric...".into(), 
+                        progress: 0.0 
+                    };
+                }
+            }
+            Message::RepairProgress(step, progress) => {
+                if self.repairing {
+                    self.launch_state = LaunchState::Installing { step, progress };
+                }
+            }
+            Message::RepairComplete(result) => {
+                if self.repairing {
+                    self.repairing = false;
+                    self.launch_state = match result {
+                        Ok(()) => LaunchState::Idle,
+                        Err(e) => LaunchState::Error(format!("Восстановление Fabric: {}", e)),
+                    };
+                    return self.rescan_mods();
+                }
+            }
+            Message::VerifyFiles => {
+                if !self.game_running.load(Ordering::SeqCst) && !self.repairing && !self.verifying && matches!(self.launch_state, LaunchState::Idle | LaunchState::Error(_)) {
+                    self.verifying = true;
+                    self.verify_report = None;
+                    self.launch_state = LaunchState::Installing { 
+                        step: "Проверка файлов...".into(), 
+                        progress: 0.0 
+                    };
+                }
+            }
+            Message::VerifyProgress(step, progress) => {
+                if self.verifying {
+                    self.launch_state = LaunchState::Installing { step, progress };
+                }
+            }
+            Message::VerifyComplete(result) => {
+                if self.verifying {
+                    self.verifying = false;
+                    self.verify_report = Some(result);
+                    self.launch_state = LaunchState::Idle;
+                    return self.rescan_mods();
+                }
+            }
+            Message::KillGame => {
+                if let Ok(mut guard) = self.game_process.lock() {
+                    if let Some(child) = guard.as_mut() {
+                        self.kill_requested = child.kill().is_ok();
+                    }
+                }
+            }
+            Message::FocusRequested => {
+                return iced::window::get_latest().and_then(|id| Task::batch([
+                    iced::window::minimize(id, false),
+                    iced::window::gain_focus(id),
+                ]));
+            }
+            Message::CloseRequested(id) => {
+                self.save_play_stats();
+                self.save_settings();
+                self.clear_discord_presence();
+                return iced::window::close(id);
             }
         }
         Task::none()
     }
 
+    fn rescan_mods(&self) -> Task<Message> {
+        Task::batch([
+            Task::perform(load_installed_mods(self.selected_version, self.fabric_loader_version(self.selected_version)), Message::ModsScanned),
+            Task::perform(load_content_versions(self.selected_version), Message::ContentVersionsComputed),
+            Task::perform(load_duplicate_mods(self.selected_version), Message::DuplicateModsFound),
+        ])
+    }
+
+    fn request_player_heads(&mut self) -> Task<Message> {
+        if !self.show_player_list {
+            return Task::none();
+        }
+        
+        let names = &self.server_status.player_names;
+        if self.player_heads.len() > MAX_CACHED_HEADS {
+            self.player_heads.retain(|name, _| names.contains(name));
+        }
+        
+        let missing: Vec<String> = names.iter()
+            .filter(|name| !self.player_heads.contains_key(*name))
+            .cloned()
+            .collect();
+        for name in &missing {
+            self.player_heads.insert(name.clone(), None);
+        }
+        
+        Task::batch(missing.into_iter().map(|name| {
+            Task::perform(fetch_player_head(name), |(name, bytes)| Message::PlayerHeadLoaded(name, bytes))
+        }))
+    }
+
+    fn apply_settings(&mut self, settings: LauncherSettings) -> Task<Message> {
+        self.nickname = settings.nickname;
+        self.account_mode = settings.account_mode;
+        self.ram_mb = settings.ram_mb;
+        self.selected_version = settings.selected_version;
+        self.shader_quality = settings.shader_quality;
+        self.optimized_jvm_flags = settings.optimized_jvm_flags;
+        self.use_system_java = settings.use_system_java;
+        self.env_vars = settings.env_vars;
+        self.garbage_collector = settings.garbage_collector;
+        self.quick_play = settings.quick_play;
+        self.sync_shaderpacks = settings.sync_shaderpacks;
+        self.sync_resourcepacks = settings.sync_resourcepacks;
+        self.mods_sync_policy = settings.mods_sync_policy;
+        self.shaderpacks_sync_policy = settings.shaderpacks_sync_policy;
+        self.resourcepacks_sync_policy = settings.resourcepacks_sync_policy;
+        self.shaderpack = settings.shaderpack;
+        self.prefetch_content = settings.prefetch_content;
+        self.fast_launch = settings.fast_launch;
+        self.glow_intensity = settings.glow_intensity.clamp(0.0, 1.0);
+        self.proxy_url = settings.proxy_url;
+        self.proxy_check = None;
+        self.content_source_url = settings.content_source_url;
+        self.fabric_loader_input = settings.fabric_loader_override.clone().unwrap_or_default();
+        self.fabric_loader_override = settings.fabric_loader_override;
+        self.fabric_loader_error = None;
+        self.connect_timeout_secs = settings.connect_timeout_secs;
+        self.download_timeout_secs = settings.download_timeout_secs;
+        self.download_limit_mb = settings.download_limit_mb;
+        self.download_limit_input = settings.download_limit_mb.map(|mb| mb.to_string()).unwrap_or_default();
+        self.notifications_enabled = settings.notifications_enabled;
+        self.notification_sound = settings.notification_sound;
+        self.crash_dialog_enabled = settings.crash_dialog_enabled;
+        self.prefetch = PrefetchState::Pending;
+        set_proxy_url(&self.proxy_url);
+        set_timeouts(self.connect_timeout_secs, self.download_timeout_secs);
+        set_bandwidth_limit(self.download_limit_mb);
+        self.save_settings();
+        self.refresh_content_packs();
+        if !matches!(self.java_status, JavaStatus::Installing { .. }) {
+            self.java_status = JavaStatus::for_version(self.selected_version, self.use_system_java);
+        }
+        self.rescan_mods()
+    }
+
+    fn refresh_content_packs(&mut self) {
+        let game_dir = get_versioned_game_directory(self.selected_version);
+        self.available_shaderpacks = detect_shaderpacks(&game_dir);
+        self.available_worlds = detect_worlds(&game_dir);
+        self.resourcepacks = load_resourcepacks(&game_dir);
+    }
+
+    fn save_resourcepacks(&mut self) {
+        let game_dir = get_versioned_game_directory(self.selected_version);
+        let enabled: Vec<String> = self.resourcepacks.iter()
+            .filter(|pack| pack.enabled)
+            .map(|pack| pack.name.clone())
+            .collect();
+        if let Err(e) = write_enabled_resourcepacks(&game_dir, &enabled) {
+            eprintln!("Failed to write resource packs: {}", e);
+        }
+        self.resourcepacks = load_resourcepacks(&game_dir);
+    }
+
+    fn request_launch(&mut self) {
+        if self.block_for_mandatory_update() || !self.can_start_launch() {
+            return;
+        }
+        let available_mb = available_memory_mb();
+        if self.ram_mb > available_mb {
+            self.memory_warning = Some(MemoryWarning {
+                available_mb,
+                suggested_mb: (available_mb.saturating_sub(MEMORY_HEADROOM_MB) / 512 * 512).max(MIN_SUGGESTED_RAM_MB),
+            });
+            return;
+        }
+        self.start_launch();
+    }
+
+    fn can_start_launch(&self) -> bool {
+        !self.nickname.is_empty()
+            && self.account_mode == AccountMode::Offline
+            && matches!(self.launch_state, LaunchState::Idle | LaunchState::Error(_) | LaunchState::NetworkError { .. })
+            && !matches!(self.data_migration, Some(DataMigration::Moving { .. }))
+    }
+
+    fn start_launch(&mut self) {
+        self.offline_launch = false;
+        self.content_changed = None;
+        self.memory_warning = None;
+        self.shader_notice = None;
+        self.launch_state = LaunchState::Installing { 
+            step: "Подготовка...".into(), 
+            progress: 0.0 
+        };
+        self.game_running.store(true, Ordering::SeqCst);
+    }
+
+    fn block_for_mandatory_update(&mut self) -> bool {
+        let Some(info) = &self.mandatory_update else {
+            return false;
+        };
+        if !matches!(self.launch_state, LaunchState::Updating { .. } | LaunchState::UpdateReady) {
+            self.launch_state = if self.pending_update.is_some() {
+                LaunchState::UpdateReady
+            } else {
+                LaunchState::UpdateAvailable(info.clone())
+            };
+        }
+        true
+    }
+
+    fn prompt_pending_update(&mut self) {
+        if self.pending_update.is_some() {
+            self.launch_state = LaunchState::UpdateReady;
+        }
+    }
+
+    fn notify(&self, summary: &str, body: &str) {
+        if self.notifications_enabled {
+            send_notification(summary.to_string(), body.to_string(), self.notification_sound);
+        }
+    }
+
+    fn refresh_discord_presence(&self) {
+        if matches!(self.launch_state, LaunchState::Playing) {
+            self.update_discord_presence("Играет на сервере", &format!("Игрок: {}", self.nickname));
+        } else {
+            self.update_discord_presence("В лаунчере", "Выбирает настройки");
+        }
+    }
+
     pub fn update_discord_presence(&self, state: &str, details: &str) {
-        if let Ok(mut guard) = self.discord_client.lock() {
+        if let Ok(mut guard) = self.discord_client.try_lock() {
             if let Some(client) = guard.as_mut() {
                 let mut act = activity::Activity::new()
                     .state(state)
@@ -181,13 +1112,16 @@ impl MinecraftLauncher {
                     act = act.timestamps(activity::Timestamps::new().start(start));
                 }
                 
-                let _ = client.set_activity(act);
+                if let Err(e) = client.set_activity(act) {
+                    eprintln!("Discord IPC: presence update failed, reconnecting: {}", e);
+                    *guard = None;
+                }
             }
         }
     }
 
     pub fn clear_discord_presence(&self) {
-        if let Ok(mut guard) = self.discord_client.lock() {
+        if let Ok(mut guard) = self.discord_client.try_lock() {
             if let Some(client) = guard.as_mut() {
                 let _ = client.clear_activity();
             }
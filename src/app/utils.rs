@@ -1,49 +1,83 @@
 use iced::{window, widget::image};
 use std::time::Duration;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use discord_rich_presence::{DiscordIpc, DiscordIpcClient};
+use crate::minecraft::{ContentVersions, DuplicateMod, GameVersion, ModInfo, content_versions, find_duplicate_mods, LOCAL_MODS_FOLDER, REMOVED_MODS_FOLDER, api_client, data_dir_size, find_legacy_data_dir, detect_resourcepacks, read_servers_dat, download_client, enabled_resourcepacks, find_java, get_game_directory, get_versioned_game_directory, is_64bit_os, java_is_64bit, java_version_line, list_log_files, remove_old_logs, scan_installed_mods, sweep_stale_files, LogFile};
 use crate::app::state::{
-    ServerStatus, UpdateResult, CURRENT_VERSION, GITHUB_RELEASES_API, INSTALLER_NAME
+    DiscordLink, FrameStats, LastLaunch, LauncherSettings, PlayTimeStats, ResourcepackEntry, ServerStatus, SettingsBackup, UpdateInfo, UpdateResult, CURRENT_VERSION, DISCORD_CLIENT_ID, GITHUB_RELEASES_API, INSTALLER_NAME, SERVER_ADDRESS
 };
 
-pub fn load_gif_frames() -> Vec<image::Handle> {
-    use ::image::codecs::gif::GifDecoder;
-    use ::image::AnimationDecoder;
-    
-    let gif_data = include_bytes!("../background.gif");
-    let cursor = std::io::Cursor::new(gif_data.as_slice());
-    
-    if let Ok(decoder) = GifDecoder::new(cursor) {
-        decoder.into_frames()
-            .filter_map(|f| f.ok())
-            .map(|frame| {
-                let rgba = frame.into_buffer();
-                let (w, h) = rgba.dimensions();
-                image::Handle::from_rgba(w, h, rgba.into_raw())
-            })
-            .collect()
-    } else {
-        vec![image::Handle::from_bytes(include_bytes!("../../background.png").to_vec())]
-    }
+const DEFAULT_SERVER_PORT: u16 = 25565;
+const MAX_PLAYER_COUNT: u64 = 100_000;
+const MAX_SAMPLE_NAMES: usize = 20;
+const MAX_STATUS_TEXT_CHARS: usize = 32;
+const PLAYER_HEAD_URL: &str = "https://mc-heads.net/avatar";
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+const BACKGROUND_MAX_SIZE: (u32, u32) = (1280, 800);
+const AVATAR_MAX_SIZE: (u32, u32) = (160, 160);
+const BACKGROUND_FRAME_BUDGET: u64 = 160 * 1024 * 1024;
+const AVATAR_FRAME_BUDGET: u64 = 16 * 1024 * 1024;
+
+pub fn load_gif_frames() -> (Vec<image::Handle>, FrameStats) {
+    decode_gif_frames(include_bytes!("../background.gif"), BACKGROUND_MAX_SIZE, BACKGROUND_FRAME_BUDGET)
+        .unwrap_or_else(|| (vec![image::Handle::from_bytes(include_bytes!("../../background.png").to_vec())], FrameStats::default()))
+}
+
+pub fn load_avatar_frames() -> (Vec<image::Handle>, FrameStats) {
+    decode_gif_frames(include_bytes!("../avatar.gif"), AVATAR_MAX_SIZE, AVATAR_FRAME_BUDGET)
+        .unwrap_or_else(|| (vec![image::Handle::from_bytes(include_bytes!("../icon.png").to_vec())], FrameStats::default()))
 }
 
-pub fn load_avatar_frames() -> Vec<image::Handle> {
+fn decode_gif_frames(data: &[u8], max_size: (u32, u32), budget: u64) -> Option<(Vec<image::Handle>, FrameStats)> {
     use ::image::codecs::gif::GifDecoder;
+    use ::image::imageops::{self, FilterType};
     use ::image::AnimationDecoder;
     
-    let gif_data = include_bytes!("../avatar.gif");
-    let cursor = std::io::Cursor::new(gif_data.as_slice());
-    
-    if let Ok(decoder) = GifDecoder::new(cursor) {
-        decoder.into_frames()
-            .filter_map(|f| f.ok())
-            .map(|frame| {
-                let rgba = frame.into_buffer();
-                let (w, h) = rgba.dimensions();
-                image::Handle::from_rgba(w, h, rgba.into_raw())
-            })
-            .collect()
-    } else {
-        vec![image::Handle::from_bytes(include_bytes!("../icon.png").to_vec())]
+    let decoder = GifDecoder::new(std::io::Cursor::new(data)).ok()?;
+    let mut stats = FrameStats::default();
+    let mut kept = Vec::new();
+    let mut stride = 1;
+    
+    for (index, frame) in decoder.into_frames().filter_map(|f| f.ok()).enumerate() {
+        stats.frames += 1;
+        if index % stride != 0 {
+            continue;
+        }
+        
+        let mut rgba = frame.into_buffer();
+        let (w, h) = rgba.dimensions();
+        let scale = (max_size.0 as f32 / w as f32).max(max_size.1 as f32 / h as f32);
+        if scale < 1.0 {
+            let (nw, nh) = (((w as f32 * scale).round() as u32).max(1), ((h as f32 * scale).round() as u32).max(1));
+            rgba = imageops::resize(&rgba, nw, nh, FilterType::Triangle);
+        }
+        stats.width = rgba.width();
+        stats.height = rgba.height();
+        kept.push(rgba);
+        
+        let frame_bytes = stats.width as u64 * stats.height as u64 * 4;
+        if kept.len() as u64 * frame_bytes > budget && kept.len() > 1 {
+            kept = kept.into_iter().step_by(2).collect();
+            stride *= 2;
+        }
     }
+    
+    if kept.is_empty() {
+        return None;
+    }
+    
+    stats.decoded = kept.len();
+    stats.bytes = kept.iter().map(|rgba| rgba.as_raw().len() as u64).sum();
+    let handles: Vec<image::Handle> = kept.into_iter()
+        .map(|rgba| image::Handle::from_rgba(rgba.width(), rgba.height(), rgba.into_raw()))
+        .collect();
+    let frames = handles.iter()
+        .flat_map(|handle| std::iter::repeat_n(handle.clone(), stride))
+        .take(stats.frames)
+        .collect();
+    Some((frames, stats))
 }
 
 pub fn load_icon() -> Option<window::Icon> {
@@ -53,9 +87,348 @@ pub fn load_icon() -> Option<window::Icon> {
     window::icon::from_rgba(img.into_raw(), width, height).ok()
 }
 
+pub fn format_ram(ram_mb: u32) -> String {
+    if ram_mb.is_multiple_of(1024) {
+        format!("{} ГБ", ram_mb / 1024)
+    } else {
+        format!("{:.1} ГБ", ram_mb as f32 / 1024.0)
+    }
+}
+
+pub fn normalize_nickname(nickname: &str) -> String {
+    nickname
+        .chars()
+        .filter(|c| !c.is_control() && !matches!(c, '\u{200B}'..='\u{200F}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}' | '\u{00AD}'))
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+pub fn format_session_time(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    let secs = seconds % 60;
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{:02}:{:02}", minutes, secs)
+    }
+}
+
+pub fn format_last_launch(last: &LastLaunch) -> String {
+    let when = match chrono::DateTime::from_timestamp(last.timestamp, 0) {
+        Some(time) => {
+            let time = time.with_timezone(&chrono::Local);
+            match PlayTimeStats::today().signed_duration_since(time.date_naive()).num_days() {
+                0 => format!("сегодня в {}", time.format("%H:%M")),
+                1 => format!("вчера в {}", time.format("%H:%M")),
+                _ => time.format("%d.%m.%Y").to_string(),
+            }
+        }
+        None => "давно".to_string(),
+    };
+    format!("{}, {}, Fabric Loader {}", when, last.version.display_name(), last.loader_version)
+}
+
+pub fn send_notification(summary: String, body: String, sound: bool) {
+    std::thread::spawn(move || {
+        let mut notification = notify_rust::Notification::new();
+        notification
+            .appname("ByStep Launcher")
+            .summary(&summary)
+            .body(&body);
+        if sound {
+            #[cfg(windows)]
+            notification.sound_name("Default");
+            #[cfg(not(windows))]
+            notification.sound_name("message-new-instant");
+        }
+        let _ = notification.show();
+    });
+}
+
+pub async fn export_settings_backup(backup: SettingsBackup) -> Result<bool, String> {
+    let Some(file) = rfd::AsyncFileDialog::new()
+        .set_title("Экспорт настроек")
+        .set_file_name(format!("bystep-backup-{}.json", chrono::Local::now().format("%Y-%m-%d")))
+        .add_filter("JSON", &["json"])
+        .save_file()
+        .await
+    else {
+        return Ok(false);
+    };
+
+    let json = serde_json::to_string_pretty(&backup).map_err(|e| e.to_string())?;
+    crate::app::write_atomic(file.path(), json.as_bytes())
+        .map_err(|e| format!("Не удалось сохранить резервную копию: {}", e))?;
+    Ok(true)
+}
+
+pub async fn import_settings_backup() -> Result<Option<Box<(LauncherSettings, PlayTimeStats)>>, String> {
+    let Some(file) = rfd::AsyncFileDialog::new()
+        .set_title("Импорт настроек")
+        .add_filter("JSON", &["json"])
+        .pick_file()
+        .await
+    else {
+        return Ok(None);
+    };
+
+    let content = file.read().await;
+    let backup: SettingsBackup = serde_json::from_slice(&content)
+        .map_err(|_| "Файл не является резервной копией лаунчера".to_string())?;
+    let settings = LauncherSettings::from_json(&backup.settings.to_string())
+        .ok_or_else(|| "Настройки в резервной копии повреждены".to_string())?;
+    Ok(Some(Box::new((settings, backup.playtime))))
+}
+
+pub async fn detect_32bit_runtime(version: GameVersion, use_system_java: bool) -> bool {
+    tokio::task::spawn_blocking(move || {
+        if !is_64bit_os() {
+            return true;
+        }
+        find_java(&get_game_directory(), version, use_system_java)
+            .ok()
+            .and_then(|java| java_is_64bit(&java))
+            .is_some_and(|is_64bit| !is_64bit)
+    })
+    .await
+    .unwrap_or(false)
+}
+
+pub async fn detect_legacy_data_dir() -> Option<(std::path::PathBuf, u64)> {
+    tokio::task::spawn_blocking(|| {
+        let dir = find_legacy_data_dir()?;
+        let size = data_dir_size(&dir);
+        Some((dir, size))
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+pub fn available_memory_mb() -> u32 {
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    (system.available_memory() / 1_048_576) as u32
+}
+
+pub async fn collect_diagnostics(version: GameVersion, use_system_java: bool, ram_mb: u32, animations: [FrameStats; 2]) -> String {
+    tokio::task::spawn_blocking(move || {
+        let mut system = sysinfo::System::new();
+        system.refresh_memory();
+        system.refresh_cpu_all();
+
+        let cpu = system.cpus().first().map(|cpu| cpu.brand().trim().to_string()).unwrap_or_default();
+        let cores = system.physical_core_count().unwrap_or(0);
+        let threads = system.cpus().len();
+        let java = match find_java(&get_game_directory(), version, use_system_java) {
+            Ok(path) => format!("{} ({})", path.display(), java_version_line(&path).unwrap_or_else(|| "версия неизвестна".into())),
+            Err(_) => "не установлена".into(),
+        };
+
+        [
+            format!("Лаунчер: {}", CURRENT_VERSION),
+            format!("ОС: {}", sysinfo::System::long_os_version().unwrap_or_else(|| "неизвестно".into())),
+            format!("Процессор: {} ({} ядер / {} потоков)", cpu, cores, threads),
+            format!("Видеокарта: {}", detect_gpu_name().unwrap_or_else(|| "неизвестно".into())),
+            format!(
+                "ОЗУ: всего {}, свободно {}",
+                format_ram((system.total_memory() / 1024 / 1024) as u32),
+                format_ram((system.available_memory() / 1024 / 1024) as u32)
+            ),
+            format!("Выделено игре: {}", format_ram(ram_mb)),
+            format!("Версия игры: {}", version.display_name()),
+            format!("Java: {}", java),
+            format!("Контент: {}", format_content_versions(&content_versions(&get_versioned_game_directory(version)))),
+            format!("servers.dat: {}", describe_servers_dat(&get_versioned_game_directory(version))),
+            format!("Фон: {}", format_frame_stats(&animations[0])),
+            format!("Аватар: {}", format_frame_stats(&animations[1])),
+        ].join("\n")
+    })
+    .await
+    .unwrap_or_default()
+}
+
+fn describe_servers_dat(game_dir: &Path) -> String {
+    if !game_dir.join("servers.dat").exists() {
+        return "файла нет".into();
+    }
+    match read_servers_dat(game_dir) {
+        Ok(servers) if servers.is_empty() => "список серверов пуст".into(),
+        Ok(servers) => servers.iter()
+            .map(|server| format!("{} ({}){}", server.name, server.ip, if server.hidden { ", скрыт" } else { "" }))
+            .collect::<Vec<_>>()
+            .join(", "),
+        Err(e) => format!("ошибка чтения: {:#}", e),
+    }
+}
+
+fn format_frame_stats(stats: &FrameStats) -> String {
+    format!(
+        "{} кадров ({} в памяти), {}x{}, ~{} МБ",
+        stats.frames,
+        stats.decoded,
+        stats.width,
+        stats.height,
+        stats.bytes.div_ceil(1024 * 1024)
+    )
+}
+
+fn detect_gpu_name() -> Option<String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-Command", "(Get-CimInstance Win32_VideoController).Name"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    let names: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+    (!names.is_empty()).then(|| names.join(", "))
+}
+
+pub async fn load_log_files(version: GameVersion) -> Vec<LogFile> {
+    tokio::task::spawn_blocking(move || list_log_files(&get_versioned_game_directory(version)))
+        .await
+        .unwrap_or_default()
+}
+
+pub async fn clean_old_logs(version: GameVersion, keep: usize, protect_since: Option<std::time::SystemTime>) -> (usize, u64) {
+    tokio::task::spawn_blocking(move || remove_old_logs(&get_versioned_game_directory(version), keep, protect_since))
+        .await
+        .unwrap_or_default()
+}
+
+pub async fn connect_discord(slot: Arc<Mutex<Option<DiscordIpcClient>>>) -> DiscordLink {
+    tokio::task::spawn_blocking(move || {
+        let mut client = match DiscordIpcClient::new(DISCORD_CLIENT_ID) {
+            Ok(client) => client,
+            Err(e) => return DiscordLink::Unavailable(e.to_string()),
+        };
+        match client.connect() {
+            Ok(()) => {
+                if let Ok(mut guard) = slot.lock() {
+                    *guard = Some(client);
+                }
+                DiscordLink::Connected
+            }
+            Err(e) => match discord_pipe_error() {
+                None => DiscordLink::Missing,
+                Some(pipe_error) => DiscordLink::Unavailable(format!("{} ({})", e, pipe_error)),
+            },
+        }
+    })
+    .await
+    .unwrap_or_else(|e| DiscordLink::Unavailable(e.to_string()))
+}
+
+fn discord_pipe_error() -> Option<std::io::Error> {
+    (0..10).find_map(|i| {
+        match std::fs::OpenOptions::new().read(true).write(true).open(format!(r"\\?\pipe\discord-ipc-{}", i)) {
+            Ok(_) => Some(std::io::Error::other("канал открыт, но рукопожатие не удалось")),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => Some(e),
+        }
+    })
+}
+
+pub async fn cleanup_stale_files() {
+    let reclaimed = tokio::task::spawn_blocking(sweep_stale_files).await.unwrap_or(0);
+    if reclaimed > 0 {
+        eprintln!("Cleanup: removed stale downloads, reclaimed {:.1} MB", reclaimed as f64 / 1_048_576.0);
+    }
+}
+
+pub fn load_resourcepacks(game_dir: &Path) -> Vec<ResourcepackEntry> {
+    let available = detect_resourcepacks(game_dir);
+    let enabled: Vec<String> = enabled_resourcepacks(game_dir)
+        .into_iter()
+        .filter(|name| available.contains(name))
+        .collect();
+    let mut entries: Vec<ResourcepackEntry> = enabled.iter()
+        .map(|name| ResourcepackEntry { name: name.clone(), enabled: true })
+        .collect();
+    entries.extend(available.into_iter()
+        .filter(|name| !enabled.contains(name))
+        .map(|name| ResourcepackEntry { name, enabled: false }));
+    entries
+}
+
+pub async fn load_content_versions(version: GameVersion) -> ContentVersions {
+    tokio::task::spawn_blocking(move || content_versions(&get_versioned_game_directory(version)))
+        .await
+        .unwrap_or_default()
+}
+
+pub fn format_content_versions(versions: &ContentVersions) -> String {
+    [("моды", &versions.mods), ("шейдеры", &versions.shaderpacks), ("текстуры", &versions.resourcepacks)]
+        .iter()
+        .map(|(label, fingerprint)| format!("{} {}", label, fingerprint.as_deref().unwrap_or("—")))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub async fn load_installed_mods(version: GameVersion, loader_version: String) -> Vec<ModInfo> {
+    let mods_dir = get_versioned_game_directory(version).join("mods");
+    tokio::task::spawn_blocking(move || {
+        let mc_version = version.minecraft_version();
+        let mut mods = scan_installed_mods(&mods_dir, mc_version, &loader_version);
+        mods.extend(scan_installed_mods(&mods_dir.join(LOCAL_MODS_FOLDER), mc_version, &loader_version));
+        mods.sort_by_key(|m| m.name.to_lowercase());
+        mods
+    })
+        .await
+        .unwrap_or_default()
+}
+
+pub fn mod_dirs(version: GameVersion) -> Vec<std::path::PathBuf> {
+    let mods_dir = get_versioned_game_directory(version).join("mods");
+    vec![mods_dir.join(LOCAL_MODS_FOLDER), mods_dir]
+}
+
+pub async fn load_duplicate_mods(version: GameVersion) -> Vec<DuplicateMod> {
+    tokio::task::spawn_blocking(move || find_duplicate_mods(&mod_dirs(version)))
+        .await
+        .unwrap_or_default()
+}
+
+pub async fn remove_duplicate_mods(version: GameVersion, duplicates: Vec<DuplicateMod>) -> Result<usize, String> {
+    let removed_dir = get_versioned_game_directory(version).join("mods").join(REMOVED_MODS_FOLDER);
+    tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(&removed_dir).map_err(|e| e.to_string())?;
+        let mut moved = 0;
+        for path in duplicates.iter().flat_map(|d| &d.older) {
+            let Some(file_name) = path.file_name() else {
+                continue;
+            };
+            std::fs::rename(path, removed_dir.join(file_name))
+                .map_err(|e| format!("{}: {}", path.display(), e))?;
+            moved += 1;
+        }
+        Ok(moved)
+    })
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+pub async fn fetch_player_head(name: String) -> (String, Option<Vec<u8>>) {
+    let url = format!("{}/{}/32", PLAYER_HEAD_URL, urlencoding::encode(&name));
+    let bytes = match api_client().get(&url).send().await {
+        Ok(response) if response.status().is_success() => response.bytes().await.ok(),
+        _ => None,
+    };
+    (name, bytes.filter(|b| b.len() <= MAX_HEAD_BYTES).map(|b| b.to_vec()))
+}
+
+const MANDATORY_UPDATE_MARKER: &str = "[mandatory]";
 
 pub async fn check_for_updates() -> UpdateResult {
-    let client = reqwest::Client::new();
+    let client = api_client();
     
     let response = match client
         .get(GITHUB_RELEASES_API)
@@ -85,27 +458,66 @@ pub async fn check_for_updates() -> UpdateResult {
         return UpdateResult::NoUpdate;
     }
     
-    if let Some(assets) = release.get("assets").and_then(|a| a.as_array()) {
-        for asset in assets {
-            let name = asset.get("name").and_then(|n| n.as_str()).unwrap_or("");
-            if name == INSTALLER_NAME {
-                if let Some(url) = asset.get("browser_download_url").and_then(|u| u.as_str()) {
-                    return UpdateResult::UpdateAvailable(
-                        latest_version.to_string(),
-                        url.to_string()
-                    );
+    let assets = match release.get("assets").and_then(|a| a.as_array()) {
+        Some(a) => a,
+        None => return UpdateResult::NoUpdate,
+    };
+    
+    let find_asset = |wanted: &str| {
+        assets.iter().find(|asset| asset.get("name").and_then(|n| n.as_str()) == Some(wanted))
+    };
+    
+    let installer = match find_asset(INSTALLER_NAME) {
+        Some(a) => a,
+        None => return UpdateResult::NoUpdate,
+    };
+    let download_url = match installer.get("browser_download_url").and_then(|u| u.as_str()) {
+        Some(u) => u.to_string(),
+        None => return UpdateResult::NoUpdate,
+    };
+    let size = installer.get("size").and_then(|s| s.as_u64()).unwrap_or(0);
+    
+    let body = release.get("body").and_then(|b| b.as_str()).unwrap_or("");
+    let mandatory = body.to_lowercase().contains(MANDATORY_UPDATE_MARKER);
+    let mut sha256 = parse_sha256(body);
+    
+    if sha256.is_none() {
+        let checksum_url = find_asset(&format!("{}.sha256", INSTALLER_NAME))
+            .and_then(|a| a.get("browser_download_url"))
+            .and_then(|u| u.as_str());
+        if let Some(url) = checksum_url {
+            if let Ok(r) = client.get(url).header("User-Agent", "ByStep-Launcher").send().await {
+                if let Ok(body) = r.text().await {
+                    sha256 = parse_sha256(&body);
                 }
             }
         }
     }
     
-    UpdateResult::NoUpdate
+    UpdateResult::UpdateAvailable(UpdateInfo {
+        version: latest_version.to_string(),
+        download_url,
+        size,
+        sha256,
+        mandatory,
+    })
 }
 
-pub async fn download_and_run_update(url: String) -> UpdateResult {
-    let client = reqwest::Client::new();
+fn parse_sha256(text: &str) -> Option<String> {
+    text.split(|c: char| !c.is_ascii_hexdigit())
+        .find(|word| word.len() == 64)
+        .map(|word| word.to_lowercase())
+}
+
+pub async fn download_and_run_update(info: UpdateInfo) -> UpdateResult {
+    let expected_hash = match &info.sha256 {
+        Some(h) => h.clone(),
+        None => return UpdateResult::VerificationFailed("в релизе не опубликована контрольная сумма SHA-256".to_string()),
+    };
     
-    let response = match client.get(&url).send().await {
+    let client = download_client();
+    
+    let response = match client.get(&info.download_url).send().await {
         Ok(r) => r,
         Err(e) => return UpdateResult::Error(e.to_string()),
     };
@@ -119,8 +531,24 @@ pub async fn download_and_run_update(url: String) -> UpdateResult {
         Err(e) => return UpdateResult::Error(e.to_string()),
     };
     
+    if info.size > 0 && bytes.len() as u64 != info.size {
+        return UpdateResult::VerificationFailed(format!(
+            "размер файла {} байт, ожидалось {}", bytes.len(), info.size
+        ));
+    }
+    
+    let actual_hash = format!("{:x}", Sha256::digest(&bytes));
+    if actual_hash != expected_hash {
+        return UpdateResult::VerificationFailed("контрольная сумма установщика не совпадает".to_string());
+    }
+    
     let temp_dir = std::env::temp_dir();
-    let installer_path = temp_dir.join(INSTALLER_NAME);
+    let installer_path = temp_dir.join(format!(
+        "{}-{}-{}.exe",
+        INSTALLER_NAME.trim_end_matches(".exe"),
+        info.version,
+        chrono::Utc::now().timestamp_millis()
+    ));
     
     if let Err(e) = std::fs::write(&installer_path, &bytes) {
         return UpdateResult::Error(e.to_string());
@@ -129,16 +557,61 @@ pub async fn download_and_run_update(url: String) -> UpdateResult {
     UpdateResult::Downloaded(installer_path)
 }
 
+pub fn validate_server_address(address: &str) -> Result<(), String> {
+    let address = address.trim();
+    let host = match address.rsplit_once(':') {
+        Some((host, port)) => {
+            match port.parse::<u16>() {
+                Ok(port) if port > 0 => {}
+                _ => return Err("Неверный порт, ожидается число от 1 до 65535".into()),
+            }
+            host
+        }
+        None => address,
+    };
+    if host.is_empty() || !host.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_')) {
+        return Err("Неверный адрес сервера, пример: play.example.com или 192.168.1.5:25565".into());
+    }
+    Ok(())
+}
+
+pub async fn resolve_server_address(address: &str) -> (String, u16) {
+    if let Some((host, port)) = address.rsplit_once(':') {
+        if let Ok(port) = port.parse::<u16>() {
+            return (host.to_string(), port);
+        }
+    }
+    
+    let host = address.trim();
+    if host.parse::<std::net::IpAddr>().is_ok() {
+        return (host.to_string(), DEFAULT_SERVER_PORT);
+    }
+    
+    if let Ok(resolver) = hickory_resolver::TokioAsyncResolver::tokio_from_system_conf() {
+        if let Ok(lookup) = resolver.srv_lookup(format!("_minecraft._tcp.{}", host)).await {
+            if let Some(record) = lookup.iter().min_by_key(|r| (r.priority(), std::cmp::Reverse(r.weight()))) {
+                let target = record.target().to_utf8();
+                return (target.trim_end_matches('.').to_string(), record.port());
+            }
+        }
+    }
+    
+    (host.to_string(), DEFAULT_SERVER_PORT)
+}
+
 pub async fn fetch_server_status() -> ServerStatus {
     use std::io::{Read, Write};
-    use std::net::TcpStream;
+    use std::net::{TcpStream, ToSocketAddrs};
     
     let mut status = ServerStatus::default();
     
-    let stream = match TcpStream::connect_timeout(
-        &"144.31.169.7:25565".parse().unwrap(),
-        Duration::from_secs(5)
-    ) {
+    let (host, port) = resolve_server_address(SERVER_ADDRESS).await;
+    let socket_addr = match (host.as_str(), port).to_socket_addrs().ok().and_then(|mut a| a.next()) {
+        Some(a) => a,
+        None => return status,
+    };
+    
+    let stream = match TcpStream::connect_timeout(&socket_addr, Duration::from_secs(5)) {
         Ok(s) => s,
         Err(_) => return status,
     };
@@ -151,8 +624,8 @@ pub async fn fetch_server_status() -> ServerStatus {
     let mut handshake = Vec::new();
     handshake.push(0x00);
     write_varint(&mut handshake, 767);
-    write_string(&mut handshake, "144.31.169.7");
-    handshake.extend_from_slice(&25565u16.to_be_bytes());
+    write_string(&mut handshake, &host);
+    handshake.extend_from_slice(&port.to_be_bytes());
     write_varint(&mut handshake, 1);
     
     let mut packet = Vec::new();
@@ -206,23 +679,66 @@ pub async fn fetch_server_status() -> ServerStatus {
     
     if let Ok(json) = serde_json::from_str::<serde_json::Value>(json_str) {
         status.online = true;
+        status.enforces_secure_chat = json.get("enforcesSecureChat").and_then(|v| v.as_bool()).unwrap_or(false);
+        status.version_name = json.get("version")
+            .and_then(|v| v.get("name"))
+            .and_then(|n| n.as_str())
+            .map(sanitize_status_text)
+            .filter(|name| !name.is_empty());
         
         if let Some(players) = json.get("players") {
-            status.players_online = players.get("online").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
-            status.players_max = players.get("max").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+            status.players_online = players.get("online").and_then(|v| v.as_u64()).unwrap_or(0).min(MAX_PLAYER_COUNT) as u32;
+            status.players_max = players.get("max").and_then(|v| v.as_u64()).unwrap_or(0).min(MAX_PLAYER_COUNT) as u32;
             
             if let Some(sample) = players.get("sample").and_then(|v| v.as_array()) {
                 status.player_names = sample.iter()
                     .filter_map(|p| p.get("name").and_then(|n| n.as_str()))
-                    .map(|s| s.to_string())
+                    .map(sanitize_status_text)
+                    .filter(|name| !name.is_empty())
+                    .take(MAX_SAMPLE_NAMES)
                     .collect();
             }
         }
+        
+        status.latency_ms = measure_latency(&mut stream);
     }
     
     status
 }
 
+fn measure_latency(stream: &mut std::net::TcpStream) -> Option<u32> {
+    use std::io::{Read, Write};
+    
+    let payload = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64;
+    let mut ping = vec![0x09, 0x01];
+    ping.extend_from_slice(&payload.to_be_bytes());
+    
+    let started = std::time::Instant::now();
+    stream.write_all(&ping).ok()?;
+    let mut pong = [0u8; 10];
+    stream.read_exact(&mut pong).ok()?;
+    if pong[0] != 0x09 || pong[1] != 0x01 || pong[2..] != payload.to_be_bytes() {
+        return None;
+    }
+    Some(started.elapsed().as_millis().min(u32::MAX as u128) as u32)
+}
+
+fn sanitize_status_text(raw: &str) -> String {
+    let mut name = String::new();
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            chars.next();
+        } else if !c.is_control() {
+            name.push(c);
+        }
+    }
+    name.trim().chars().take(MAX_STATUS_TEXT_CHARS).collect()
+}
+
 fn write_varint(buf: &mut Vec<u8>, mut value: i32) {
     loop {
         let mut byte = (value & 0x7F) as u8;
@@ -1,9 +1,10 @@
 use iced::{
-    Alignment, Border, Color, Element, Length, Shadow, Theme, Vector,
+    Alignment, Border, Color, Element, Length, Shadow, Theme,
     widget::{button, column, container, row, text, image, stack, Space, scrollable},
 };
-use crate::app::state::{Message, MinecraftLauncher, Tab};
-use crate::app::styles::{ACCENT, TEXT_PRIMARY, TEXT_SECONDARY};
+use crate::app::state::{AccountMode, DataMigration, Message, MinecraftLauncher, Tab};
+use crate::app::utils::format_ram;
+use crate::app::styles::{ACCENT, BG_CARD, TEXT_PRIMARY, TEXT_SECONDARY, glow};
 
 impl MinecraftLauncher {
     pub fn view(&self) -> Element<'_, Message> {
@@ -21,11 +22,19 @@ impl MinecraftLauncher {
 
         let sidebar = self.sidebar_view(avatar_handle);
         let content_area = container(
-            match self.active_tab {
-                Tab::Dashboard => self.dashboard_view(),
-                Tab::Statistics => self.statistics_view(),
-                Tab::Settings => self.settings_view(),
-            }
+            column![
+                self.config_warning_view(),
+                self.data_migration_view(),
+                self.content_changed_view(),
+                self.duplicate_mods_view(),
+                self.shader_notice_view(),
+                self.memory_warning_view(),
+                match self.active_tab {
+                    Tab::Dashboard => self.dashboard_view(),
+                    Tab::Statistics => self.statistics_view(),
+                    Tab::Settings => self.settings_view(),
+                },
+            ]
         )
         .width(Length::Fill)
         .height(Length::Fill)
@@ -50,6 +59,8 @@ impl MinecraftLauncher {
 
         let crash_dialog: Element<'_, Message> = if self.show_crash_dialog {
             self.crash_dialog_view()
+        } else if let Some(reason) = &self.kick_reason {
+            self.kick_dialog_view(reason)
         } else {
             Space::new(0, 0).into()
         };
@@ -63,6 +74,7 @@ impl MinecraftLauncher {
     }
 
     fn sidebar_view(&self, avatar_handle: image::Handle) -> Element<'_, Message> {
+        let glow_intensity = self.glow_intensity;
         container(
             column![
                 container(
@@ -104,13 +116,11 @@ impl MinecraftLauncher {
                         .style(move |_| container::Style {
                             background: Some(iced::Background::Color(ACCENT)),
                             border: Border { radius: 12.0.into(), ..Default::default() },
-                            shadow: Shadow {
-                                color: Color { r: 1.0, g: 0.2, b: 0.2, a: 0.7 },
-                                offset: Vector::new(0.0, 0.0),
-                                blur_radius: 12.0,
-                            },
+                            shadow: glow(Color { r: 1.0, g: 0.2, b: 0.2, a: 0.7 }, 12.0, glow_intensity),
                             ..Default::default()
                         }),
+                        Space::with_height(6),
+                        self.account_mode_toggle(),
                     ].spacing(0).align_x(Alignment::Center).width(Length::Fill)
                 )
                 .width(Length::Fill)
@@ -118,9 +128,9 @@ impl MinecraftLauncher {
                 
                 Space::with_height(15),
 
-                sidebar_button("ГЛАВНАЯ", Tab::Dashboard, &self.active_tab),
-                sidebar_button("СТАТИСТИКА", Tab::Statistics, &self.active_tab),
-                sidebar_button("НАСТРОЙКИ", Tab::Settings, &self.active_tab),
+                sidebar_button("ГЛАВНАЯ", Tab::Dashboard, &self.active_tab, glow_intensity),
+                sidebar_button("СТАТИСТИКА", Tab::Statistics, &self.active_tab, glow_intensity),
+                sidebar_button("НАСТРОЙКИ", Tab::Settings, &self.active_tab, glow_intensity),
                 
                 Space::with_height(Length::Fill),
                 
@@ -143,7 +153,333 @@ impl MinecraftLauncher {
         .into()
     }
 
+    fn account_mode_toggle(&self) -> Element<'_, Message> {
+        let online = self.account_mode == AccountMode::Online;
+        button(
+            text(if online { "● ОНЛАЙН" } else { "○ ОФЛАЙН" }).size(9)
+        )
+        .on_press(Message::AccountModeToggled)
+        .padding([4, 14])
+        .style(move |_, status| {
+            let hovered = status == button::Status::Hovered;
+            button::Style {
+                background: Some(iced::Background::Color(
+                    if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } }
+                    else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                )),
+                text_color: if online { Color { r: 0.3, g: 0.9, b: 0.4, a: 1.0 } } else { TEXT_SECONDARY },
+                border: Border { radius: 12.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                ..Default::default()
+            }
+        })
+        .into()
+    }
+
+    fn config_warning_view(&self) -> Element<'_, Message> {
+        match &self.config_warning {
+            Some(warning) => {
+                column![
+                    container(
+                        text(warning).size(12).color(Color { r: 1.0, g: 0.75, b: 0.3, a: 1.0 })
+                    )
+                    .padding([10, 15])
+                    .width(Length::Fill)
+                    .style(move |_| container::Style {
+                        background: Some(iced::Background::Color(Color { r: 0.25, g: 0.18, b: 0.05, a: 0.85 })),
+                        border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 0.75, b: 0.3, a: 0.4 } },
+                        ..Default::default()
+                    }),
+                    Space::with_height(15),
+                ].into()
+            }
+            None => Space::with_height(0).into(),
+        }
+    }
+
+    fn data_migration_view(&self) -> Element<'_, Message> {
+        let Some(migration) = &self.data_migration else {
+            return Space::with_height(0).into();
+        };
+        let action_button = |label: &'static str, message: Message| {
+            button(
+                container(text(label).size(12)).padding([5, 12])
+            )
+            .on_press(message)
+            .style(move |_, status| {
+                let hovered = status == button::Status::Hovered;
+                button::Style {
+                    background: Some(iced::Background::Color(
+                        if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } }
+                        else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                    )),
+                    text_color: TEXT_PRIMARY,
+                    border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                    ..Default::default()
+                }
+            })
+        };
+
+        let content: Element<'_, Message> = match migration {
+            DataMigration::Available { from, size } => row![
+                text(format!(
+                    "Найдена старая папка игры {} ({:.1} ГБ). Перенести её, чтобы не скачивать файлы заново?",
+                    from.display(),
+                    *size as f64 / 1_073_741_824.0
+                )).size(12).color(TEXT_PRIMARY).width(Length::Fill),
+                Space::with_width(10),
+                action_button("Перенести", Message::MigrateDataDir),
+                Space::with_width(8),
+                action_button("Не нужно", Message::DismissDataMigration),
+            ].align_y(Alignment::Center).into(),
+            DataMigration::Moving { progress, .. } => {
+                let progress_percent = (*progress * 100.0) as u16;
+                column![
+                    text(format!("Перенос файлов игры... {}%", progress_percent)).size(12).color(TEXT_PRIMARY),
+                    container(
+                        row![
+                            container(Space::new(Length::Fill, Length::Fill))
+                                .width(Length::FillPortion(progress_percent.max(1)))
+                                .height(Length::Fill)
+                                .style(move |_| container::Style {
+                                    background: Some(iced::Background::Color(ACCENT)),
+                                    border: Border { radius: 2.0.into(), ..Default::default() },
+                                    ..Default::default()
+                                }),
+                            container(Space::new(Length::Fill, Length::Fill))
+                                .width(Length::FillPortion((100 - progress_percent).max(1)))
+                                .height(Length::Fill),
+                        ]
+                    )
+                    .width(Length::Fill)
+                    .height(4)
+                    .style(move |_| container::Style {
+                        background: Some(iced::Background::Color(Color { r: 0.2, g: 0.2, b: 0.2, a: 1.0 })),
+                        border: Border { radius: 2.0.into(), ..Default::default() },
+                        ..Default::default()
+                    }),
+                ].spacing(6).into()
+            }
+            DataMigration::Failed(e) => row![
+                text(format!("Не удалось перенести файлы: {}", e)).size(12).color(TEXT_PRIMARY).width(Length::Fill),
+                Space::with_width(10),
+                action_button("Закрыть", Message::DismissDataMigration),
+            ].align_y(Alignment::Center).into(),
+            DataMigration::Done => row![
+                text("Файлы игры перенесены в новую папку").size(12).color(TEXT_PRIMARY).width(Length::Fill),
+                Space::with_width(10),
+                action_button("Закрыть", Message::DismissDataMigration),
+            ].align_y(Alignment::Center).into(),
+        };
+
+        column![
+            container(content)
+                .padding([10, 15])
+                .width(Length::Fill)
+                .style(move |_| container::Style {
+                    background: Some(iced::Background::Color(BG_CARD)),
+                    border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                    ..Default::default()
+                }),
+            Space::with_height(15),
+        ].into()
+    }
+
+    fn content_changed_view(&self) -> Element<'_, Message> {
+        let Some(kinds) = &self.content_changed else {
+            return Space::with_height(0).into();
+        };
+        let kinds = kinds.iter().map(|kind| kind.to_string().to_lowercase()).collect::<Vec<_>>().join(", ");
+
+        column![
+            container(
+                row![
+                    text(format!(
+                        "Пока вы играли, на сервере обновились: {}. Обновления уже скачаны и применятся при следующем запуске",
+                        kinds
+                    )).size(12).color(TEXT_PRIMARY).width(Length::Fill),
+                    Space::with_width(10),
+                    button(
+                        container(text("Закрыть").size(12)).padding([5, 12])
+                    )
+                    .on_press(Message::DismissContentChanged)
+                    .style(move |_, status| {
+                        let hovered = status == button::Status::Hovered;
+                        button::Style {
+                            background: Some(iced::Background::Color(
+                                if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } }
+                                else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                            )),
+                            text_color: TEXT_PRIMARY,
+                            border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                            ..Default::default()
+                        }
+                    }),
+                ].align_y(Alignment::Center)
+            )
+            .padding([10, 15])
+            .width(Length::Fill)
+            .style(move |_| container::Style {
+                background: Some(iced::Background::Color(BG_CARD)),
+                border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                ..Default::default()
+            }),
+            Space::with_height(15),
+        ].into()
+    }
+
+    fn duplicate_mods_view(&self) -> Element<'_, Message> {
+        if self.duplicate_mods.is_empty() {
+            return Space::with_height(0).into();
+        }
+        let action_button = |label: &'static str, message: Message| {
+            button(
+                container(text(label).size(12)).padding([5, 12])
+            )
+            .on_press(message)
+            .style(move |_, status| {
+                let hovered = status == button::Status::Hovered;
+                button::Style {
+                    background: Some(iced::Background::Color(
+                        if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } }
+                        else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                    )),
+                    text_color: TEXT_PRIMARY,
+                    border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                    ..Default::default()
+                }
+            })
+        };
+        let file_name = |path: &std::path::PathBuf| path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let details = self.duplicate_mods.iter()
+            .map(|d| format!(
+                "{}: оставить {}, убрать {}",
+                d.name,
+                file_name(&d.keep),
+                d.older.iter().map(file_name).collect::<Vec<_>>().join(", ")
+            ))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        column![
+            container(
+                column![
+                    text("В папке модов есть несколько версий одного мода, с ними Fabric не запустится").size(12).color(Color { r: 1.0, g: 0.75, b: 0.3, a: 1.0 }),
+                    text(details).size(11).color(TEXT_SECONDARY),
+                ]
+                .push_maybe(self.duplicate_mods_error.as_ref().map(|e| {
+                    text(format!("Не удалось убрать: {}", e)).size(11).color(Color { r: 1.0, g: 0.4, b: 0.4, a: 1.0 })
+                }))
+                .push(row![
+                    action_button("Убрать старые", Message::RemoveDuplicateMods),
+                    action_button("Закрыть", Message::DismissDuplicateMods),
+                ].spacing(8))
+                .spacing(10)
+            )
+            .padding([10, 15])
+            .width(Length::Fill)
+            .style(move |_| container::Style {
+                background: Some(iced::Background::Color(Color { r: 0.25, g: 0.18, b: 0.05, a: 0.85 })),
+                border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 0.75, b: 0.3, a: 0.4 } },
+                ..Default::default()
+            }),
+            Space::with_height(15),
+        ].into()
+    }
+
+    fn shader_notice_view(&self) -> Element<'_, Message> {
+        let Some(notice) = &self.shader_notice else {
+            return Space::with_height(0).into();
+        };
+
+        column![
+            container(
+                row![
+                    text(notice).size(12).color(Color { r: 1.0, g: 0.75, b: 0.3, a: 1.0 }).width(Length::Fill),
+                    button(
+                        container(text("Закрыть").size(12)).padding([5, 12])
+                    )
+                    .on_press(Message::DismissShaderNotice)
+                    .style(move |_, status| {
+                        let hovered = status == button::Status::Hovered;
+                        button::Style {
+                            background: Some(iced::Background::Color(
+                                if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } }
+                                else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                            )),
+                            text_color: TEXT_PRIMARY,
+                            border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                            ..Default::default()
+                        }
+                    }),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center)
+            )
+            .padding([10, 15])
+            .width(Length::Fill)
+            .style(move |_| container::Style {
+                background: Some(iced::Background::Color(Color { r: 0.25, g: 0.18, b: 0.05, a: 0.85 })),
+                border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 0.75, b: 0.3, a: 0.4 } },
+                ..Default::default()
+            }),
+            Space::with_height(15),
+        ].into()
+    }
+
+    fn memory_warning_view(&self) -> Element<'_, Message> {
+        let Some(warning) = self.memory_warning else {
+            return Space::with_height(0).into();
+        };
+        let action_button = |label: String, message: Message| {
+            button(
+                container(text(label).size(12)).padding([5, 12])
+            )
+            .on_press(message)
+            .style(move |_, status| {
+                let hovered = status == button::Status::Hovered;
+                button::Style {
+                    background: Some(iced::Background::Color(
+                        if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } }
+                        else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                    )),
+                    text_color: TEXT_PRIMARY,
+                    border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                    ..Default::default()
+                }
+            })
+        };
+
+        column![
+            container(
+                column![
+                    text(format!(
+                        "Игре выделено {}, а свободно только {}. Система может начать тормозить или игра не запустится",
+                        format_ram(self.ram_mb),
+                        format_ram(warning.available_mb)
+                    )).size(12).color(Color { r: 1.0, g: 0.75, b: 0.3, a: 1.0 }),
+                    row![
+                        action_button("Запустить так".to_string(), Message::LaunchIgnoringMemory),
+                    ]
+                    .push_maybe((warning.suggested_mb < self.ram_mb).then(|| {
+                        action_button(format!("Уменьшить до {} и запустить", format_ram(warning.suggested_mb)), Message::LowerRamAndLaunch)
+                    }))
+                    .push(action_button("Отмена".to_string(), Message::DismissMemoryWarning))
+                    .spacing(8),
+                ].spacing(10)
+            )
+            .padding([10, 15])
+            .width(Length::Fill)
+            .style(move |_| container::Style {
+                background: Some(iced::Background::Color(Color { r: 0.25, g: 0.18, b: 0.05, a: 0.85 })),
+                border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 0.75, b: 0.3, a: 0.4 } },
+                ..Default::default()
+            }),
+            Space::with_height(15),
+        ].into()
+    }
+
     fn crash_dialog_view(&self) -> Element<'_, Message> {
+        let glow_intensity = self.glow_intensity;
         let crash_log_widget: Element<'_, Message> = if let Some(log) = &self.crash_log {
             column![
                 container(
@@ -183,7 +519,7 @@ impl MinecraftLauncher {
         container(
             container(
                 column![
-                    text("Игра завершилась с ошибкой").size(18).color(TEXT_PRIMARY),
+                    text("Игра вылетает несколько раз подряд").size(18).color(TEXT_PRIMARY),
                     Space::with_height(10),
                     text("Рекомендуем переустановить файлы игры.").size(13).color(TEXT_SECONDARY),
                     Space::with_height(15),
@@ -203,12 +539,7 @@ impl MinecraftLauncher {
                                 )),
                                 text_color: Color::WHITE,
                                 border: Border { radius: 8.0.into(), ..Default::default() },
-                                shadow: Shadow {
-                                    color: Color { r: 1.0, g: 0.2, b: 0.2, a: 0.6 },
-                                    offset: Vector::new(0.0, 0.0),
-                                    blur_radius: 12.0,
-                                },
-                                ..Default::default()
+                                shadow: glow(Color { r: 1.0, g: 0.2, b: 0.2, a: 0.6 }, 12.0, glow_intensity),
                             }
                         }),
                         Space::with_width(10),
@@ -228,7 +559,20 @@ impl MinecraftLauncher {
                                 ..Default::default()
                             }
                         }),
-                    ]
+                    ],
+                    Space::with_height(10),
+                    button(
+                        text("Больше не показывать").size(12)
+                    )
+                    .on_press(Message::DisableCrashDialog)
+                    .style(move |_, status| {
+                        let hovered = status == button::Status::Hovered;
+                        button::Style {
+                            background: None,
+                            text_color: if hovered { TEXT_PRIMARY } else { TEXT_SECONDARY },
+                            ..Default::default()
+                        }
+                    }),
                 ].align_x(Alignment::Center)
             )
             .padding(30)
@@ -249,12 +593,56 @@ impl MinecraftLauncher {
         .into()
     }
 
+    fn kick_dialog_view<'a>(&'a self, reason: &'a str) -> Element<'a, Message> {
+        container(
+            container(
+                column![
+                    text("Сервер не пустил в игру").size(18).color(TEXT_PRIMARY),
+                    Space::with_height(10),
+                    text(reason).size(13).color(TEXT_SECONDARY),
+                    Space::with_height(20),
+                    button(
+                        container(text("Понятно").size(14)).padding([10, 20])
+                    )
+                    .on_press(Message::DismissKickDialog)
+                    .style(move |_, status| {
+                        let hovered = status == button::Status::Hovered;
+                        button::Style {
+                            background: Some(iced::Background::Color(
+                                if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } }
+                                else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                            )),
+                            text_color: TEXT_SECONDARY,
+                            border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                            ..Default::default()
+                        }
+                    }),
+                ].align_x(Alignment::Center).max_width(400)
+            )
+            .padding(30)
+            .style(move |_| container::Style {
+                background: Some(iced::Background::Color(Color { r: 0.08, g: 0.08, b: 0.1, a: 0.98 })),
+                border: Border { radius: 15.0.into(), width: 1.0, color: ACCENT },
+                ..Default::default()
+            })
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .style(move |_| container::Style {
+            background: Some(iced::Background::Color(Color { r: 0.0, g: 0.0, b: 0.0, a: 0.7 })),
+            ..Default::default()
+        })
+        .into()
+    }
+
     pub fn theme(&self) -> Theme {
         Theme::Dark
     }
 }
 
-fn sidebar_button<'a>(label: &'a str, tab: Tab, active_tab: &Tab) -> Element<'a, Message> {
+fn sidebar_button<'a>(label: &'a str, tab: Tab, active_tab: &Tab, glow_intensity: f32) -> Element<'a, Message> {
     let is_active = tab == *active_tab;
     button(
         container(text(label).size(12).font(iced::Font::MONOSPACE).style(move |_| text::Style { color: Some(if is_active { Color::WHITE } else { TEXT_SECONDARY }) }))
@@ -275,15 +663,10 @@ fn sidebar_button<'a>(label: &'a str, tab: Tab, active_tab: &Tab) -> Element<'a,
             text_color: if is_active { Color::WHITE } else { TEXT_SECONDARY },
             border: Border { radius: 10.0.into(), width: 0.0, color: Color::TRANSPARENT },
             shadow: if is_active {
-                Shadow {
-                    color: Color { r: 1.0, g: 0.2, b: 0.2, a: 0.6 },
-                    offset: Vector::new(0.0, 0.0),
-                    blur_radius: 15.0,
-                }
+                glow(Color { r: 1.0, g: 0.2, b: 0.2, a: 0.6 }, 15.0, glow_intensity)
             } else {
                 Shadow::default()
             },
-            ..Default::default()
         }
     })
     .width(Length::Fill)
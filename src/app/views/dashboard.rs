@@ -1,33 +1,39 @@
 use iced::{
-    Alignment, Border, Color, Element, Length, Shadow, Vector,
-    widget::{button, column, container, row, text, image, Space, pick_list, scrollable},
+    Alignment, Border, Color, Element, Length, Shadow,
+    widget::{button, column, container, row, text, text_input, image, tooltip, Space, pick_list, scrollable},
 };
-use crate::app::state::{LaunchState, Message, MinecraftLauncher, CHANGELOG};
-use crate::app::styles::{ACCENT, BG_CARD, TEXT_PRIMARY, TEXT_SECONDARY};
-use crate::minecraft::{GameVersion, ShaderQuality};
+use crate::app::state::{AccountMode, JavaStatus, LaunchState, Message, MinecraftLauncher, PrefetchState, CHANGELOG};
+use crate::app::utils::{format_content_versions, format_last_launch, format_ram, format_session_time, validate_server_address};
+use crate::app::styles::{ACCENT, BG_CARD, TEXT_PRIMARY, TEXT_SECONDARY, glow, input_style, menu_style, pick_list_style};
+use crate::minecraft::{DownloadKind, GameVersion, InstallStep, ShaderQuality, LOCAL_MODS_FOLDER, REMOVED_MODS_FOLDER};
 
 impl MinecraftLauncher {
     pub fn dashboard_view(&self) -> Element<'_, Message> {
         let (button_text, button_enabled) = match &self.launch_state {
             LaunchState::CheckingUpdate => ("ПРОВЕРКА...", false),
-            LaunchState::UpdateAvailable { .. } => ("ИГРАТЬ", false),
+            LaunchState::UpdateAvailable(_) => ("ИГРАТЬ", false),
             LaunchState::Updating { .. } => ("ОБНОВЛЕНИЕ...", false),
-            LaunchState::Idle => ("ИГРАТЬ", !self.nickname.is_empty()),
+            LaunchState::UpdateReady => ("ИГРАТЬ", false),
+            LaunchState::Idle => ("ИГРАТЬ", !self.nickname.is_empty() && self.account_mode == AccountMode::Offline && !matches!(self.java_status, JavaStatus::Installing { .. })),
             LaunchState::Installing { .. } => ("УСТАНОВКА...", false),
             LaunchState::Launching => ("ЗАПУСК...", false),
             LaunchState::Playing => ("В ИГРЕ", false),
             LaunchState::Error(_) => ("ПОВТОРИТЬ", true),
+            LaunchState::NetworkError { .. } => ("ПОВТОРИТЬ", true),
         };
 
         let status_widget = self.status_widget_view();
         let header_row = self.header_with_buttons();
         let server_status_widget = self.server_status_widget_view();
+        let mod_list_widget = self.mod_list_view();
 
         column![
             header_row,
             Space::with_height(20),
             server_status_widget,
             Space::with_height(10),
+            mod_list_widget,
+            Space::with_height(10),
             status_widget,
             Space::with_height(Length::Fill),
             self.bottom_panel(button_text, button_enabled)
@@ -55,12 +61,13 @@ impl MinecraftLauncher {
                 text_color: TEXT_SECONDARY,
                 border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
                 shadow: Shadow::default(),
-                ..Default::default()
             }
         });
 
+        let has_news = self.new_changelog_entries > 0;
+        let changelog_label = if has_news { "? •" } else { "?" };
         let changelog_button = button(
-            container(text("?").size(14)).padding([6, 10])
+            container(text(changelog_label).size(14)).padding([6, 10])
         )
         .on_press(Message::ToggleChangelog)
         .style(move |_, status| {
@@ -70,10 +77,9 @@ impl MinecraftLauncher {
                     if hovered { Color { r: 0.2, g: 0.2, b: 0.22, a: 0.9 } } 
                     else { Color { r: 0.12, g: 0.12, b: 0.14, a: 0.8 } }
                 )),
-                text_color: TEXT_SECONDARY,
-                border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                text_color: if has_news { ACCENT } else { TEXT_SECONDARY },
+                border: Border { radius: 8.0.into(), width: 1.0, color: if has_news { ACCENT } else { Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } } },
                 shadow: Shadow::default(),
-                ..Default::default()
             }
         });
 
@@ -81,15 +87,30 @@ impl MinecraftLauncher {
             container(
                 scrollable(
                     column(
-                        CHANGELOG.iter().map(|(ver, desc)| {
+                        CHANGELOG.iter().enumerate().map(|(index, (ver, desc))| {
+                            let is_new = index < self.new_changelog_entries;
+                            let title: Element<'_, Message> = if is_new {
+                                row![
+                                    text(format!("v{}", ver)).size(13).color(ACCENT),
+                                    Space::with_width(6),
+                                    text("НОВОЕ").size(10).color(TEXT_PRIMARY),
+                                ].align_y(Alignment::Center).into()
+                            } else {
+                                text(format!("v{}", ver)).size(13).color(ACCENT).into()
+                            };
                             container(
                                 column![
-                                    text(format!("v{}", ver)).size(13).color(ACCENT),
-                                    text(*desc).size(11).color(TEXT_SECONDARY),
+                                    title,
+                                    text(*desc).size(11).color(if is_new { TEXT_PRIMARY } else { TEXT_SECONDARY }),
                                 ].spacing(2)
                             )
                             .padding([8, 10])
                             .width(Length::Fill)
+                            .style(move |_| container::Style {
+                                background: is_new.then_some(iced::Background::Color(Color { r: ACCENT.r, g: ACCENT.g, b: ACCENT.b, a: 0.12 })),
+                                border: Border { radius: 6.0.into(), ..Default::default() },
+                                ..Default::default()
+                            })
                             .into()
                         }).collect::<Vec<_>>()
                     ).spacing(5)
@@ -126,6 +147,7 @@ impl MinecraftLauncher {
     }
 
     fn bottom_panel<'a>(&'a self, button_text: &'a str, button_enabled: bool) -> Element<'a, Message> {
+        let glow_intensity = self.glow_intensity;
         let versions: Vec<GameVersion> = GameVersion::all();
         let shader_qualities: Vec<ShaderQuality> = ShaderQuality::all();
 
@@ -160,7 +182,7 @@ impl MinecraftLauncher {
                     Space::with_width(20),
                     column![
                         text("ОЗУ").size(11).color(TEXT_SECONDARY),
-                        text(format!("{} ГБ", self.ram_gb)).size(14).color(ACCENT),
+                        text(format_ram(self.ram_mb)).size(14).color(ACCENT),
                     ].spacing(5),
                     Space::with_width(Length::Fill),
                     button(
@@ -179,18 +201,17 @@ impl MinecraftLauncher {
                             text_color: Color::WHITE,
                             border: Border { radius: 10.0.into(), width: 0.0, color: Color::TRANSPARENT },
                             shadow: if button_enabled {
-                                Shadow {
-                                    color: Color { r: 1.0, g: 0.2, b: 0.2, a: 0.8 },
-                                    offset: Vector::new(0.0, 0.0),
-                                    blur_radius: 25.0,
-                                }
+                                glow(Color { r: 1.0, g: 0.2, b: 0.2, a: 0.8 }, 25.0, glow_intensity)
                             } else {
                                 Shadow::default()
                             },
-                            ..Default::default()
                         }
                     }),
-                ].align_y(Alignment::Center)
+                ].align_y(Alignment::Center),
+                self.last_launch_view(),
+                self.custom_server_view(button_enabled),
+                self.account_mode_hint(),
+                self.java_status_view(),
             ]
             .padding(25)
         )
@@ -203,11 +224,119 @@ impl MinecraftLauncher {
         .into()
     }
 
+    fn last_launch_view(&self) -> Element<'_, Message> {
+        let Some(last) = &self.play_stats.last_launch else {
+            return Space::with_height(0).into();
+        };
+        column![
+            Space::with_height(12),
+            text(format!("Последний запуск: {}", format_last_launch(last))).size(11).color(TEXT_SECONDARY),
+        ].into()
+    }
+
+    fn custom_server_view(&self, launch_enabled: bool) -> Element<'_, Message> {
+        let address = self.custom_server.trim();
+        let validation = if address.is_empty() { Ok(()) } else { validate_server_address(address) };
+        let can_connect = launch_enabled && !address.is_empty() && validation.is_ok();
+
+        column![
+            Space::with_height(15),
+            row![
+                text_input("Подключиться к другому серверу: адрес:порт", &self.custom_server)
+                    .on_input(Message::CustomServerChanged)
+                    .on_submit_maybe(can_connect.then_some(Message::ConnectToCustomServer))
+                    .size(13)
+                    .padding([8, 12])
+                    .style(input_style),
+                Space::with_width(10),
+                button(
+                    container(text("Подключиться").size(12)).padding([5, 12])
+                )
+                .on_press_maybe(can_connect.then_some(Message::ConnectToCustomServer))
+                .style(move |_, status| {
+                    let hovered = status == button::Status::Hovered;
+                    button::Style {
+                        background: Some(iced::Background::Color(
+                            if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } } 
+                            else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                        )),
+                        text_color: TEXT_PRIMARY,
+                        border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                        ..Default::default()
+                    }
+                }),
+            ].align_y(Alignment::Center),
+        ]
+        .push_maybe(validation.err().map(|e| text(e).size(11).color(WARNING_TEXT)))
+        .spacing(5)
+        .into()
+    }
+
+    fn account_mode_hint(&self) -> Element<'_, Message> {
+        if self.account_mode == AccountMode::Offline {
+            return Space::with_height(0).into();
+        }
+        column![
+            Space::with_height(15),
+            text("Онлайн-режим требует вход в аккаунт Microsoft, а он пока не поддерживается. Переключитесь на офлайн-режим под аватаром, чтобы играть").size(12).color(WARNING_TEXT),
+        ].into()
+    }
+
+    fn java_status_view(&self) -> Element<'_, Message> {
+        let java_version = self.selected_version.java_version();
+        let install_button = |label: &'static str| {
+            button(
+                container(text(label).size(12)).padding([5, 12])
+            )
+            .on_press(Message::InstallJava)
+            .style(move |_, status| {
+                let hovered = status == button::Status::Hovered;
+                button::Style {
+                    background: Some(iced::Background::Color(
+                        if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } } 
+                        else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                    )),
+                    text_color: TEXT_PRIMARY,
+                    border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                    ..Default::default()
+                }
+            })
+        };
+
+        let content: Element<'_, Message> = match &self.java_status {
+            JavaStatus::Ready(_) => return Space::with_height(0).into(),
+            JavaStatus::Missing => row![
+                text(format!("Java {} будет установлена при запуске", java_version)).size(12).color(TEXT_SECONDARY),
+                Space::with_width(Length::Fill),
+                install_button("Установить Java сейчас"),
+            ].align_y(Alignment::Center).into(),
+            JavaStatus::Installing { step } => row![
+                text(step.as_str()).size(12).color(TEXT_SECONDARY),
+            ].align_y(Alignment::Center).into(),
+            JavaStatus::Failed(e) => row![
+                text(format!("Не удалось установить Java {}: {}", java_version, e)).size(12).color(WARNING_TEXT),
+                Space::with_width(Length::Fill),
+                install_button("Повторить"),
+            ].align_y(Alignment::Center).into(),
+        };
+
+        column![Space::with_height(12), content].into()
+    }
+
+    fn spinner_glyph(&self) -> &'static str {
+        ["◐", "◓", "◑", "◒"][(self.current_frame / 4) % 4]
+    }
+
     fn status_widget_view(&self) -> Element<'_, Message> {
+        let glow_intensity = self.glow_intensity;
         match &self.launch_state {
             LaunchState::CheckingUpdate => {
                 container(
-                    text("Проверка обновлений...").size(14).color(TEXT_SECONDARY)
+                    row![
+                        text(self.spinner_glyph()).size(16).color(ACCENT),
+                        Space::with_width(10),
+                        text("Проверка обновлений...").size(14).color(TEXT_SECONDARY),
+                    ].align_y(Alignment::Center)
                 )
                 .padding(15)
                 .style(move |_| container::Style {
@@ -218,12 +347,21 @@ impl MinecraftLauncher {
                 .width(Length::Fill)
                 .into()
             }
-            LaunchState::UpdateAvailable { version, .. } => {
+            LaunchState::UpdateAvailable(info) => {
+                let mandatory = info.mandatory;
                 container(
                     column![
-                        text(format!("Доступно обновление v{}", version)).size(16).color(ACCENT),
+                        text(if mandatory {
+                            format!("Обязательное обновление v{}", info.version)
+                        } else {
+                            format!("Доступно обновление v{}", info.version)
+                        }).size(16).color(ACCENT),
                         Space::with_height(10),
-                        text("Хотите обновить сейчас?").size(13).color(TEXT_SECONDARY),
+                        text(if mandatory {
+                            "Без этого обновления играть на сервере не получится"
+                        } else {
+                            "Хотите обновить сейчас?"
+                        }).size(13).color(TEXT_SECONDARY),
                         Space::with_height(15),
                         row![
                             button(
@@ -239,15 +377,12 @@ impl MinecraftLauncher {
                                     )),
                                     text_color: Color::WHITE,
                                     border: Border { radius: 8.0.into(), ..Default::default() },
-                                    shadow: Shadow {
-                                        color: Color { r: 1.0, g: 0.2, b: 0.2, a: 0.7 },
-                                        offset: Vector::new(0.0, 0.0),
-                                        blur_radius: 15.0,
-                                    },
-                                    ..Default::default()
+                                    shadow: glow(Color { r: 1.0, g: 0.2, b: 0.2, a: 0.7 }, 15.0, glow_intensity),
                                 }
                             }),
-                            Space::with_width(10),
+                        ]
+                        .spacing(10)
+                        .push_maybe((!mandatory).then(|| {
                             button(
                                 container(text("Позже").size(14)).padding([8, 20])
                             )
@@ -263,8 +398,8 @@ impl MinecraftLauncher {
                                     border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
                                     ..Default::default()
                                 }
-                            }),
-                        ]
+                            })
+                        }))
                     ].align_x(Alignment::Center)
                 )
                 .padding(20)
@@ -282,6 +417,23 @@ impl MinecraftLauncher {
                         text(progress).size(14).color(ACCENT),
                         Space::with_height(5),
                         text("Пожалуйста, подождите...").size(12).color(TEXT_SECONDARY),
+                        Space::with_height(10),
+                        button(
+                            container(text("Отмена").size(13)).padding([6, 16])
+                        )
+                        .on_press(Message::DeclineUpdate)
+                        .style(move |_, status| {
+                            let hovered = status == button::Status::Hovered;
+                            button::Style {
+                                background: Some(iced::Background::Color(
+                                    if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } } 
+                                    else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                                )),
+                                text_color: TEXT_SECONDARY,
+                                border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                                ..Default::default()
+                            }
+                        }),
                     ].align_x(Alignment::Center)
                 )
                 .padding(20)
@@ -293,13 +445,73 @@ impl MinecraftLauncher {
                 .width(Length::Fill)
                 .into()
             }
+            LaunchState::UpdateReady => {
+                let mandatory = self.mandatory_update.is_some();
+                container(
+                    column![
+                        text("Обновление загружено").size(16).color(ACCENT),
+                        Space::with_height(10),
+                        text("Перезапустить лаунчер и установить его сейчас?").size(13).color(TEXT_SECONDARY),
+                        Space::with_height(15),
+                        row![
+                            button(
+                                container(text("Перезапустить").size(14)).padding([8, 20])
+                            )
+                            .on_press(Message::ApplyUpdate)
+                            .style(move |_, status| {
+                                let hovered = status == button::Status::Hovered;
+                                button::Style {
+                                    background: Some(iced::Background::Color(
+                                        if hovered { Color { r: 0.95, g: 0.25, b: 0.25, a: 1.0 } } 
+                                        else { ACCENT }
+                                    )),
+                                    text_color: Color::WHITE,
+                                    border: Border { radius: 8.0.into(), ..Default::default() },
+                                    shadow: glow(Color { r: 1.0, g: 0.2, b: 0.2, a: 0.7 }, 15.0, glow_intensity),
+                                }
+                            }),
+                        ]
+                        .spacing(10)
+                        .push_maybe((!mandatory).then(|| {
+                            button(
+                                container(text("Позже").size(14)).padding([8, 20])
+                            )
+                            .on_press(Message::DeclineUpdate)
+                            .style(move |_, status| {
+                                let hovered = status == button::Status::Hovered;
+                                button::Style {
+                                    background: Some(iced::Background::Color(
+                                        if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } } 
+                                        else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                                    )),
+                                    text_color: TEXT_SECONDARY,
+                                    border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                                    ..Default::default()
+                                }
+                            })
+                        }))
+                    ].align_x(Alignment::Center)
+                )
+                .padding(20)
+                .style(move |_| container::Style {
+                    background: Some(iced::Background::Color(BG_CARD)),
+                    border: Border { radius: 10.0.into(), width: 1.0, color: ACCENT },
+                    ..Default::default()
+                })
+                .width(Length::Fill)
+                .into()
+            }
             LaunchState::Installing { step, progress } => {
                 let progress_percent = (*progress * 100.0) as u16;
                 let remaining = 100 - progress_percent;
                 
                 container(
                     column![
-                        text(step).size(14).color(TEXT_PRIMARY),
+                        row![
+                            text(self.spinner_glyph()).size(16).color(ACCENT),
+                            Space::with_width(10),
+                            text(step).size(14).color(TEXT_PRIMARY),
+                        ].align_y(Alignment::Center),
                         Space::with_height(10),
                         container(
                             row![
@@ -329,6 +541,23 @@ impl MinecraftLauncher {
                         }),
                         Space::with_height(5),
                         text(format!("{}%", (*progress * 100.0) as u32)).size(12).color(ACCENT),
+                        Space::with_height(10),
+                        button(
+                            container(text("Отмена").size(12)).padding([6, 14])
+                        )
+                        .on_press(Message::CancelLaunch)
+                        .style(move |_, status| {
+                            let hovered = status == button::Status::Hovered;
+                            button::Style {
+                                background: Some(iced::Background::Color(
+                                    if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } }
+                                    else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                                )),
+                                text_color: TEXT_SECONDARY,
+                                border: Border { radius: 6.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                                ..Default::default()
+                            }
+                        }),
                     ].align_x(Alignment::Center)
                 )
                 .padding(20)
@@ -340,6 +569,61 @@ impl MinecraftLauncher {
                 .width(Length::Fill)
                 .into()
             }
+            LaunchState::Launching => {
+                container(
+                    row![
+                        text(self.spinner_glyph()).size(18).color(ACCENT),
+                        Space::with_width(12),
+                        column![
+                            text("Запуск Minecraft").size(14).color(TEXT_PRIMARY),
+                            text("Java загружает игру и моды, это может занять до минуты").size(12).color(TEXT_SECONDARY),
+                        ].spacing(4),
+                    ].align_y(Alignment::Center)
+                )
+                .padding(15)
+                .style(move |_| container::Style {
+                    background: Some(iced::Background::Color(BG_CARD)),
+                    border: Border { radius: 10.0.into(), ..Default::default() },
+                    ..Default::default()
+                })
+                .width(Length::Fill)
+                .into()
+            }
+            LaunchState::Playing => {
+                container(
+                    row![
+                        column![
+                            text("Игра запущена").size(14).color(TEXT_PRIMARY),
+                            text(format!("в игре: {}", format_session_time(self.current_session_seconds))).size(12).color(ACCENT),
+                        ].spacing(4),
+                        Space::with_width(Length::Fill),
+                        button(
+                            container(text("Завершить игру").size(13)).padding([6, 14])
+                        )
+                        .on_press(Message::KillGame)
+                        .style(move |_, status| {
+                            let hovered = status == button::Status::Hovered;
+                            button::Style {
+                                background: Some(iced::Background::Color(
+                                    if hovered { Color { r: 0.4, g: 0.1, b: 0.1, a: 1.0 } }
+                                    else { Color { r: 0.3, g: 0.08, b: 0.08, a: 1.0 } }
+                                )),
+                                text_color: WARNING_TEXT,
+                                border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 0.5, g: 0.15, b: 0.15, a: 1.0 } },
+                                ..Default::default()
+                            }
+                        }),
+                    ].align_y(Alignment::Center)
+                )
+                .padding(15)
+                .style(move |_| container::Style {
+                    background: Some(iced::Background::Color(BG_CARD)),
+                    border: Border { radius: 10.0.into(), ..Default::default() },
+                    ..Default::default()
+                })
+                .width(Length::Fill)
+                .into()
+            }
             LaunchState::Error(e) => {
                 container(
                     text(format!("Ошибка: {}", e)).size(14).color(Color { r: 1.0, g: 0.4, b: 0.4, a: 1.0 })
@@ -353,10 +637,225 @@ impl MinecraftLauncher {
                 .width(Length::Fill)
                 .into()
             }
+            LaunchState::NetworkError { message, can_play_offline } => {
+                let offline_button: Element<'_, Message> = if *can_play_offline {
+                    button(
+                        container(text("Играть офлайн").size(13)).padding([6, 14])
+                    )
+                    .on_press(Message::LaunchOffline)
+                    .style(move |_, status| {
+                        let hovered = status == button::Status::Hovered;
+                        button::Style {
+                            background: Some(iced::Background::Color(
+                                if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } } 
+                                else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                            )),
+                            text_color: TEXT_PRIMARY,
+                            border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                            ..Default::default()
+                        }
+                    })
+                    .into()
+                } else {
+                    Space::with_height(0).into()
+                };
+                
+                container(
+                    column![
+                        text("Нет подключения к интернету").size(14).color(WARNING_TEXT),
+                        Space::with_height(6),
+                        text("Проверьте соединение и попробуйте снова.").size(12).color(TEXT_SECONDARY),
+                        text(message.as_str()).size(11).color(TEXT_SECONDARY),
+                        Space::with_height(10),
+                        offline_button,
+                    ]
+                )
+                .padding(15)
+                .style(move |_| container::Style {
+                    background: Some(iced::Background::Color(Color { r: 0.3, g: 0.1, b: 0.1, a: 0.8 })),
+                    border: Border { radius: 8.0.into(), ..Default::default() },
+                    ..Default::default()
+                })
+                .width(Length::Fill)
+                .into()
+            }
+            LaunchState::Idle if self.prefetch == PrefetchState::Ready(self.selected_version) || self.should_prefetch() => {
+                let (label, color) = if self.should_prefetch() {
+                    ("Подготовка файлов в фоне...", TEXT_SECONDARY)
+                } else {
+                    ("Готово к запуску", Color { r: 0.3, g: 0.9, b: 0.4, a: 1.0 })
+                };
+                container(
+                    text(label).size(14).color(color)
+                )
+                .padding(15)
+                .style(move |_| container::Style {
+                    background: Some(iced::Background::Color(BG_CARD)),
+                    border: Border { radius: 10.0.into(), ..Default::default() },
+                    ..Default::default()
+                })
+                .width(Length::Fill)
+                .into()
+            }
             _ => Space::with_height(0).into()
         }
     }
 
+    fn mod_list_view(&self) -> Element<'_, Message> {
+        let header = button(
+            row![
+                text(format!("МОДЫ ({})", self.installed_mods.len())).size(12).color(TEXT_SECONDARY),
+                Space::with_width(Length::Fill),
+                text(format!("Контент: {}", self.content_versions.mods.as_deref().unwrap_or("—"))).size(11).font(iced::Font::MONOSPACE).color(TEXT_SECONDARY),
+                Space::with_width(10),
+                text(if self.show_mod_list { "▲" } else { "▼" }).size(12).color(TEXT_SECONDARY),
+            ].align_y(Alignment::Center)
+        )
+        .on_press(Message::ToggleModList)
+        .padding(0)
+        .style(move |_, _| button::Style {
+            background: None,
+            text_color: TEXT_SECONDARY,
+            ..Default::default()
+        });
+
+        let list: Element<'_, Message> = if self.show_mod_list {
+            column![
+                Space::with_height(10),
+                text("Свои моды кладите в папку mods/local — синхронизация их не удаляет").size(11).color(TEXT_SECONDARY),
+                text(format!("Версии контента: {}", format_content_versions(&self.content_versions))).size(11).color(TEXT_SECONDARY),
+                Space::with_height(8),
+                scrollable(
+                    column(
+                        self.installed_mods.iter().map(|m| {
+                            let name_color = if m.incompatibility.is_some() { WARNING_TEXT } else { TEXT_PRIMARY };
+                            row![
+                                text(&m.name).size(12).color(name_color),
+                                Space::with_width(Length::Fill),
+                                text(m.version.clone().unwrap_or_default()).size(11).color(TEXT_SECONDARY),
+                            ]
+                            .padding([2, 10])
+                            .into()
+                        }).collect::<Vec<_>>()
+                    ).spacing(4)
+                ).height(150)
+            ].into()
+        } else {
+            Space::with_height(0).into()
+        };
+
+        let incompatible: Vec<String> = self.installed_mods.iter()
+            .filter_map(|m| m.incompatibility.as_ref().map(|req| {
+                format!("Мод {} несовместим с {} (требует {})", m.name, self.selected_version.display_name(), req)
+            }))
+            .collect();
+        
+        let warnings: Element<'_, Message> = if incompatible.is_empty() {
+            Space::with_height(0).into()
+        } else {
+            column![
+                Space::with_height(8),
+                column(
+                    incompatible.into_iter()
+                        .map(|line| text(line).size(12).color(WARNING_TEXT).into())
+                        .collect::<Vec<_>>()
+                ).spacing(2)
+            ].into()
+        };
+
+        container(
+            column![header, warnings, list]
+        )
+        .padding(15)
+        .style(move |_| container::Style {
+            background: Some(iced::Background::Color(BG_CARD)),
+            border: Border { radius: 10.0.into(), ..Default::default() },
+            ..Default::default()
+        })
+        .width(Length::Fill)
+        .into()
+    }
+
+    fn player_list_view(&self) -> Element<'_, Message> {
+        let names = &self.server_status.player_names;
+        if names.is_empty() {
+            if self.server_status.online && self.server_status.players_online > 0 {
+                return column![
+                    Space::with_height(8),
+                    text("список игроков скрыт").size(12).color(TEXT_SECONDARY),
+                ].into();
+            }
+            return Space::with_height(0).into();
+        }
+
+        let summary = if names.len() > COLLAPSED_PLAYER_NAMES {
+            format!("{} и ещё {}", names[..COLLAPSED_PLAYER_NAMES].join(", "), names.len() - COLLAPSED_PLAYER_NAMES)
+        } else {
+            names.join(", ")
+        };
+
+        let header = button(
+            row![
+                text(if self.show_player_list { "Игроки онлайн".to_string() } else { summary }).size(12).color(TEXT_SECONDARY),
+                Space::with_width(Length::Fill),
+                text(if self.show_player_list { "▲" } else { "▼" }).size(12).color(TEXT_SECONDARY),
+            ].align_y(Alignment::Center)
+        )
+        .on_press(Message::TogglePlayerList)
+        .padding(0)
+        .style(move |_, _| button::Style {
+            background: None,
+            text_color: TEXT_SECONDARY,
+            ..Default::default()
+        });
+
+        let list: Element<'_, Message> = if self.show_player_list {
+            scrollable(
+                column(
+                    names.iter().map(|name| {
+                        let head: Element<'_, Message> = match self.player_heads.get(name) {
+                            Some(Some(handle)) => image(handle.clone()).width(16).height(16).into(),
+                            _ => Space::new(16, 16).into(),
+                        };
+                        let details: Element<'_, Message> = match self.player_heads.get(name) {
+                            Some(Some(handle)) => container(
+                                row![
+                                    image(handle.clone()).width(32).height(32),
+                                    text(name.as_str()).size(13).color(TEXT_PRIMARY),
+                                ].spacing(8).align_y(Alignment::Center)
+                            )
+                            .padding(8)
+                            .style(move |_| container::Style {
+                                background: Some(iced::Background::Color(Color { r: 0.08, g: 0.08, b: 0.1, a: 0.98 })),
+                                border: Border { radius: 8.0.into(), width: 0.5, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.15 } },
+                                ..Default::default()
+                            })
+                            .into(),
+                            _ => Space::with_height(0).into(),
+                        };
+                        tooltip(
+                            row![
+                                head,
+                                text(name.as_str()).size(12).color(TEXT_PRIMARY),
+                            ].spacing(8).align_y(Alignment::Center).padding([2, 10]),
+                            details,
+                            tooltip::Position::Right,
+                        )
+                        .into()
+                    }).collect::<Vec<_>>()
+                ).spacing(4)
+            ).height(Length::Shrink).into()
+        } else {
+            Space::with_height(0).into()
+        };
+
+        column![
+            Space::with_height(8),
+            header,
+            list,
+        ].spacing(6).into()
+    }
+
     fn server_status_widget_view(&self) -> Element<'_, Message> {
         container(
             column![
@@ -375,18 +874,32 @@ impl MinecraftLauncher {
                     text(if self.server_status.online { "СЕРВЕР ОНЛАЙН" } else { "СЕРВЕР ОФЛАЙН" })
                         .size(12)
                         .color(TEXT_SECONDARY),
+                    Space::with_width(8),
+                    text(self.server_status.version_name.clone().unwrap_or_default())
+                        .size(11)
+                        .color(TEXT_SECONDARY),
                     Space::with_width(Length::Fill),
+                    match self.server_status.latency_ms.filter(|_| self.server_status.online) {
+                        Some(latency) => Element::from(
+                            text(format!("{} мс", latency))
+                                .size(11)
+                                .color(TEXT_SECONDARY)
+                        ),
+                        None => Element::from(Space::with_width(0)),
+                    },
+                    Space::with_width(10),
                     text(format!("{}/{}", self.server_status.players_online, self.server_status.players_max))
                         .size(14)
                         .color(if self.server_status.online { ACCENT } else { TEXT_SECONDARY }),
                 ].align_y(Alignment::Center),
-                if !self.server_status.player_names.is_empty() {
+                self.player_list_view(),
+                if self.server_status.requires_online_mode() {
                     Element::from(
                         column![
                             Space::with_height(8),
-                            text(self.server_status.player_names.join(", "))
+                            text("Сервер требует лицензионный аккаунт: в офлайн-режиме вас отключат при входе")
                                 .size(12)
-                                .color(TEXT_SECONDARY)
+                                .color(WARNING_TEXT)
                         ]
                     )
                 } else {
@@ -405,29 +918,53 @@ impl MinecraftLauncher {
     }
 }
 
-fn pick_list_style(_theme: &iced::Theme, _status: pick_list::Status) -> pick_list::Style {
-    pick_list::Style {
-        text_color: TEXT_PRIMARY,
-        placeholder_color: TEXT_SECONDARY,
-        handle_color: TEXT_SECONDARY,
-        background: iced::Background::Color(Color { r: 0.08, g: 0.08, b: 0.1, a: 0.95 }),
-        border: Border { radius: 8.0.into(), width: 0.5, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.15 } },
+const WARNING_TEXT: Color = Color { r: 1.0, g: 0.4, b: 0.4, a: 1.0 };
+const COLLAPSED_PLAYER_NAMES: usize = 5;
+
+impl std::fmt::Display for GameVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
     }
 }
 
-fn menu_style(_theme: &iced::Theme) -> iced::overlay::menu::Style {
-    iced::overlay::menu::Style {
-        text_color: TEXT_PRIMARY,
-        background: iced::Background::Color(Color { r: 0.08, g: 0.08, b: 0.1, a: 0.98 }),
-        border: Border { radius: 8.0.into(), width: 0.5, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.15 } },
-        selected_text_color: Color::WHITE,
-        selected_background: iced::Background::Color(ACCENT),
+impl std::fmt::Display for DownloadKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DownloadKind::Game => write!(f, "Загрузка"),
+            DownloadKind::Fabric => write!(f, "Fabric"),
+            DownloadKind::Mods => write!(f, "Моды"),
+            DownloadKind::Shaderpacks => write!(f, "Шейдеры"),
+            DownloadKind::Resourcepacks => write!(f, "Текстуры"),
+        }
     }
 }
 
-impl std::fmt::Display for GameVersion {
+impl std::fmt::Display for InstallStep {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.display_name())
+        match self {
+            InstallStep::Preparing => write!(f, "Подготовка..."),
+            InstallStep::CheckingInstall => write!(f, "Проверка установки..."),
+            InstallStep::WaitingForInstall => write!(f, "Ожидание завершения другой установки..."),
+            InstallStep::Installing(version) => write!(f, "Установка {}...", version.display_name()),
+            InstallStep::UpdatingLoader(loader_version) => write!(f, "Обновление Fabric Loader до {}...", loader_version),
+            InstallStep::CheckingJava => write!(f, "Проверка Java..."),
+            InstallStep::DownloadingJava(version) => write!(f, "Загрузка Java {}...", version),
+            InstallStep::ExtractingJava { version, percent } => write!(f, "Распаковка Java {}... {}%", version, percent),
+            InstallStep::FetchingVersionInfo => write!(f, "Загрузка информации о версии..."),
+            InstallStep::ResumingInstall => write!(f, "Продолжение прерванной установки..."),
+            InstallStep::InstallingFabric => write!(f, "Установка Fabric..."),
+            InstallStep::FetchingFabricProfile => write!(f, "Загрузка профиля Fabric..."),
+            InstallStep::CheckingMods => write!(f, "Проверка модов..."),
+            InstallStep::ModsUnpublished(version) => write!(f, "Моды для {} не опубликованы", version.display_name()),
+            InstallStep::ModsRemoved(names) => write!(f, "Убраны моды, которых нет на сервере: {} (перемещены в mods/{}, свои моды кладите в mods/{})", names.join(", "), REMOVED_MODS_FOLDER, LOCAL_MODS_FOLDER),
+            InstallStep::Downloading { kind, done, total } => write!(f, "{}: {:.1} / {:.1} МБ", kind, *done as f64 / 1_048_576.0, *total as f64 / 1_048_576.0),
+            InstallStep::PreparingAssets { done, total } => write!(f, "Подготовка ресурсов: {}/{}", done, total),
+            InstallStep::VerifyingFiles { done, total } => write!(f, "Проверка файлов: {}/{}", done, total),
+            InstallStep::SyncingContent => write!(f, "Синхронизация модов, шейдеров и текстур..."),
+            InstallStep::ConfiguringShaders => write!(f, "Настройка шейдеров..."),
+            InstallStep::Launching => write!(f, "Запуск игры..."),
+            InstallStep::Complete => write!(f, "Установка завершена!"),
+        }
     }
 }
 
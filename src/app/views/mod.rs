@@ -1,3 +1,4 @@
 mod dashboard;
+mod resourcepacks;
 mod settings;
 mod statistics;
@@ -0,0 +1,64 @@
+use iced::{
+    Alignment, Border, Color, Element, Length,
+    widget::{button, checkbox, column, container, row, text, Column, Space},
+};
+use crate::app::state::{Message, MinecraftLauncher};
+use crate::app::styles::{TEXT_PRIMARY, TEXT_SECONDARY, checkbox_style};
+
+impl MinecraftLauncher {
+    pub fn resourcepacks_view(&self) -> Element<'_, Message> {
+        let list: Element<'_, Message> = if self.resourcepacks.is_empty() {
+            text("В папке resourcepacks нет текстурпаков").size(12).color(TEXT_SECONDARY).into()
+        } else {
+            Column::with_children(self.resourcepacks.iter().map(|pack| {
+                let name = pack.name.clone();
+                let toggle = checkbox(pack.name.as_str(), pack.enabled)
+                    .on_toggle(move |enabled| Message::ResourcepackToggled(name.clone(), enabled))
+                    .text_size(13)
+                    .style(checkbox_style);
+
+                let controls: Element<'_, Message> = if pack.enabled {
+                    row![
+                        order_button("▲", Message::ResourcepackMoved(pack.name.clone(), true)),
+                        order_button("▼", Message::ResourcepackMoved(pack.name.clone(), false)),
+                    ].spacing(4).into()
+                } else {
+                    Space::with_width(0).into()
+                };
+
+                row![
+                    container(toggle).width(Length::Fill),
+                    controls,
+                ].align_y(Alignment::Center).into()
+            }))
+            .spacing(6)
+            .into()
+        };
+
+        column![
+            text("ТЕКСТУРПАКИ").size(12).color(TEXT_SECONDARY),
+            list,
+            text("Пак ниже в списке перекрывает паки выше").size(11).color(TEXT_SECONDARY),
+        ].spacing(8).into()
+    }
+}
+
+fn order_button(label: &str, message: Message) -> Element<'_, Message> {
+    button(
+        container(text(label).size(10)).padding([2, 6])
+    )
+    .on_press(message)
+    .style(move |_, status| {
+        let hovered = status == button::Status::Hovered;
+        button::Style {
+            background: Some(iced::Background::Color(
+                if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } } 
+                else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+            )),
+            text_color: TEXT_PRIMARY,
+            border: Border { radius: 6.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+            ..Default::default()
+        }
+    })
+    .into()
+}
@@ -1,13 +1,16 @@
 use iced::{
     Border, Color, Element, Length,
-    widget::{button, column, container, row, slider, text, text_input, Space},
+    widget::{button, checkbox, column, container, pick_list, row, scrollable, slider, text, text_input, Space},
 };
-use crate::app::state::{Message, MinecraftLauncher};
-use crate::app::styles::{ACCENT, BG_CARD, TEXT_PRIMARY, TEXT_SECONDARY, input_style, slider_style};
+use std::sync::atomic::Ordering;
+use crate::app::state::{JavaStatus, LaunchState, Message, MinecraftLauncher, ShaderpackChoice, MAX_RAM_32BIT_MB};
+use crate::app::utils::format_ram;
+use crate::app::styles::{ACCENT, BG_CARD, TEXT_PRIMARY, TEXT_SECONDARY, checkbox_style, input_style, menu_style, pick_list_style, slider_style};
+use crate::minecraft::{DownloadKind, GarbageCollector, QuickPlay, KEEP_LOG_FILES, ShaderQuality, SyncPolicy, is_valid_env_var_name, validate_content_source_url, validate_proxy_url};
 
 impl MinecraftLauncher {
     pub fn settings_view(&self) -> Element<'_, Message> {
-        column![
+        scrollable(column![
             text("НАСТРОЙКИ").size(36).font(iced::Font::MONOSPACE).style(move |_| text::Style { color: Some(TEXT_PRIMARY) }),
             Space::with_height(30),
             
@@ -25,15 +28,173 @@ impl MinecraftLauncher {
 
                     column![
                         row![
-                            text("ПАМЯТЬ (ГБ)").size(12).color(TEXT_SECONDARY),
+                            text("ПАМЯТЬ").size(12).color(TEXT_SECONDARY),
                             Space::with_width(Length::Fill),
-                            text(format!("{}", self.ram_gb)).size(14).color(ACCENT),
+                            text(format_ram(self.ram_mb)).size(14).color(ACCENT),
                         ],
-                        slider(2..=16, self.ram_gb, Message::RamChanged)
-                            .step(1u32)
-                            .style(slider_style)
+                        if self.runtime_32bit {
+                            slider(512..=MAX_RAM_32BIT_MB, self.ram_mb.min(MAX_RAM_32BIT_MB), Message::RamChanged)
+                                .step(256u32)
+                                .style(slider_style)
+                        } else {
+                            slider(2048..=16384, self.ram_mb, Message::RamChanged)
+                                .step(512u32)
+                                .style(slider_style)
+                        },
+                        if self.runtime_32bit {
+                            Element::from(text("Обнаружена 32-битная система или Java: больше 1.5 ГБ выделить нельзя. Установите 64-битную Java для нормальной работы").size(11).color(Color { r: 1.0, g: 0.4, b: 0.4, a: 1.0 }))
+                        } else {
+                            Element::from(Space::with_height(0))
+                        },
                     ].spacing(12),
 
+                    Space::with_height(20),
+
+                    column![
+                        checkbox("Оптимизированные флаги JVM", self.optimized_jvm_flags)
+                            .on_toggle(Message::OptimizedJvmFlagsToggled)
+                            .text_size(14)
+                            .style(checkbox_style),
+                        text("Вся память выделяется сразу и включается настройка G1 от Aikar: меньше фризов в модах, но лаунчер сразу займёт всю выбранную ОЗУ").size(11).color(TEXT_SECONDARY),
+                    ].spacing(8),
+
+                    Space::with_height(20),
+
+                    self.garbage_collector_view(),
+
+                    Space::with_height(20),
+
+                    self.java_runtime_view(),
+
+                    Space::with_height(20),
+
+                    column![
+                        row![
+                            text("ПОДСВЕТКА").size(12).color(TEXT_SECONDARY),
+                            Space::with_width(Length::Fill),
+                            text(if self.glow_intensity <= 0.0 { "Выкл".to_string() } else { format!("{}%", (self.glow_intensity * 100.0).round() as u32) }).size(14).color(ACCENT),
+                        ],
+                        slider(0.0..=1.0, self.glow_intensity, Message::GlowIntensityChanged)
+                            .step(0.1)
+                            .style(slider_style),
+                    ].spacing(12),
+
+                    Space::with_height(20),
+
+                    self.quick_play_view(),
+
+                    Space::with_height(20),
+
+                    self.shaderpack_settings_view(),
+
+                    Space::with_height(20),
+
+                    self.resourcepacks_view(),
+
+                    Space::with_height(20),
+
+                    column![
+                        text("СИНХРОНИЗАЦИЯ").size(12).color(TEXT_SECONDARY),
+                        checkbox("Готовить игру заранее", self.prefetch_content)
+                            .on_toggle(Message::PrefetchContentToggled)
+                            .text_size(14)
+                            .style(checkbox_style),
+                        checkbox("Быстрый запуск (обновления скачиваются во время игры)", self.fast_launch)
+                            .on_toggle(Message::FastLaunchToggled)
+                            .text_size(14)
+                            .style(checkbox_style),
+                        checkbox("Шейдеры с сервера", self.sync_shaderpacks)
+                            .on_toggle(Message::SyncShaderpacksToggled)
+                            .text_size(14)
+                            .style(checkbox_style),
+                        checkbox("Текстурпаки с сервера", self.sync_resourcepacks)
+                            .on_toggle(Message::SyncResourcepacksToggled)
+                            .text_size(14)
+                            .style(checkbox_style),
+                    ].spacing(8),
+
+                    Space::with_height(20),
+
+                    self.sync_policy_view(),
+
+                    Space::with_height(20),
+
+                    column![
+                        text("УВЕДОМЛЕНИЯ").size(12).color(TEXT_SECONDARY),
+                        checkbox("Уведомлять о запуске, вылете и обновлениях", self.notifications_enabled)
+                            .on_toggle(Message::NotificationsToggled)
+                            .text_size(14)
+                            .style(checkbox_style),
+                        checkbox("Звук уведомлений", self.notification_sound)
+                            .on_toggle_maybe(self.notifications_enabled.then_some(Message::NotificationSoundToggled))
+                            .text_size(14)
+                            .style(checkbox_style),
+                        checkbox("Предлагать переустановку после повторных вылетов", self.crash_dialog_enabled)
+                            .on_toggle(Message::CrashDialogToggled)
+                            .text_size(14)
+                            .style(checkbox_style),
+                    ].spacing(8),
+
+                    Space::with_height(20),
+
+                    self.proxy_settings_view(),
+
+                    Space::with_height(20),
+
+                    self.content_source_view(),
+
+                    Space::with_height(20),
+
+                    self.fabric_loader_view(),
+
+                    Space::with_height(20),
+
+                    column![
+                        text("ДОПОЛНИТЕЛЬНО").size(12).color(TEXT_SECONDARY),
+                        row![
+                            text("Таймаут подключения").size(13).color(TEXT_PRIMARY),
+                            Space::with_width(Length::Fill),
+                            text(format!("{} сек", self.connect_timeout_secs)).size(14).color(ACCENT),
+                        ],
+                        slider(5..=60, self.connect_timeout_secs, Message::ConnectTimeoutChanged)
+                            .step(5u32)
+                            .style(slider_style),
+                        row![
+                            text("Таймаут загрузки файла").size(13).color(TEXT_PRIMARY),
+                            Space::with_width(Length::Fill),
+                            text(format!("{} сек", self.download_timeout_secs)).size(14).color(ACCENT),
+                        ],
+                        slider(60..=900, self.download_timeout_secs, Message::DownloadTimeoutChanged)
+                            .step(30u32)
+                            .style(slider_style),
+                        row![
+                            text("Ограничение скорости загрузки, МБ/с").size(13).color(TEXT_PRIMARY),
+                            Space::with_width(Length::Fill),
+                            text_input("Без ограничения", &self.download_limit_input)
+                                .on_input(Message::DownloadLimitChanged)
+                                .padding(8)
+                                .width(140)
+                                .style(input_style),
+                        ].align_y(iced::Alignment::Center),
+                        self.download_limit_hint(),
+                    ].spacing(10),
+
+                    Space::with_height(20),
+
+                    self.env_vars_view(),
+
+                    Space::with_height(20),
+
+                    self.backup_settings_view(),
+
+                    Space::with_height(20),
+
+                    self.diagnostics_view(),
+
+                    Space::with_height(20),
+
+                    self.log_files_view(),
+
                     Space::with_height(30),
 
                     column![
@@ -57,6 +218,44 @@ impl MinecraftLauncher {
                         }),
                         Space::with_height(5),
                         text("Удалит все файлы игры для переустановки").size(11).color(TEXT_SECONDARY),
+                        Space::with_height(12),
+                        button(
+                            container(text("Восстановить Fabric").size(14)).padding([10, 20])
+                        )
+                        .on_press_maybe((!self.repairing).then_some(Message::RepairFabric))
+                        .style(move |_, status| {
+                            let hovered = status == button::Status::Hovered;
+                            button::Style {
+                                background: Some(iced::Background::Color(
+                                    if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } } 
+                                    else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                                )),
+                                text_color: TEXT_PRIMARY,
+                                border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                                ..Default::default()
+                            }
+                        }),
+                        Space::with_height(5),
+                        text("Переустановит только Fabric и моды, не трогая Minecraft").size(11).color(TEXT_SECONDARY),
+                        Space::with_height(12),
+                        button(
+                            container(text("Проверить файлы").size(14)).padding([10, 20])
+                        )
+                        .on_press_maybe((!self.verifying && !self.repairing).then_some(Message::VerifyFiles))
+                        .style(move |_, status| {
+                            let hovered = status == button::Status::Hovered;
+                            button::Style {
+                                background: Some(iced::Background::Color(
+                                    if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } } 
+                                    else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                                )),
+                                text_color: TEXT_PRIMARY,
+                                border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                                ..Default::default()
+                            }
+                        }),
+                        Space::with_height(5),
+                        self.verify_status_view(),
                     ].spacing(0),
                 ]
                 .padding(30)
@@ -68,6 +267,521 @@ impl MinecraftLauncher {
             })
             .width(Length::Fill)
             .max_width(500)
-        ].into()
+        ])
+        .height(Length::Fill)
+        .into()
+    }
+
+    fn quick_play_view(&self) -> Element<'_, Message> {
+        let enabled = self.quick_play != QuickPlay::Menu;
+        let mut options = vec![QuickPlay::Server];
+        options.extend(self.available_worlds.iter().cloned().map(QuickPlay::World));
+
+        column![
+            text("БЫСТРЫЙ ВХОД").size(12).color(TEXT_SECONDARY),
+            checkbox("Сразу заходить в игру, минуя меню", enabled)
+                .on_toggle(Message::QuickPlayToggled)
+                .text_size(14)
+                .style(checkbox_style),
+            if enabled {
+                Element::from(
+                    pick_list(options, Some(self.quick_play.clone()), Message::QuickPlaySelected)
+                        .width(Length::Fill)
+                        .padding(12)
+                        .style(pick_list_style)
+                        .menu_style(menu_style)
+                )
+            } else {
+                Element::from(Space::with_height(0))
+            },
+        ].spacing(8).into()
+    }
+
+    fn shaderpack_settings_view(&self) -> Element<'_, Message> {
+        let mut options = vec![ShaderpackChoice::Disabled, ShaderpackChoice::Auto];
+        options.extend(self.available_shaderpacks.iter().cloned().map(ShaderpackChoice::Pack));
+        let selected = match (&self.shaderpack, self.shader_quality) {
+            (_, ShaderQuality::Off) => ShaderpackChoice::Disabled,
+            (Some(name), _) => ShaderpackChoice::Pack(name.clone()),
+            (None, _) => ShaderpackChoice::Auto,
+        };
+
+        column![
+            text("ШЕЙДЕРПАК").size(12).color(TEXT_SECONDARY),
+            pick_list(options, Some(selected), Message::ShaderpackSelected)
+                .width(Length::Fill)
+                .padding(12)
+                .style(pick_list_style)
+                .menu_style(menu_style),
+            text("Автоматически — пак, загруженный для выбранного качества, иначе первый в папке shaderpacks").size(11).color(TEXT_SECONDARY),
+        ].spacing(8).into()
+    }
+
+    fn java_runtime_view(&self) -> Element<'_, Message> {
+        let java_version = self.selected_version.java_version();
+        let current = match &self.java_status {
+            JavaStatus::Ready(path) => format!("Используется: {}", path.display()),
+            JavaStatus::Installing { .. } => format!("Java {} устанавливается...", java_version),
+            _ if self.use_system_java => format!("Подходящая системная Java {} не найдена, встроенная будет скачана при запуске", java_version),
+            _ => format!("Java {} будет скачана при запуске", java_version),
+        };
+
+        column![
+            text("JAVA").size(12).color(TEXT_SECONDARY),
+            checkbox("Использовать системную Java, если доступна", self.use_system_java)
+                .on_toggle(Message::UseSystemJavaToggled)
+                .text_size(14)
+                .style(checkbox_style),
+            text(current).size(11).color(TEXT_SECONDARY),
+        ].spacing(8).into()
+    }
+
+    fn sync_policy_view(&self) -> Element<'_, Message> {
+        let policy_row = |label: &'static str, kind: DownloadKind, policy: SyncPolicy| {
+            row![
+                text(label).size(13).color(TEXT_PRIMARY),
+                Space::with_width(Length::Fill),
+                pick_list(SyncPolicy::all(), Some(policy), move |policy| Message::SyncPolicyChanged(kind, policy))
+                    .width(180)
+                    .padding(8)
+                    .style(pick_list_style)
+                    .menu_style(menu_style),
+            ].align_y(iced::Alignment::Center)
+        };
+
+        column![
+            text("ЛИШНИЕ ФАЙЛЫ").size(12).color(TEXT_SECONDARY),
+            policy_row("Моды", DownloadKind::Mods, self.mods_sync_policy),
+            policy_row("Шейдеры", DownloadKind::Shaderpacks, self.shaderpacks_sync_policy),
+            policy_row("Текстурпаки", DownloadKind::Resourcepacks, self.resourcepacks_sync_policy),
+            text("Слияние сохраняет ваши файлы, точная копия убирает всё, чего нет на сервере").size(11).color(TEXT_SECONDARY),
+        ].spacing(8).into()
+    }
+
+    fn garbage_collector_view(&self) -> Element<'_, Message> {
+        let java_version = self.selected_version.java_version();
+        let options: Vec<GarbageCollector> = GarbageCollector::all()
+            .into_iter()
+            .filter(|gc| gc.supported_by(java_version))
+            .collect();
+
+        column![
+            text("СБОРЩИК МУСОРА").size(12).color(TEXT_SECONDARY),
+            pick_list(options, Some(self.garbage_collector.resolve(java_version)), Message::GarbageCollectorChanged)
+                .width(Length::Fill)
+                .padding(12)
+                .style(pick_list_style)
+                .menu_style(menu_style),
+            text("ZGC и Shenandoah дают меньше пауз на больших объёмах памяти, флаги Aikar работают только с G1").size(11).color(TEXT_SECONDARY),
+        ].spacing(8).into()
+    }
+
+    fn proxy_settings_view(&self) -> Element<'_, Message> {
+        let status: Element<'_, Message> = if let Err(e) = validate_proxy_url(&self.proxy_url) {
+            text(e.to_string()).size(11).color(Color { r: 1.0, g: 0.4, b: 0.4, a: 1.0 }).into()
+        } else if self.proxy_checking {
+            text("Проверка соединения...").size(11).color(TEXT_SECONDARY).into()
+        } else {
+            match &self.proxy_check {
+                Some(Ok(())) => text("Соединение установлено").size(11).color(Color { r: 0.3, g: 0.9, b: 0.4, a: 1.0 }).into(),
+                Some(Err(e)) => text(format!("Нет соединения: {}", e)).size(11).color(Color { r: 1.0, g: 0.4, b: 0.4, a: 1.0 }).into(),
+                None => text("Пусто — используются HTTP_PROXY / HTTPS_PROXY / NO_PROXY из системы").size(11).color(TEXT_SECONDARY).into(),
+            }
+        };
+
+        column![
+            text("ПРОКСИ").size(12).color(TEXT_SECONDARY),
+            row![
+                text_input("http://host:port", &self.proxy_url)
+                    .on_input(Message::ProxyUrlChanged)
+                    .padding(14)
+                    .style(input_style),
+                Space::with_width(10),
+                button(
+                    container(text("Проверить").size(14)).padding([10, 16])
+                )
+                .on_press_maybe((!self.proxy_checking).then_some(Message::CheckProxy))
+                .style(move |_, status| {
+                    let hovered = status == button::Status::Hovered;
+                    button::Style {
+                        background: Some(iced::Background::Color(
+                            if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } } 
+                            else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                        )),
+                        text_color: TEXT_PRIMARY,
+                        border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                        ..Default::default()
+                    }
+                }),
+            ].align_y(iced::Alignment::Center),
+            status,
+        ].spacing(8).into()
+    }
+
+    fn content_source_view(&self) -> Element<'_, Message> {
+        let status: Element<'_, Message> = match validate_content_source_url(&self.content_source_url) {
+            Err(e) => text(e.to_string()).size(11).color(Color { r: 1.0, g: 0.4, b: 0.4, a: 1.0 }).into(),
+            Ok(()) if self.content_source_url.trim().is_empty() => {
+                text("Пусто — моды и паки загружаются из репозитория PRISSET/mods на GitHub").size(11).color(TEXT_SECONDARY).into()
+            }
+            Ok(()) => {
+                text("Список файлов берётся из <адрес>/<папка версии>/manifest.json").size(11).color(TEXT_SECONDARY).into()
+            }
+        };
+
+        column![
+            text("ИСТОЧНИК КОНТЕНТА").size(12).color(TEXT_SECONDARY),
+            text_input("https://example.com/mods", &self.content_source_url)
+                .on_input(Message::ContentSourceUrlChanged)
+                .padding(14)
+                .style(input_style),
+            status,
+        ].spacing(8).into()
+    }
+
+    fn verify_status_view(&self) -> Element<'_, Message> {
+        match (&self.launch_state, &self.verify_report) {
+            (LaunchState::Installing { step, .. }, _) if self.verifying => {
+                text(step.clone()).size(11).color(ACCENT).into()
+            }
+            (_, Some(Ok(report))) => {
+                let color = if report.fixed < report.corrupted {
+                    Color { r: 1.0, g: 0.4, b: 0.4, a: 1.0 }
+                } else {
+                    Color { r: 0.3, g: 0.9, b: 0.4, a: 1.0 }
+                };
+                text(format!("Проверено {} файлов, повреждено {}, исправлено {}", report.checked, report.corrupted, report.fixed)).size(11).color(color).into()
+            }
+            (_, Some(Err(e))) => {
+                text(format!("Проверка не удалась: {}", e)).size(11).color(Color { r: 1.0, g: 0.4, b: 0.4, a: 1.0 }).into()
+            }
+            _ => text("Сверит контрольные суммы файлов игры и модов и перекачает повреждённые").size(11).color(TEXT_SECONDARY).into(),
+        }
+    }
+
+    fn download_limit_hint(&self) -> Element<'_, Message> {
+        let input = self.download_limit_input.trim();
+        if !input.is_empty() && !input.parse::<u32>().is_ok_and(|mb| mb > 0) {
+            text("Введите целое число больше нуля или оставьте поле пустым").size(11).color(Color { r: 1.0, g: 0.4, b: 0.4, a: 1.0 }).into()
+        } else {
+            text("Лимит общий для всех одновременных загрузок").size(11).color(TEXT_SECONDARY).into()
+        }
+    }
+
+    fn fabric_loader_view(&self) -> Element<'_, Message> {
+        let status: Element<'_, Message> = if self.fabric_loader_checking {
+            text("Проверка версии...").size(11).color(TEXT_SECONDARY).into()
+        } else if let Some(e) = &self.fabric_loader_error {
+            text(e.clone()).size(11).color(Color { r: 1.0, g: 0.4, b: 0.4, a: 1.0 }).into()
+        } else if let Some(pinned) = &self.fabric_loader_override {
+            text(format!("Закреплена версия {} для всех версий игры", pinned)).size(11).color(Color { r: 0.3, g: 0.9, b: 0.4, a: 1.0 }).into()
+        } else {
+            text(format!("Пусто — используется версия по умолчанию ({})", self.selected_version.fabric_loader_version())).size(11).color(TEXT_SECONDARY).into()
+        };
+        let small_button = |label: &'static str, message: Option<Message>| {
+            button(
+                container(text(label).size(14)).padding([10, 16])
+            )
+            .on_press_maybe(message)
+            .style(move |_, status| {
+                let hovered = status == button::Status::Hovered;
+                button::Style {
+                    background: Some(iced::Background::Color(
+                        if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } } 
+                        else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                    )),
+                    text_color: TEXT_PRIMARY,
+                    border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                    ..Default::default()
+                }
+            })
+        };
+        let game_running = self.game_running.load(Ordering::SeqCst);
+        let editable = !self.fabric_loader_checking && !game_running;
+
+        column![
+            text("FABRIC LOADER").size(12).color(TEXT_SECONDARY),
+            row![
+                text_input(self.selected_version.fabric_loader_version(), &self.fabric_loader_input)
+                    .on_input_maybe(editable.then_some(Message::FabricLoaderInputChanged))
+                    .on_submit(Message::ApplyFabricLoader)
+                    .padding(14)
+                    .style(input_style),
+                Space::with_width(10),
+                small_button("Применить", editable.then_some(Message::ApplyFabricLoader)),
+                Space::with_width(10),
+                small_button("Сбросить", (editable && self.fabric_loader_override.is_some()).then_some(Message::ResetFabricLoader)),
+            ].align_y(iced::Alignment::Center),
+            status,
+        ].spacing(8).into()
+    }
+
+    fn env_vars_view(&self) -> Element<'_, Message> {
+        let small_button = |label: &'static str, message: Message| {
+            button(
+                container(text(label).size(14)).padding([10, 16])
+            )
+            .on_press(message)
+            .style(move |_, status| {
+                let hovered = status == button::Status::Hovered;
+                button::Style {
+                    background: Some(iced::Background::Color(
+                        if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } } 
+                        else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                    )),
+                    text_color: TEXT_PRIMARY,
+                    border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                    ..Default::default()
+                }
+            })
+        };
+
+        let mut entries = column![].spacing(8);
+        for (index, (name, value)) in self.env_vars.iter().enumerate() {
+            entries = entries.push(
+                row![
+                    text_input("ИМЯ", name)
+                        .on_input(move |name| Message::EnvVarNameChanged(index, name))
+                        .padding(10)
+                        .width(Length::FillPortion(2))
+                        .style(input_style),
+                    Space::with_width(8),
+                    text_input("значение", value)
+                        .on_input(move |value| Message::EnvVarValueChanged(index, value))
+                        .padding(10)
+                        .width(Length::FillPortion(3))
+                        .style(input_style),
+                    Space::with_width(8),
+                    small_button("✕", Message::RemoveEnvVar(index)),
+                ].align_y(iced::Alignment::Center)
+            );
+        }
+
+        let has_invalid = self.env_vars.iter().any(|(name, _)| !is_valid_env_var_name(name.trim()));
+        let status = if has_invalid {
+            text("Пустые имена и имена со знаком = не передаются игре").size(11).color(Color { r: 1.0, g: 0.4, b: 0.4, a: 1.0 })
+        } else {
+            text("Передаются процессу игры при запуске, например __GL_THREADED_OPTIMIZATIONS").size(11).color(TEXT_SECONDARY)
+        };
+
+        column![
+            text("ПЕРЕМЕННЫЕ ОКРУЖЕНИЯ").size(12).color(TEXT_SECONDARY),
+            entries,
+            small_button("Добавить переменную", Message::AddEnvVar),
+            status,
+        ].spacing(8).into()
+    }
+
+    fn diagnostics_view(&self) -> Element<'_, Message> {
+        let header = button(
+            row![
+                text("ДИАГНОСТИКА").size(12).color(TEXT_SECONDARY),
+                Space::with_width(Length::Fill),
+                text(if self.show_diagnostics { "▲" } else { "▼" }).size(12).color(TEXT_SECONDARY),
+            ].align_y(iced::Alignment::Center)
+        )
+        .on_press(Message::ToggleDiagnostics)
+        .padding(0)
+        .style(move |_, _| button::Style {
+            background: None,
+            text_color: TEXT_SECONDARY,
+            ..Default::default()
+        });
+
+        if !self.show_diagnostics {
+            return header.into();
+        }
+
+        let body: Element<'_, Message> = match &self.diagnostics {
+            Some(report) => column![
+                text(report.as_str()).size(12).color(TEXT_PRIMARY),
+                button(
+                    container(text("Скопировать диагностику").size(14)).padding([10, 16])
+                )
+                .on_press(Message::CopyDiagnostics)
+                .style(move |_, status| {
+                    let hovered = status == button::Status::Hovered;
+                    button::Style {
+                        background: Some(iced::Background::Color(
+                            if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } } 
+                            else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                        )),
+                        text_color: TEXT_PRIMARY,
+                        border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                        ..Default::default()
+                    }
+                }),
+            ].spacing(10).into(),
+            None => text("Сбор информации о системе...").size(11).color(TEXT_SECONDARY).into(),
+        };
+
+        column![header, body].spacing(8).into()
+    }
+
+    fn log_files_view(&self) -> Element<'_, Message> {
+        let header = button(
+            row![
+                text("ЛОГИ И ОТЧЁТЫ О ВЫЛЕТАХ").size(12).color(TEXT_SECONDARY),
+                Space::with_width(Length::Fill),
+                text(if self.show_log_files { "▲" } else { "▼" }).size(12).color(TEXT_SECONDARY),
+            ].align_y(iced::Alignment::Center)
+        )
+        .on_press(Message::ToggleLogFiles)
+        .padding(0)
+        .style(move |_, _| button::Style {
+            background: None,
+            text_color: TEXT_SECONDARY,
+            ..Default::default()
+        });
+
+        if !self.show_log_files {
+            return header.into();
+        }
+
+        let Some(files) = &self.log_files else {
+            return column![
+                header,
+                text("Поиск файлов...").size(11).color(TEXT_SECONDARY),
+            ].spacing(8).into();
+        };
+
+        let action_button = |label: &'static str, message: Message| {
+            button(
+                container(text(label).size(14)).padding([10, 16])
+            )
+            .on_press(message)
+            .style(move |_, status| {
+                let hovered = status == button::Status::Hovered;
+                button::Style {
+                    background: Some(iced::Background::Color(
+                        if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } } 
+                        else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                    )),
+                    text_color: TEXT_PRIMARY,
+                    border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                    ..Default::default()
+                }
+            })
+        };
+
+        let crash_reports = files.iter().filter(|file| file.crash_report).count();
+        let total_size: u64 = files.iter().map(|file| file.size).sum();
+        let summary = text(format!(
+            "Отчётов о вылетах: {}, архивов логов: {}, всего {:.1} МБ",
+            crash_reports,
+            files.len() - crash_reports,
+            total_size as f64 / 1_048_576.0
+        )).size(12).color(TEXT_PRIMARY);
+
+        let mut list = column![].spacing(4);
+        for file in files {
+            let modified = chrono::DateTime::<chrono::Local>::from(file.modified).format("%d.%m.%Y %H:%M");
+            list = list.push(
+                row![
+                    text(modified.to_string()).size(11).color(TEXT_SECONDARY),
+                    Space::with_width(10),
+                    text(file.name()).size(11).color(if file.crash_report { Color { r: 1.0, g: 0.6, b: 0.6, a: 1.0 } } else { TEXT_PRIMARY }).width(Length::Fill),
+                    text(format!("{} КБ", file.size.div_ceil(1024))).size(11).color(TEXT_SECONDARY),
+                ]
+            );
+        }
+
+        let actions: Element<'_, Message> = if self.confirm_log_cleanup {
+            column![
+                text(format!("Удалить всё, кроме последних {} отчётов и {} архивов логов?", KEEP_LOG_FILES, KEEP_LOG_FILES)).size(12).color(TEXT_PRIMARY),
+                row![
+                    action_button("Удалить", Message::ConfirmCleanOldLogs),
+                    action_button("Отмена", Message::CancelCleanOldLogs),
+                ].spacing(10),
+            ].spacing(8).into()
+        } else {
+            action_button("Очистить старые логи", Message::CleanOldLogs).into()
+        };
+
+        let status: Element<'_, Message> = match &self.log_cleanup_status {
+            Some(message) => text(message.as_str()).size(11).color(Color { r: 0.3, g: 0.9, b: 0.4, a: 1.0 }).into(),
+            None => text("Файлы текущей игровой сессии и latest.log не удаляются").size(11).color(TEXT_SECONDARY).into(),
+        };
+
+        column![
+            header,
+            summary,
+            if files.is_empty() {
+                Element::from(text("Отчётов о вылетах и архивов логов нет").size(11).color(TEXT_SECONDARY))
+            } else {
+                scrollable(list).height(150).into()
+            },
+            actions,
+            status,
+        ].spacing(8).into()
+    }
+
+    fn backup_settings_view(&self) -> Element<'_, Message> {
+        let game_running = self.game_running.load(Ordering::SeqCst);
+        let backup_button = |label: &'static str, message: Option<Message>| {
+            button(
+                container(text(label).size(14)).padding([10, 16])
+            )
+            .on_press_maybe(message)
+            .style(move |_, status| {
+                let hovered = status == button::Status::Hovered;
+                button::Style {
+                    background: Some(iced::Background::Color(
+                        if hovered { Color { r: 0.25, g: 0.25, b: 0.28, a: 1.0 } } 
+                        else { Color { r: 0.15, g: 0.15, b: 0.18, a: 1.0 } }
+                    )),
+                    text_color: TEXT_PRIMARY,
+                    border: Border { radius: 8.0.into(), width: 1.0, color: Color { r: 1.0, g: 1.0, b: 1.0, a: 0.1 } },
+                    ..Default::default()
+                }
+            })
+        };
+
+        let status: Element<'_, Message> = match &self.backup_status {
+            Some(Ok(message)) => text(message.as_str()).size(11).color(Color { r: 0.3, g: 0.9, b: 0.4, a: 1.0 }).into(),
+            Some(Err(e)) => text(e.as_str()).size(11).color(Color { r: 1.0, g: 0.4, b: 0.4, a: 1.0 }).into(),
+            None => text("Настройки и статистика игрового времени в одном файле").size(11).color(TEXT_SECONDARY).into(),
+        };
+
+        column![
+            text("РЕЗЕРВНАЯ КОПИЯ").size(12).color(TEXT_SECONDARY),
+            row![
+                backup_button("Экспорт настроек", Some(Message::ExportSettings)),
+                backup_button("Импорт настроек", (!game_running).then_some(Message::ImportSettings)),
+            ].spacing(10),
+            status,
+        ].spacing(8).into()
+    }
+}
+
+impl std::fmt::Display for QuickPlay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuickPlay::Menu => write!(f, "Главное меню"),
+            QuickPlay::Server => write!(f, "Сервер"),
+            QuickPlay::World(name) => write!(f, "Мир: {}", name),
+        }
+    }
+}
+
+impl std::fmt::Display for SyncPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+impl std::fmt::Display for GarbageCollector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_name())
+    }
+}
+
+impl std::fmt::Display for ShaderpackChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShaderpackChoice::Disabled => write!(f, "Нет"),
+            ShaderpackChoice::Auto => write!(f, "Автоматически"),
+            ShaderpackChoice::Pack(name) => write!(f, "{}", name),
+        }
     }
 }
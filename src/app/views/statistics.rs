@@ -2,34 +2,15 @@ use iced::{
     Alignment, Border, Color, Element, Length,
     widget::{column, container, row, text, Space},
 };
-use chrono::{Local, Datelike, NaiveDate};
-use crate::app::state::{Message, MinecraftLauncher};
+use crate::app::state::{Message, MinecraftLauncher, PlayTimeStats};
 use crate::app::styles::{ACCENT, BG_CARD, TEXT_PRIMARY, TEXT_SECONDARY};
 
 impl MinecraftLauncher {
     pub fn statistics_view(&self) -> Element<'_, Message> {
-        let today = Local::now();
-        let today_str = today.format("%Y-%m-%d").to_string();
-        let today_seconds = self.play_stats.daily.get(&today_str).copied().unwrap_or(0);
-        
-        let week_seconds: u64 = (0..7)
-            .filter_map(|days_ago| {
-                let date = today.date_naive() - chrono::Duration::days(days_ago);
-                let date_str = date.format("%Y-%m-%d").to_string();
-                self.play_stats.daily.get(&date_str).copied()
-            })
-            .sum();
-        
-        let month_seconds: u64 = self.play_stats.daily.iter()
-            .filter(|(date_str, _)| {
-                if let Ok(date) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-                    date.year() == today.year() && date.month() == today.month()
-                } else {
-                    false
-                }
-            })
-            .map(|(_, &secs)| secs)
-            .sum();
+        let today = PlayTimeStats::today();
+        let today_seconds = self.play_stats.seconds_on(today);
+        let week_seconds = self.play_stats.seconds_in_last_days(today, 7);
+        let month_seconds = self.play_stats.seconds_in_month(today);
 
         let format_time = |seconds: u64| -> String {
             let hours = seconds / 3600;
@@ -4,9 +4,13 @@ mod minecraft;
 mod app;
 
 use iced::window;
-use app::{MinecraftLauncher, load_icon};
+use app::{MinecraftLauncher, acquire_single_instance, load_icon};
 
 pub fn main() -> iced::Result {
+    if !acquire_single_instance() {
+        return Ok(());
+    }
+    
     let icon = load_icon();
     
     iced::application("ByStep Launcher", MinecraftLauncher::update, MinecraftLauncher::view)
@@ -14,6 +18,7 @@ pub fn main() -> iced::Result {
         .theme(MinecraftLauncher::theme)
         .window(window::Settings {
             icon: icon,
+            exit_on_close_request: false,
             ..Default::default()
         })
         .run_with(MinecraftLauncher::new)
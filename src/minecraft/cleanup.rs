@@ -0,0 +1,155 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::installer::INSTALL_MARKER_FILE;
+use super::launcher::get_game_directory;
+
+const STALE_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+const INSTALLER_PREFIX: &str = "ByStep-Launcher-Setup";
+pub const KEEP_LOG_FILES: usize = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogFile {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: SystemTime,
+    pub crash_report: bool,
+}
+
+impl LogFile {
+    pub fn name(&self) -> String {
+        self.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+    }
+}
+
+pub fn list_log_files(game_dir: &Path) -> Vec<LogFile> {
+    let mut files = Vec::new();
+    collect_log_files(&game_dir.join("crash-reports"), true, &|name| name.ends_with(".txt"), &mut files);
+    collect_log_files(&game_dir.join("logs"), false, &|name| name.ends_with(".log.gz"), &mut files);
+    files.sort_by_key(|file| std::cmp::Reverse(file.modified));
+    files
+}
+
+pub fn remove_old_logs(game_dir: &Path, keep: usize, protect_since: Option<SystemTime>) -> (usize, u64) {
+    let files = list_log_files(game_dir);
+    let mut removed = 0;
+    let mut reclaimed = 0;
+    
+    for crash_report in [true, false] {
+        let stale = files.iter()
+            .filter(|file| file.crash_report == crash_report)
+            .skip(keep)
+            .filter(|file| protect_since.is_none_or(|since| file.modified < since));
+        for file in stale {
+            if fs::remove_file(&file.path).is_ok() {
+                removed += 1;
+                reclaimed += file.size;
+            }
+        }
+    }
+    
+    (removed, reclaimed)
+}
+
+fn collect_log_files(dir: &Path, crash_report: bool, matches: &dyn Fn(&str) -> bool, out: &mut Vec<LogFile>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return,
+    };
+    
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.file_name().is_some_and(|name| matches(&name.to_string_lossy())) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else { continue };
+        if !metadata.is_file() {
+            continue;
+        }
+        let Ok(modified) = metadata.modified() else { continue };
+        out.push(LogFile { path, size: metadata.len(), modified, crash_report });
+    }
+}
+
+pub fn sweep_stale_files() -> u64 {
+    let mut reclaimed = 0;
+    
+    let game_dir = get_game_directory();
+    reclaimed += sweep_dir(&game_dir, &|name| name.ends_with(".part") || name.ends_with(".part.len"));
+    reclaimed += sweep_dir(&game_dir.join("runtime"), &|name| {
+        name.starts_with("java") && name.ends_with(".zip")
+    });
+    reclaimed += sweep_flat(&std::env::temp_dir(), &|name| {
+        name.starts_with(INSTALLER_PREFIX) && (name.ends_with(".exe") || name.ends_with(".part"))
+    });
+    
+    reclaimed
+}
+
+fn sweep_dir(dir: &Path, matches: &dyn Fn(&str) -> bool) -> u64 {
+    let mut reclaimed = 0;
+    if dir.join(INSTALL_MARKER_FILE).exists() {
+        return 0;
+    }
+    let entries = match fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return 0,
+    };
+    
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let file_type = match entry.file_type() {
+            Ok(t) => t,
+            Err(_) => continue,
+        };
+        if file_type.is_dir() {
+            reclaimed += sweep_dir(&path, matches);
+        } else if file_type.is_file() {
+            reclaimed += remove_if_stale(&path, matches);
+        }
+    }
+    
+    reclaimed
+}
+
+fn sweep_flat(dir: &Path, matches: &dyn Fn(&str) -> bool) -> u64 {
+    let entries = match fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return 0,
+    };
+    
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .map(|entry| remove_if_stale(&entry.path(), matches))
+        .sum()
+}
+
+fn remove_if_stale(path: &Path, matches: &dyn Fn(&str) -> bool) -> u64 {
+    let name = match path.file_name() {
+        Some(n) => n.to_string_lossy(),
+        None => return 0,
+    };
+    if !matches(&name) {
+        return 0;
+    }
+    
+    let metadata = match path.metadata() {
+        Ok(m) => m,
+        Err(_) => return 0,
+    };
+    let age = metadata.modified()
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .unwrap_or_default();
+    
+    if age < STALE_AGE {
+        return 0;
+    }
+    
+    match fs::remove_file(path) {
+        Ok(()) => metadata.len(),
+        Err(_) => 0,
+    }
+}
@@ -0,0 +1,211 @@
+use anyhow::{anyhow, Result};
+use reqwest::{Client, NoProxy, Proxy};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+pub const USER_AGENT: &str = "ByStep-Launcher";
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u32 = 10;
+pub const DEFAULT_DOWNLOAD_TIMEOUT_SECS: u32 = 300;
+pub const MAX_CONNECTIONS_PER_HOST: usize = 6;
+const API_TIMEOUT_SECS: u32 = 30;
+const CONNECTIVITY_CHECK_URL: &str = "https://launchermeta.mojang.com/mc/game/version_manifest_v2.json";
+
+struct HttpConfig {
+    proxy_url: String,
+    connect_timeout_secs: u32,
+    download_timeout_secs: u32,
+}
+
+static CONFIG: RwLock<HttpConfig> = RwLock::new(HttpConfig {
+    proxy_url: String::new(),
+    connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+    download_timeout_secs: DEFAULT_DOWNLOAD_TIMEOUT_SECS,
+});
+static API_CLIENT: RwLock<Option<Client>> = RwLock::new(None);
+static DOWNLOAD_CLIENT: RwLock<Option<Client>> = RwLock::new(None);
+static HOST_LIMITS: Mutex<Option<HashMap<String, Arc<Semaphore>>>> = Mutex::new(None);
+static BANDWIDTH_LIMIT: AtomicU64 = AtomicU64::new(0);
+static BANDWIDTH_BUCKET: Mutex<Option<TokenBucket>> = Mutex::new(None);
+
+struct TokenBucket {
+    available: f64,
+    refilled_at: Instant,
+}
+
+pub fn api_client() -> Client {
+    cached_client(&API_CLIENT, |_| API_TIMEOUT_SECS)
+}
+
+pub fn download_client() -> Client {
+    cached_client(&DOWNLOAD_CLIENT, |config| config.download_timeout_secs)
+}
+
+pub fn set_proxy_url(url: &str) {
+    let url = url.trim().to_string();
+    if let Ok(mut config) = CONFIG.write() {
+        if config.proxy_url == url {
+            return;
+        }
+        config.proxy_url = url;
+    }
+    reset_clients();
+}
+
+pub fn set_timeouts(connect_timeout_secs: u32, download_timeout_secs: u32) {
+    if let Ok(mut config) = CONFIG.write() {
+        if config.connect_timeout_secs == connect_timeout_secs && config.download_timeout_secs == download_timeout_secs {
+            return;
+        }
+        config.connect_timeout_secs = connect_timeout_secs;
+        config.download_timeout_secs = download_timeout_secs;
+    }
+    reset_clients();
+}
+
+pub fn set_bandwidth_limit(megabytes_per_sec: Option<u32>) {
+    let bytes_per_sec = megabytes_per_sec.map_or(0, |mb| mb as u64 * 1024 * 1024);
+    BANDWIDTH_LIMIT.store(bytes_per_sec, Ordering::Relaxed);
+    if let Ok(mut bucket) = BANDWIDTH_BUCKET.lock() {
+        *bucket = None;
+    }
+}
+
+pub async fn throttle_download(bytes: usize) {
+    let limit = BANDWIDTH_LIMIT.load(Ordering::Relaxed);
+    if limit == 0 {
+        return;
+    }
+    let wait = {
+        let Ok(mut guard) = BANDWIDTH_BUCKET.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        let bucket = guard.get_or_insert(TokenBucket { available: limit as f64, refilled_at: now });
+        let refill = now.duration_since(bucket.refilled_at).as_secs_f64() * limit as f64;
+        bucket.available = (bucket.available + refill).min(limit as f64) - bytes as f64;
+        bucket.refilled_at = now;
+        if bucket.available < 0.0 {
+            Duration::from_secs_f64(-bucket.available / limit as f64)
+        } else {
+            Duration::ZERO
+        }
+    };
+    if !wait.is_zero() {
+        tokio::time::sleep(wait).await;
+    }
+}
+
+pub async fn acquire_host_slot(url: &str) -> Option<OwnedSemaphorePermit> {
+    let host = reqwest::Url::parse(url).ok()?.host_str()?.to_string();
+    let semaphore = {
+        let mut limits = HOST_LIMITS.lock().ok()?;
+        limits
+            .get_or_insert_with(HashMap::new)
+            .entry(host)
+            .or_insert_with(|| Arc::new(Semaphore::new(MAX_CONNECTIONS_PER_HOST)))
+            .clone()
+    };
+    semaphore.acquire_owned().await.ok()
+}
+
+pub fn validate_proxy_url(url: &str) -> Result<()> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Ok(());
+    }
+    Proxy::all(url).map(|_| ()).map_err(|e| anyhow!("Неверный адрес прокси: {}", e))
+}
+
+pub async fn check_connectivity(proxy_url: &str) -> Result<()> {
+    validate_proxy_url(proxy_url)?;
+    let connect_timeout_secs = CONFIG.read().map(|config| config.connect_timeout_secs).unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS);
+    let client = build_client(API_TIMEOUT_SECS, connect_timeout_secs, proxy_url.trim());
+    let response = client.head(CONNECTIVITY_CHECK_URL).send().await?;
+    if !response.status().is_success() {
+        return Err(anyhow!("HTTP {}", response.status()));
+    }
+    Ok(())
+}
+
+pub fn is_network_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|e| e.is_connect() || e.is_timeout())
+    })
+}
+
+fn reset_clients() {
+    for cache in [&API_CLIENT, &DOWNLOAD_CLIENT] {
+        if let Ok(mut client) = cache.write() {
+            *client = None;
+        }
+    }
+}
+
+fn cached_client(cache: &RwLock<Option<Client>>, timeout_secs: fn(&HttpConfig) -> u32) -> Client {
+    if let Ok(guard) = cache.read() {
+        if let Some(client) = guard.as_ref() {
+            return client.clone();
+        }
+    }
+    let client = match CONFIG.read() {
+        Ok(config) => build_client(timeout_secs(&config), config.connect_timeout_secs, &config.proxy_url),
+        Err(_) => build_client(API_TIMEOUT_SECS, DEFAULT_CONNECT_TIMEOUT_SECS, ""),
+    };
+    if let Ok(mut guard) = cache.write() {
+        *guard = Some(client.clone());
+    }
+    client
+}
+
+fn build_client(timeout_secs: u32, connect_timeout_secs: u32, proxy_url: &str) -> Client {
+    let mut builder = Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(Duration::from_secs(timeout_secs as u64))
+        .connect_timeout(Duration::from_secs(connect_timeout_secs.min(timeout_secs) as u64))
+        .pool_max_idle_per_host(MAX_CONNECTIONS_PER_HOST)
+        .gzip(true)
+        .brotli(true)
+        .deflate(true)
+        .pool_idle_timeout(Duration::from_secs(90))
+        .tcp_keepalive(Duration::from_secs(60));
+
+    for proxy in proxies(proxy_url) {
+        builder = builder.proxy(proxy);
+    }
+
+    builder.build().unwrap_or_else(|_| Client::new())
+}
+
+fn proxies(proxy_url: &str) -> Vec<Proxy> {
+    if !proxy_url.is_empty() {
+        if let Ok(proxy) = Proxy::all(proxy_url) {
+            return vec![proxy.no_proxy(NoProxy::from_env())];
+        }
+    }
+
+    let mut proxies = Vec::new();
+    if let Some(url) = env_var(&["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]) {
+        if let Ok(proxy) = Proxy::https(&url) {
+            proxies.push(proxy.no_proxy(NoProxy::from_env()));
+        }
+    }
+    if let Some(url) = env_var(&["HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"]) {
+        if let Ok(proxy) = Proxy::http(&url) {
+            proxies.push(proxy.no_proxy(NoProxy::from_env()));
+        }
+    }
+    proxies
+}
+
+fn env_var(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .map(|value| value.trim().to_string())
+        .find(|value| !value.is_empty())
+}
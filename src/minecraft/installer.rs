@@ -1,59 +1,228 @@
-use anyhow::{anyhow, Result};
-use futures_util::StreamExt;
+use anyhow::{anyhow, Context, Result};
+use futures_util::{stream, StreamExt};
 use reqwest::Client;
-use std::io::Write;
+use std::io::{Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OwnedMutexGuard;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
 
-use super::version::{GameVersion, ShaderQuality};
+use super::version::{GameVersion, ShaderQuality, SyncPolicy};
+use super::progress::{DownloadKind, InstallStep};
 use super::types::*;
+use super::http::{acquire_host_slot, api_client, download_client, throttle_download};
+use super::launcher::{find_system_java, java_major_version};
 
 const VERSION_MANIFEST_URL: &str = "https://launchermeta.mojang.com/mc/game/version_manifest_v2.json";
 const FABRIC_META_URL: &str = "https://meta.fabricmc.net";
 const JAVA17_URL: &str = "https://github.com/adoptium/temurin17-binaries/releases/download/jdk-17.0.13%2B11/OpenJDK17U-jre_x64_windows_hotspot_17.0.13_11.zip";
 const JAVA21_URL: &str = "https://github.com/adoptium/temurin21-binaries/releases/download/jdk-21.0.5%2B11/OpenJDK21U-jre_x64_windows_hotspot_21.0.5_11.zip";
 const MODS_RAW_BASE: &str = "https://raw.githubusercontent.com/PRISSET/mods/main";
+const MAX_CONCURRENT_DOWNLOADS: usize = 8;
+const DEFAULT_MAVEN_URL: &str = "https://libraries.minecraft.net/";
 const MODS_API_BASE: &str = "https://api.github.com/repos/PRISSET/mods/contents";
+const RESOURCES_URL: &str = "https://resources.download.minecraft.net";
 
-pub type ProgressCallback = Box<dyn Fn(&str, f32) + Send + Sync>;
+pub const LOCAL_MODS_FOLDER: &str = "local";
+pub const REMOVED_MODS_FOLDER: &str = ".removed";
+pub const INSTALL_MARKER_FILE: &str = "install-progress.json";
+const CONTENT_MANIFEST_FILE: &str = "manifest.json";
+const SYNCED_SHADERPACKS_FILE: &str = ".synced-shaderpacks.json";
+const STAGING_FOLDER: &str = ".staged-content";
+const STAGED_MANIFEST_FILE: &str = "staged.json";
+const EXTRACT_SPACE_MARGIN: u64 = 50 * 1024 * 1024;
+const PREALLOCATE_MIN_LEN: u64 = 1024 * 1024;
+const PART_LEN_RECORD_STEP: u64 = 1024 * 1024;
+const FILE_OP_ATTEMPTS: u32 = 3;
+const FILE_OP_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
+const ERROR_SHARING_VIOLATION: i32 = 32;
+const ERROR_LOCK_VIOLATION: i32 = 33;
+
+const CONTENT_FINGERPRINT_LEN: usize = 6;
+const VERIFY_PROGRESS_STEP: usize = 25;
+const GITHUB_API_ATTEMPTS: u32 = 3;
+const GITHUB_API_BACKOFF: std::time::Duration = std::time::Duration::from_millis(500);
+const GITHUB_API_MAX_RETRY_AFTER: std::time::Duration = std::time::Duration::from_secs(10);
+type InstallLocks = HashMap<(PathBuf, DownloadKind), Arc<tokio::sync::Mutex<()>>>;
+
+// Only serializes installs inside this process; a second launcher process is kept out by the single-instance guard.
+static INSTALL_LOCKS: Mutex<Option<InstallLocks>> = Mutex::new(None);
+
+pub fn validate_content_source_url(url: &str) -> Result<()> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Ok(());
+    }
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(()),
+        Ok(_) => Err(anyhow!("Адрес должен начинаться с http:// или https://")),
+        Err(e) => Err(anyhow!("Неверный адрес: {}", e)),
+    }
+}
+
+pub async fn validate_fabric_loader_version(loader_version: &str) -> Result<()> {
+    #[derive(Deserialize)]
+    struct LoaderEntry {
+        version: String,
+    }
+    
+    let loader_version = loader_version.trim();
+    if loader_version.is_empty() || !loader_version.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+')) {
+        return Err(anyhow!("Неверный формат версии"));
+    }
+    
+    let response = api_client()
+        .get(format!("{}/v2/versions/loader", FABRIC_META_URL))
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(anyhow!("Не удалось получить список версий Fabric: {}", response.status()));
+    }
+    
+    let loaders: Vec<LoaderEntry> = response.json().await?;
+    if loaders.iter().any(|entry| entry.version == loader_version) {
+        Ok(())
+    } else {
+        Err(anyhow!("Fabric Loader {} не существует", loader_version))
+    }
+}
+
+pub type ProgressCallback = Box<dyn Fn(&InstallStep, f32) + Send + Sync>;
+
+#[derive(Debug, Clone)]
+pub struct InstallerConfig {
+    pub minecraft_version: String,
+    pub loader_version: String,
+    pub java_version: u8,
+    pub content_folder: String,
+    pub version_manifest_url: String,
+    pub fabric_meta_url: String,
+    pub maven_url: String,
+    pub mods_api_base: String,
+    pub mods_raw_base: String,
+    pub resources_url: String,
+    pub runtime_dir: PathBuf,
+    pub content_manifest_url: Option<String>,
+    pub mods_sync_policy: SyncPolicy,
+    pub shaderpacks_sync_policy: SyncPolicy,
+    pub resourcepacks_sync_policy: SyncPolicy,
+    pub use_system_java: bool,
+}
+
+impl InstallerConfig {
+    pub fn for_version(version: GameVersion) -> Self {
+        Self {
+            minecraft_version: version.minecraft_version().to_string(),
+            loader_version: version.fabric_loader_version().to_string(),
+            java_version: version.java_version(),
+            content_folder: version.mods_folder().to_string(),
+            version_manifest_url: VERSION_MANIFEST_URL.to_string(),
+            fabric_meta_url: FABRIC_META_URL.to_string(),
+            maven_url: DEFAULT_MAVEN_URL.to_string(),
+            mods_api_base: MODS_API_BASE.to_string(),
+            mods_raw_base: MODS_RAW_BASE.to_string(),
+            resources_url: RESOURCES_URL.to_string(),
+            runtime_dir: directories::ProjectDirs::from("com", "bystep", "minecraft")
+                .map(|dirs| dirs.data_dir().to_path_buf())
+                .unwrap_or_else(|| {
+                    std::env::current_dir()
+                        .unwrap_or_else(|_| PathBuf::from("."))
+                        .join(".bystep-minecraft")
+                })
+                .join("runtime"),
+            content_manifest_url: None,
+            mods_sync_policy: SyncPolicy::Mirror,
+            shaderpacks_sync_policy: SyncPolicy::Mirror,
+            resourcepacks_sync_policy: SyncPolicy::Merge,
+            use_system_java: false,
+        }
+    }
+
+    pub fn with_sync_policies(mut self, mods: SyncPolicy, shaderpacks: SyncPolicy, resourcepacks: SyncPolicy) -> Self {
+        self.mods_sync_policy = mods;
+        self.shaderpacks_sync_policy = shaderpacks;
+        self.resourcepacks_sync_policy = resourcepacks;
+        self
+    }
+
+    fn sync_policy(&self, kind: DownloadKind) -> SyncPolicy {
+        match kind {
+            DownloadKind::Mods => self.mods_sync_policy,
+            DownloadKind::Shaderpacks => self.shaderpacks_sync_policy,
+            DownloadKind::Resourcepacks => self.resourcepacks_sync_policy,
+            DownloadKind::Game | DownloadKind::Fabric => SyncPolicy::Mirror,
+        }
+    }
+
+    fn managed_extensions<'a>(&self, kind: DownloadKind, extensions: &'a [&'a str]) -> &'a [&'a str] {
+        match self.sync_policy(kind) {
+            SyncPolicy::Mirror => extensions,
+            SyncPolicy::Merge => &[],
+        }
+    }
+
+    pub fn with_loader_version(mut self, loader_version: &str) -> Self {
+        let loader_version = loader_version.trim();
+        if !loader_version.is_empty() {
+            self.loader_version = loader_version.to_string();
+        }
+        self
+    }
+
+    pub fn with_content_source(mut self, base_url: &str) -> Self {
+        let base_url = base_url.trim().trim_end_matches('/');
+        self.content_manifest_url = (!base_url.is_empty())
+            .then(|| format!("{}/{}/{}", base_url, self.content_folder, CONTENT_MANIFEST_FILE));
+        self
+    }
+
+    pub fn with_system_java(mut self, enabled: bool) -> Self {
+        self.use_system_java = enabled;
+        self
+    }
+}
 
 pub struct MinecraftInstaller {
     client: Client,
     game_dir: PathBuf,
     version: GameVersion,
+    config: InstallerConfig,
     progress_callback: Option<ProgressCallback>,
 }
 
 impl MinecraftInstaller {
-    pub fn new(game_dir: PathBuf, version: GameVersion) -> Self {
+    pub fn with_config(game_dir: PathBuf, version: GameVersion, config: InstallerConfig) -> Self {
         Self {
-            client: Client::builder()
-                .timeout(std::time::Duration::from_secs(300))
-                .build()
-                .unwrap_or_else(|_| Client::new()),
+            client: download_client(),
             game_dir,
             version,
+            config,
             progress_callback: None,
         }
     }
 
     pub fn with_progress<F>(mut self, callback: F) -> Self
     where
-        F: Fn(&str, f32) + Send + Sync + 'static,
+        F: Fn(&InstallStep, f32) + Send + Sync + 'static,
     {
         self.progress_callback = Some(Box::new(callback));
         self
     }
 
-    fn report_progress(&self, message: &str, progress: f32) {
+    fn report_progress(&self, step: InstallStep, progress: f32) {
         if let Some(cb) = &self.progress_callback {
-            cb(message, progress);
+            cb(&step, progress);
         }
     }
 
     pub async fn is_installed(&self) -> bool {
-        let mc_version = self.version.minecraft_version();
-        let loader_version = self.version.fabric_loader_version();
+        let mc_version = self.config.minecraft_version.as_str();
+        let loader_version = self.config.loader_version.as_str();
         let fabric_id = format!("fabric-loader-{}-{}", loader_version, mc_version);
         let fabric_json = self.game_dir
             .join("versions")
@@ -65,94 +234,453 @@ impl MinecraftInstaller {
             .join(mc_version)
             .join(format!("{}.jar", mc_version));
         
-        fabric_json.exists() && client_jar.exists()
+        if !(fabric_json.exists() && client_jar.exists() && self.fabric_loader_jar().exists()) {
+            return false;
+        }
+        
+        if version_asset_index_id(&self.game_dir, mc_version).is_none() {
+            eprintln!("Version file for {} is missing or corrupted, reinstalling", mc_version);
+            return false;
+        }
+        if !fabric_profile_valid(&fabric_json) {
+            eprintln!("Fabric profile {} is corrupted, reinstalling", fabric_json.display());
+            return false;
+        }
+        true
+    }
+
+    pub fn stale_fabric_versions(&self) -> Vec<String> {
+        let mc_version = self.config.minecraft_version.as_str();
+        let current_id = format!("fabric-loader-{}-{}", self.config.loader_version, mc_version);
+        let suffix = format!("-{}", mc_version);
+        
+        fs::read_dir(self.game_dir.join("versions"))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .filter(|name| name.starts_with("fabric-loader-") && name.ends_with(&suffix) && *name != current_id)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn remove_stale_fabric_versions(&self) {
+        for id in self.stale_fabric_versions() {
+            let _ = fs::remove_dir_all(self.game_dir.join("versions").join(&id));
+        }
+        
+        let loader_dir = self.game_dir.join("libraries").join("net").join("fabricmc").join("fabric-loader");
+        if let Ok(entries) = fs::read_dir(&loader_dir) {
+            for entry in entries.flatten() {
+                if entry.file_name().to_string_lossy() != self.config.loader_version.as_str() {
+                    let _ = fs::remove_dir_all(entry.path());
+                }
+            }
+        }
     }
 
     pub async fn install_simple(&self) -> Result<()> {
-        self.report_progress("Проверка Java...", 0.05);
+        self.report_progress(InstallStep::CheckingJava, 0.05);
         self.ensure_java().await?;
         
-        self.report_progress("Загрузка информации о версии...", 0.10);
+        let _game_lock = self.acquire_install_lock(&self.game_dir, DownloadKind::Game, 0.10).await;
+        let _mods_lock = self.acquire_install_lock(&self.game_dir, DownloadKind::Mods, 0.10).await;
+        
+        self.report_progress(InstallStep::FetchingVersionInfo, 0.10);
         let version_info = self.download_version_info().await?;
         
-        self.report_progress("Загрузка клиента Minecraft...", 0.15);
-        self.download_client(&version_info).await?;
+        let mut marker = self.load_install_marker();
+        if !marker.completed.is_empty() {
+            self.report_progress(InstallStep::ResumingInstall, 0.10);
+        }
+        
+        let client = if marker.completed.contains(&InstallPhase::Client) { Vec::new() } else { self.plan_client(&version_info)? };
+        let libraries = if marker.completed.contains(&InstallPhase::Libraries) { Vec::new() } else { self.plan_libraries(&version_info)? };
+        let assets = if marker.completed.contains(&InstallPhase::Assets) { Vec::new() } else { self.plan_assets(&version_info).await? };
+        let phases: Vec<(InstallPhase, Vec<PathBuf>)> = [
+            (InstallPhase::Client, &client),
+            (InstallPhase::Libraries, &libraries),
+            (InstallPhase::Assets, &assets),
+        ]
+        .into_iter()
+        .map(|(phase, files)| (phase, files.iter().map(|file| file.path.clone()).collect()))
+        .collect();
         
-        self.report_progress("Загрузка библиотек...", 0.20);
-        self.download_libraries(&version_info).await?;
+        let mut pending = client;
+        pending.extend(libraries);
+        pending.extend(assets);
         
-        self.report_progress("Загрузка ресурсов...", 0.40);
-        self.download_assets(&version_info).await?;
+        self.report_progress(InstallStep::InstallingFabric, 0.10);
+        let (fabric_profile, fabric_libraries) = self.plan_fabric().await?;
+        pending.extend(fabric_libraries);
         
-        self.report_progress("Установка Fabric...", 0.70);
-        self.install_fabric().await?;
+        self.report_progress(InstallStep::CheckingMods, 0.10);
+        pending.extend(self.plan_mods().await?);
+        
+        let result = self.download_all(pending, DownloadKind::Game, 0.10, 0.85).await;
+        for (phase, paths) in phases {
+            if !marker.completed.contains(&phase) && paths.iter().all(|path| path.exists()) {
+                marker.completed.push(phase);
+            }
+        }
+        self.save_install_marker(&marker);
+        result?;
         
-        self.report_progress("Загрузка модов...", 0.80);
-        self.download_mods().await?;
+        self.verify_fabric_libraries(&fabric_profile)?;
+        self.write_fabric_profile(&fabric_profile)?;
+        self.remove_stale_fabric_versions();
+        self.materialize_legacy_assets(&version_info)?;
+        self.create_default_options()?;
         
-        self.report_progress("Установка завершена!", 0.85);
+        let _ = fs::remove_file(self.install_marker_path());
+        self.report_progress(InstallStep::Complete, 0.85);
         Ok(())
     }
 
-    pub async fn download_mods(&self) -> Result<()> {
-        let mods_dir = self.game_dir.join("mods");
-        fs::create_dir_all(&mods_dir)?;
+    async fn acquire_install_lock(&self, dir: &Path, kind: DownloadKind, progress: f32) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = INSTALL_LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+            locks
+                .get_or_insert_with(HashMap::new)
+                .entry((dir.to_path_buf(), kind))
+                .or_default()
+                .clone()
+        };
+        match lock.clone().try_lock_owned() {
+            Ok(guard) => guard,
+            Err(_) => {
+                self.report_progress(InstallStep::WaitingForInstall, progress);
+                lock.lock_owned().await
+            }
+        }
+    }
+
+    fn install_marker_path(&self) -> PathBuf {
+        self.game_dir.join(INSTALL_MARKER_FILE)
+    }
+
+    fn load_install_marker(&self) -> InstallMarker {
+        fs::read_to_string(self.install_marker_path())
+            .ok()
+            .and_then(|content| serde_json::from_str::<InstallMarker>(&content).ok())
+            .filter(|marker| marker.minecraft_version == self.config.minecraft_version)
+            .unwrap_or_else(|| InstallMarker {
+                minecraft_version: self.config.minecraft_version.clone(),
+                completed: Vec::new(),
+            })
+    }
+
+    fn save_install_marker(&self, marker: &InstallMarker) {
+        if let Ok(json) = serde_json::to_string_pretty(marker) {
+            let _ = fs::write(self.install_marker_path(), json);
+        }
+    }
+
+    pub async fn repair_fabric(&self) -> Result<()> {
+        let _game_lock = self.acquire_install_lock(&self.game_dir, DownloadKind::Game, 0.05).await;
+        let _mods_lock = self.acquire_install_lock(&self.game_dir, DownloadKind::Mods, 0.05).await;
+        self.report_progress(InstallStep::FetchingFabricProfile, 0.05);
+        let (fabric_profile, _) = self.plan_fabric().await?;
         
-        let mods_api_url = format!("{}/{}", MODS_API_BASE, self.version.mods_folder());
+        let fabric_version_id = format!("fabric-loader-{}-{}", self.config.loader_version, self.config.minecraft_version);
+        let _ = fs::remove_dir_all(self.game_dir.join("versions").join(&fabric_version_id));
         
-        let response = self.client
-            .get(&mods_api_url)
-            .header("User-Agent", "ByStep-Launcher")
-            .header("Accept", "application/vnd.github.v3+json")
-            .send()
-            .await?;
+        let libraries = self.fabric_libraries(&fabric_profile);
+        for library in &libraries {
+            let _ = fs::remove_file(&library.path);
+            if let Some(parent) = library.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+        }
         
-        if !response.status().is_success() {
-            return Err(anyhow!("Не удалось получить список модов: {}", response.status()));
+        self.download_all(libraries, DownloadKind::Fabric, 0.10, 0.70).await?;
+        self.verify_fabric_libraries(&fabric_profile)?;
+        self.write_fabric_profile(&fabric_profile)?;
+        self.remove_stale_fabric_versions();
+        
+        self.report_progress(InstallStep::CheckingMods, 0.75);
+        let pending = self.plan_mods().await?;
+        self.download_all(pending, DownloadKind::Mods, 0.75, 1.0).await
+    }
+
+    pub async fn verify_files(&self) -> Result<VerifyReport> {
+        let _game_lock = self.acquire_install_lock(&self.game_dir, DownloadKind::Game, 0.0).await;
+        let _mods_lock = self.acquire_install_lock(&self.game_dir, DownloadKind::Mods, 0.0).await;
+        self.report_progress(InstallStep::CheckingInstall, 0.0);
+        
+        let mc_version = self.config.minecraft_version.as_str();
+        let version_info: VersionInfo = fs::read_to_string(self.game_dir.join("versions").join(mc_version).join(format!("{}.json", mc_version)))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .ok_or_else(|| anyhow!("Игра не установлена или файл версии {} повреждён", mc_version))?;
+        
+        let mut files = vec![(
+            PendingDownload {
+                url: version_info.downloads.client.url.clone(),
+                path: self.game_dir.join("versions").join(mc_version).join(format!("{}.jar", mc_version)),
+                size: version_info.downloads.client.size,
+                required: false,
+                sha256: None,
+            },
+            ExpectedHash::Sha1(version_info.downloads.client.sha1.clone()),
+        )];
+        
+        let libraries_dir = self.game_dir.join("libraries");
+        for library in &version_info.libraries {
+            if !self.should_use_library(library) {
+                continue;
+            }
+            if let Some(artifact) = library.downloads.as_ref().and_then(|d| d.artifact.as_ref()) {
+                files.push((
+                    PendingDownload {
+                        url: artifact.url.clone(),
+                        path: libraries_dir.join(&artifact.path),
+                        size: artifact.size,
+                        required: false,
+                        sha256: None,
+                    },
+                    ExpectedHash::Sha1(artifact.sha1.clone()),
+                ));
+            }
         }
         
-        let files: Vec<GitHubFile> = response.json().await?;
-        let mod_files: Vec<&GitHubFile> = files.iter()
-            .filter(|f| f.file_type == "file" && (f.name.ends_with(".jar") || f.name.ends_with(".zip")))
+        let index_path = self.game_dir.join("assets").join("indexes").join(format!("{}.json", version_info.asset_index.id));
+        if let Some(asset_index) = fs::read_to_string(&index_path).ok().and_then(|content| serde_json::from_str::<AssetIndex>(&content).ok()) {
+            let objects_dir = self.game_dir.join("assets").join("objects");
+            let mut seen = std::collections::HashSet::new();
+            for object in asset_index.objects.values() {
+                if object.hash.len() < 2 || !seen.insert(object.hash.clone()) {
+                    continue;
+                }
+                let hash_prefix = &object.hash[..2];
+                files.push((
+                    PendingDownload {
+                        url: format!("{}/{}/{}", self.config.resources_url, hash_prefix, object.hash),
+                        path: objects_dir.join(hash_prefix).join(&object.hash),
+                        size: object.size,
+                        required: false,
+                        sha256: None,
+                    },
+                    ExpectedHash::Sha1(object.hash.clone()),
+                ));
+            }
+        }
+        
+        if let Ok(Some(mod_files)) = self.list_content(DownloadKind::Mods, None).await {
+            let mods_dir = self.game_dir.join("mods");
+            for file in mod_files.into_iter().filter(|f| f.name.ends_with(".jar") || f.name.ends_with(".zip")) {
+                let expected = match &file.sha256 {
+                    Some(sha256) => ExpectedHash::Sha256(sha256.clone()),
+                    None => ExpectedHash::Size(file.size),
+                };
+                files.push((file.into_pending(&mods_dir), expected));
+            }
+        }
+        
+        let total = files.len();
+        let mut checks = stream::iter(files)
+            .map(|(file, expected)| async move {
+                let path = file.path.clone();
+                tokio::task::spawn_blocking(move || {
+                    let valid = expected.matches(&path);
+                    (file, expected, valid)
+                })
+                    .await
+            })
+            .buffer_unordered(MAX_CONCURRENT_DOWNLOADS);
+        
+        let mut done = 0;
+        let mut corrupted = Vec::new();
+        while let Some(checked) = checks.next().await {
+            let (file, expected, valid) = checked?;
+            done += 1;
+            if !valid {
+                corrupted.push((file, expected));
+            }
+            if done % VERIFY_PROGRESS_STEP == 0 || done == total {
+                self.report_progress(InstallStep::VerifyingFiles { done, total }, 0.8 * done as f32 / total.max(1) as f32);
+            }
+        }
+        
+        let corrupted_count = corrupted.len();
+        for (file, _) in &corrupted {
+            let _ = fs::remove_file(&file.path);
+            if let Some(parent) = file.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        let (pending, expected): (Vec<PendingDownload>, Vec<ExpectedHash>) = corrupted.into_iter().unzip();
+        let paths: Vec<PathBuf> = pending.iter().map(|file| file.path.clone()).collect();
+        self.download_all(pending, DownloadKind::Game, 0.8, 1.0).await?;
+        
+        let fixed = paths.iter()
+            .zip(&expected)
+            .filter(|(path, expected)| expected.matches(path))
+            .count();
+        
+        Ok(VerifyReport { checked: total, corrupted: corrupted_count, fixed })
+    }
+
+    pub async fn download_mods(&self) -> Result<()> {
+        let _lock = self.acquire_install_lock(&self.game_dir, DownloadKind::Mods, 0.80).await;
+        let pending = self.plan_mods().await?;
+        self.download_all(pending, DownloadKind::Mods, 0.80, 0.85).await
+    }
+
+    async fn plan_mods(&self) -> Result<Vec<PendingDownload>> {
+        let mods_dir = self.game_dir.join("mods");
+        retry_file_op(&mods_dir, || fs::create_dir_all(mods_dir.join(LOCAL_MODS_FOLDER)))?;
+        
+        let Some(files) = self.list_content(DownloadKind::Mods, None).await? else {
+            self.report_progress(InstallStep::ModsUnpublished(self.version), 0.85);
+            return Ok(Vec::new());
+        };
+        
+        let mod_files: Vec<RemoteFile> = files.into_iter()
+            .filter(|f| f.name.ends_with(".jar") || f.name.ends_with(".zip"))
             .collect();
         
         let mod_names: Vec<String> = mod_files.iter().map(|f| f.name.clone()).collect();
+        self.retire_unlisted_mods(&mods_dir, &mod_names)?;
+        
+        let pending = mod_files.into_iter()
+            .filter(|file| !file.is_current(&mods_dir))
+            .map(|file| file.into_pending(&mods_dir))
+            .collect();
         
-        if let Ok(entries) = fs::read_dir(&mods_dir) {
+        Ok(pending)
+    }
+    
+    fn retire_unlisted_mods(&self, mods_dir: &Path, mod_names: &[String]) -> Result<()> {
+        let removed_dir = mods_dir.join(REMOVED_MODS_FOLDER);
+        let mut removed = Vec::new();
+        let entries = match self.config.sync_policy(DownloadKind::Mods) {
+            SyncPolicy::Mirror => fs::read_dir(mods_dir).ok(),
+            SyncPolicy::Merge => None,
+        };
+        if let Some(entries) = entries {
             for entry in entries.flatten() {
                 let file_name = entry.file_name().to_string_lossy().to_string();
                 if (file_name.ends_with(".jar") || file_name.ends_with(".zip")) && !mod_names.contains(&file_name) {
-                    for _ in 0..3 {
-                        if fs::remove_file(entry.path()).is_ok() {
-                            break;
-                        }
-                        std::thread::sleep(std::time::Duration::from_millis(100));
-                    }
+                    retry_file_op(&removed_dir, || fs::create_dir_all(&removed_dir))?;
+                    let target = removed_dir.join(&file_name);
+                    retry_file_op(&entry.path(), || {
+                        let _ = fs::remove_file(&target);
+                        fs::rename(entry.path(), &target)
+                    })?;
+                    removed.push(file_name);
+                }
+            }
+        }
+        if !removed.is_empty() {
+            eprintln!("Moved mods missing from the server list to {}: {}", removed_dir.display(), removed.join(", "));
+            self.report_progress(InstallStep::ModsRemoved(removed), 0.80);
+        }
+        Ok(())
+    }
+    
+    async fn outdated_content(&self, quality: ShaderQuality, sync_shaderpacks: bool, sync_resourcepacks: bool) -> Result<Vec<(DownloadKind, Vec<RemoteFile>)>> {
+        let mut outdated = Vec::new();
+        
+        if let Some(files) = self.list_content(DownloadKind::Mods, None).await? {
+            let mod_files: Vec<RemoteFile> = files.into_iter()
+                .filter(|f| f.name.ends_with(".jar") || f.name.ends_with(".zip"))
+                .collect();
+            if content_outdated(&self.game_dir.join("mods"), &mod_files, self.config.managed_extensions(DownloadKind::Mods, &[".jar", ".zip"])) {
+                outdated.push((DownloadKind::Mods, mod_files));
+            }
+        }
+        
+        if sync_shaderpacks && quality != ShaderQuality::Off {
+            if let Some(files) = self.list_shaderpacks(quality).await? {
+                if content_outdated(&self.game_dir.join("shaderpacks"), &files, self.config.managed_extensions(DownloadKind::Shaderpacks, &[".zip"])) {
+                    outdated.push((DownloadKind::Shaderpacks, files));
+                }
+            }
+        }
+        
+        if sync_resourcepacks {
+            if let Some(files) = self.list_content(DownloadKind::Resourcepacks, None).await? {
+                if content_outdated(&self.game_dir.join("resourcepacks"), &files, self.config.managed_extensions(DownloadKind::Resourcepacks, &[".zip"])) {
+                    outdated.push((DownloadKind::Resourcepacks, files));
                 }
             }
         }
         
-        let total = mod_files.len();
-        for (i, file) in mod_files.iter().enumerate() {
-            let mod_path = mods_dir.join(&file.name);
+        Ok(outdated)
+    }
+    
+    pub async fn stage_content_updates(&self, quality: ShaderQuality, sync_shaderpacks: bool, sync_resourcepacks: bool) -> Result<Vec<DownloadKind>> {
+        let outdated = self.outdated_content(quality, sync_shaderpacks, sync_resourcepacks).await?;
+        if outdated.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let staging_dir = self.game_dir.join(STAGING_FOLDER);
+        let mut staged = StagedContent::default();
+        let mut kinds = Vec::new();
+        for (kind, files) in outdated {
+            let live_dir = self.game_dir.join(content_dir_name(kind));
+            let stage_dir = staging_dir.join(content_dir_name(kind));
+            fs::create_dir_all(&stage_dir)?;
             
-            if mod_path.exists() {
-                continue;
+            staged.files.insert(content_dir_name(kind).to_string(), files.iter().map(|file| file.name.clone()).collect());
+            let pending = files.into_iter()
+                .filter(|file| !file.is_current(&live_dir) && !file.is_current(&stage_dir))
+                .map(|file| file.into_pending(&stage_dir))
+                .collect();
+            self.download_all(pending, kind, 0.0, 1.0).await?;
+            kinds.push(kind);
+        }
+        
+        write_json_file(&staging_dir.join(STAGED_MANIFEST_FILE), &serde_json::to_string(&staged)?)?;
+        Ok(kinds)
+    }
+    
+    pub fn apply_staged_content(&self) -> Result<Vec<DownloadKind>> {
+        let staging_dir = self.game_dir.join(STAGING_FOLDER);
+        let Some(staged) = fs::read(staging_dir.join(STAGED_MANIFEST_FILE)).ok()
+            .and_then(|content| serde_json::from_slice::<StagedContent>(&content).ok()) else {
+            if staging_dir.exists() {
+                let _ = fs::remove_dir_all(&staging_dir);
             }
+            return Ok(Vec::new());
+        };
+        
+        let mut applied = Vec::new();
+        for kind in [DownloadKind::Mods, DownloadKind::Shaderpacks, DownloadKind::Resourcepacks] {
+            let Some(names) = staged.files.get(content_dir_name(kind)) else {
+                continue;
+            };
+            let live_dir = self.game_dir.join(content_dir_name(kind));
+            let stage_dir = staging_dir.join(content_dir_name(kind));
+            retry_file_op(&live_dir, || fs::create_dir_all(&live_dir))?;
             
-            self.report_progress(
-                &format!("Мод: {} ({}/{})", file.name, i + 1, total),
-                0.80 + (0.05 * (i as f32 / total as f32))
-            );
+            for name in names {
+                let source = stage_dir.join(name);
+                if source.exists() {
+                    let target = live_dir.join(name);
+                    retry_file_op(&target, || {
+                        let _ = fs::remove_file(&target);
+                        fs::rename(&source, &target)
+                    })?;
+                }
+            }
             
-            let raw_url = format!("{}/{}/{}", MODS_RAW_BASE, self.version.mods_folder(), urlencoding::encode(&file.name));
-            let _ = self.download_file(&raw_url, &mod_path).await;
+            match kind {
+                DownloadKind::Mods => self.retire_unlisted_mods(&live_dir, names)?,
+                _ => remove_unlisted(&live_dir, names, self.config.managed_extensions(kind, &[".zip"])),
+            }
+            applied.push(kind);
         }
         
-        Ok(())
+        fs::remove_dir_all(&staging_dir)?;
+        Ok(applied)
     }
     
     pub async fn download_shaderpacks(&self, quality: ShaderQuality) -> Result<()> {
+        let _lock = self.acquire_install_lock(&self.game_dir, DownloadKind::Shaderpacks, 0.86).await;
         let shaderpacks_dir = self.game_dir.join("shaderpacks");
         fs::create_dir_all(&shaderpacks_dir)?;
         
@@ -160,113 +688,191 @@ impl MinecraftInstaller {
             return Ok(());
         }
         
-        let api_url = format!("{}/{}/shaderpacks", MODS_API_BASE, self.version.mods_folder());
+        let Some(files) = self.list_shaderpacks(quality).await? else {
+            return Ok(());
+        };
+        
+        let names: Vec<String> = files.iter().map(|file| file.name.clone()).collect();
+        remove_unlisted(&shaderpacks_dir, &names, self.config.managed_extensions(DownloadKind::Shaderpacks, &[".zip"]));
         
-        let response = self.client
-            .get(&api_url)
-            .header("User-Agent", "ByStep-Launcher")
-            .header("Accept", "application/vnd.github.v3+json")
-            .send()
-            .await?;
+        let pending = files.into_iter()
+            .filter(|file| !file.is_current(&shaderpacks_dir))
+            .map(|file| file.into_pending(&shaderpacks_dir))
+            .collect();
         
-        if !response.status().is_success() {
-            return Ok(());
+        self.download_all(pending, DownloadKind::Shaderpacks, 0.86, 0.88).await?;
+        record_synced_shaderpacks(&shaderpacks_dir, quality, names);
+        Ok(())
+    }
+    
+    async fn list_shaderpacks(&self, quality: ShaderQuality) -> Result<Option<Vec<RemoteFile>>> {
+        if let Some(tier) = quality.folder_name() {
+            if let Some(files) = self.list_content(DownloadKind::Shaderpacks, Some(tier)).await?.filter(|files| !files.is_empty()) {
+                return Ok(Some(files));
+            }
         }
+        self.list_content(DownloadKind::Shaderpacks, None).await
+    }
+    
+    pub async fn download_resourcepacks(&self) -> Result<()> {
+        let _lock = self.acquire_install_lock(&self.game_dir, DownloadKind::Resourcepacks, 0.90).await;
+        let resourcepacks_dir = self.game_dir.join("resourcepacks");
+        fs::create_dir_all(&resourcepacks_dir)?;
         
-        let files: Vec<GitHubFile> = response.json().await?;
-        let shader_names: Vec<String> = files.iter()
-            .filter(|f| f.file_type == "file")
-            .map(|f| f.name.clone())
+        let Some(files) = self.list_content(DownloadKind::Resourcepacks, None).await? else {
+            return Ok(());
+        };
+        
+        let names: Vec<String> = files.iter().map(|file| file.name.clone()).collect();
+        remove_unlisted(&resourcepacks_dir, &names, self.config.managed_extensions(DownloadKind::Resourcepacks, &[".zip"]));
+        
+        let pending = files.into_iter()
+            .filter(|file| !file.is_current(&resourcepacks_dir))
+            .map(|file| file.into_pending(&resourcepacks_dir))
             .collect();
         
-        if let Ok(entries) = fs::read_dir(&shaderpacks_dir) {
-            for entry in entries.flatten() {
-                let file_name = entry.file_name().to_string_lossy().to_string();
-                if file_name.ends_with(".zip") && !shader_names.contains(&file_name) {
-                    let _ = fs::remove_file(entry.path());
+        self.download_all(pending, DownloadKind::Resourcepacks, 0.90, 0.94).await
+    }
+
+    async fn list_content(&self, kind: DownloadKind, tier: Option<&str>) -> Result<Option<Vec<RemoteFile>>> {
+        match &self.config.content_manifest_url {
+            Some(manifest_url) => self.list_manifest_content(manifest_url, kind, tier).await,
+            None => match self.list_github_content(kind, tier).await {
+                Err(e) => {
+                    let fallback_url = format!("{}/{}/{}", self.config.mods_raw_base, self.config.content_folder, CONTENT_MANIFEST_FILE);
+                    match self.list_manifest_content(&fallback_url, kind, tier).await {
+                        Ok(Some(files)) => Ok(Some(files)),
+                        _ => Err(e),
+                    }
                 }
-            }
+                listed => listed,
+            },
         }
+    }
+
+    async fn list_github_content(&self, kind: DownloadKind, tier: Option<&str>) -> Result<Option<Vec<RemoteFile>>> {
+        let folder = match tier {
+            Some(tier) => format!("{}/{}", content_path(&self.config.content_folder, kind).trim_end_matches('/'), tier),
+            None => content_path(&self.config.content_folder, kind),
+        };
+        let response = self.github_api_get(&format!("{}/{}", self.config.mods_api_base, folder)).await?;
         
-        let shader_files: Vec<&GitHubFile> = files.iter().filter(|f| f.file_type == "file").collect();
-        let total = shader_files.len();
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(anyhow!("Не удалось получить список файлов: {}", response.status()));
+        }
         
-        for (i, file) in shader_files.iter().enumerate() {
-            let shaderpack_path = shaderpacks_dir.join(&file.name);
-            
-            if shaderpack_path.exists() {
-                continue;
-            }
+        let files: Vec<GitHubFile> = response.json().await?;
+        Ok(Some(files.into_iter()
+            .filter(|f| f.file_type == "file")
+            .map(|f| RemoteFile {
+                url: format!("{}/{}/{}", self.config.mods_raw_base, folder, urlencoding::encode(&f.name)),
+                name: f.name,
+                size: f.size,
+                sha256: None,
+            })
+            .collect()))
+    }
+
+    async fn github_api_get(&self, url: &str) -> Result<reqwest::Response> {
+        let mut attempt = 1;
+        loop {
+            let result = self.client
+                .get(url)
+                .header("User-Agent", "ByStep-Launcher")
+                .header("Accept", "application/vnd.github.v3+json")
+                .send()
+                .await;
             
-            self.report_progress(
-                &format!("Шейдер: {} ({}/{})", file.name, i + 1, total),
-                0.86 + (0.02 * (i as f32 / total.max(1) as f32))
-            );
+            let backoff = GITHUB_API_BACKOFF * 2u32.pow(attempt - 1);
+            let delay = match &result {
+                Ok(response) if is_github_rate_limited(response) => {
+                    return Err(github_rate_limit(response).into());
+                }
+                Ok(response) if response.status().is_server_error()
+                    || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    || (response.status() == reqwest::StatusCode::FORBIDDEN && retry_after(response).is_some()) => {
+                    retry_after(response).unwrap_or(backoff)
+                }
+                Err(e) if e.is_timeout() || e.is_connect() => backoff,
+                _ => return Ok(result?),
+            };
             
-            let raw_url = format!("{}/{}/shaderpacks/{}", MODS_RAW_BASE, self.version.mods_folder(), urlencoding::encode(&file.name));
-            let _ = self.download_file(&raw_url, &shaderpack_path).await;
+            if attempt >= GITHUB_API_ATTEMPTS || delay > GITHUB_API_MAX_RETRY_AFTER {
+                return match result? {
+                    response if matches!(response.status(), reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::TOO_MANY_REQUESTS) => {
+                        Err(github_rate_limit(&response).into())
+                    }
+                    response => Ok(response),
+                };
+            }
+            attempt += 1;
+            tokio::time::sleep(delay).await;
         }
-        
-        Ok(())
     }
-    
-    pub async fn download_resourcepacks(&self) -> Result<()> {
-        let resourcepacks_dir = self.game_dir.join("resourcepacks");
-        fs::create_dir_all(&resourcepacks_dir)?;
-        
-        let api_url = format!("{}/{}/resourcepacks", MODS_API_BASE, self.version.mods_folder());
-        
+
+    async fn list_manifest_content(&self, manifest_url: &str, kind: DownloadKind, tier: Option<&str>) -> Result<Option<Vec<RemoteFile>>> {
         let response = self.client
-            .get(&api_url)
+            .get(manifest_url)
             .header("User-Agent", "ByStep-Launcher")
-            .header("Accept", "application/vnd.github.v3+json")
             .send()
             .await?;
         
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
         if !response.status().is_success() {
-            return Ok(());
+            return Err(anyhow!("Не удалось получить манифест контента: {}", response.status()));
         }
         
-        let files: Vec<GitHubFile> = response.json().await?;
-        let pack_files: Vec<&GitHubFile> = files.iter().filter(|f| f.file_type == "file").collect();
-        let total = pack_files.len();
-        
-        for (i, file) in pack_files.iter().enumerate() {
-            let pack_path = resourcepacks_dir.join(&file.name);
-            
-            if pack_path.exists() {
-                continue;
-            }
-            
-            self.report_progress(
-                &format!("Текстуры: {} ({}/{})", file.name, i + 1, total),
-                0.90 + (0.04 * (i as f32 / total.max(1) as f32))
-            );
-            
-            let raw_url = format!("{}/{}/resourcepacks/{}", MODS_RAW_BASE, self.version.mods_folder(), urlencoding::encode(&file.name));
-            let _ = self.download_file(&raw_url, &pack_path).await;
-        }
+        let manifest: ContentManifest = response.json().await?;
+        let entries = match (kind, tier) {
+            (DownloadKind::Shaderpacks, Some("low")) => manifest.shaderpacks_low,
+            (DownloadKind::Shaderpacks, Some("high")) => manifest.shaderpacks_high,
+            (DownloadKind::Shaderpacks, _) => manifest.shaderpacks,
+            (DownloadKind::Resourcepacks, _) => manifest.resourcepacks,
+            _ => manifest.mods,
+        };
+        let base = reqwest::Url::parse(manifest_url)?;
+        let subfolder = match tier {
+            Some(tier) => format!("{}{}/", content_path("", kind), tier),
+            None => content_path("", kind),
+        };
         
-        Ok(())
+        Ok(Some(entries.into_iter()
+            .filter(|f| is_plain_file_name(&f.name))
+            .filter_map(|f| {
+                let relative = match &f.url {
+                    Some(url) => url.clone(),
+                    None => format!("{}{}", subfolder, urlencoding::encode(&f.name)),
+                };
+                let url = base.join(&relative).ok()?;
+                Some(RemoteFile { name: f.name, url: url.to_string(), size: f.size, sha256: f.sha256 })
+            })
+            .collect()))
     }
 
-    async fn ensure_java(&self) -> Result<()> {
-        let java_version = self.version.java_version();
-        let base_dir = directories::ProjectDirs::from("com", "bystep", "minecraft")
-            .map(|dirs| dirs.data_dir().to_path_buf())
-            .unwrap_or_else(|| {
-                std::env::current_dir()
-                    .unwrap_or_else(|_| PathBuf::from("."))
-                    .join(".bystep-minecraft")
-            });
-        let java_dir = base_dir.join("runtime").join(format!("java-{}", java_version));
+    pub async fn ensure_java(&self) -> Result<()> {
+        let java_version = self.config.java_version;
+        let runtime_dir = self.config.runtime_dir.clone();
+        let java_dir = runtime_dir.join(format!("java-{}", java_version));
         let java_exe = java_dir.join("bin").join("java.exe");
         
-        if java_exe.exists() {
+        if self.config.use_system_java && find_system_java(java_version).is_some() {
+            return Ok(());
+        }
+        
+        if java_exe.exists() && java_major_version(&java_exe).is_none_or(|major| major == java_version) {
+            return Ok(());
+        }
+        
+        let _lock = self.acquire_install_lock(&java_dir, DownloadKind::Game, 0.05).await;
+        if java_exe.exists() && java_major_version(&java_exe).is_none_or(|major| major == java_version) {
             return Ok(());
         }
         
-        let runtime_dir = base_dir.join("runtime");
         fs::create_dir_all(&runtime_dir)?;
         
         let (java_url, prefix) = match java_version {
@@ -276,61 +882,111 @@ impl MinecraftInstaller {
         };
         
         let zip_path = runtime_dir.join(format!("java{}.zip", java_version));
+        self.report_progress(InstallStep::DownloadingJava(java_version), 0.05);
         self.download_file(java_url, &zip_path).await?;
-        self.extract_zip(&zip_path, &runtime_dir)?;
-        let _ = fs::remove_file(&zip_path);
         
-        if let Ok(entries) = fs::read_dir(&runtime_dir) {
-            for entry in entries.flatten() {
-                let name = entry.file_name().to_string_lossy().to_lowercase();
-                if name.starts_with(prefix) || name.starts_with("openjdk") {
-                    let extracted = entry.path();
-                    if extracted != java_dir && extracted.is_dir() {
-                        let _ = fs::rename(&extracted, &java_dir);
+        let mut quarantined = false;
+        for _ in 0..2 {
+            if java_dir.exists() {
+                let _ = fs::remove_dir_all(&java_dir);
+            }
+            self.extract_java(&zip_path, &runtime_dir, java_version).await?;
+            
+            if let Ok(entries) = fs::read_dir(&runtime_dir) {
+                for entry in entries.flatten() {
+                    let name = entry.file_name().to_string_lossy().to_lowercase();
+                    if name.starts_with(prefix) || name.starts_with("openjdk") {
+                        let extracted = entry.path();
+                        if extracted != java_dir && extracted.is_dir() {
+                            let _ = fs::rename(&extracted, &java_dir);
+                        }
                     }
                 }
             }
-        }
-        
-        if !java_exe.exists() {
-            return Err(anyhow!("Failed to install Java {}", java_version));
-        }
+            
+            if !java_exe.exists() {
+                quarantined = true;
+                continue;
+            }
+            
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            
+            if java_exe.exists() && java_runs(&java_exe) {
+                let _ = fs::remove_file(&zip_path);
+                return Ok(());
+            }
+            quarantined = true;
+        }
+        let _ = fs::remove_file(&zip_path);
         
-        Ok(())
+        if quarantined {
+            return Err(anyhow!(
+                "Java {} была удалена или заблокирована после распаковки, вероятно антивирусом. Добавьте папку {} в исключения антивируса и попробуйте снова",
+                java_version,
+                runtime_dir.parent().unwrap_or(&runtime_dir).display()
+            ));
+        }
+        
+        Err(anyhow!("Failed to install Java {}", java_version))
     }
     
-    fn extract_zip(&self, zip_path: &Path, dest: &Path) -> Result<()> {
-        let file = fs::File::open(zip_path)?;
-        let mut archive = zip::ZipArchive::new(file)?;
-        
-        for i in 0..archive.len() {
-            let mut file = archive.by_index(i)?;
-            let outpath = match file.enclosed_name() {
-                Some(path) => dest.join(path),
-                None => continue,
-            };
-            
-            if file.name().ends_with('/') {
-                fs::create_dir_all(&outpath)?;
-            } else {
-                if let Some(p) = outpath.parent() {
-                    if !p.exists() {
-                        fs::create_dir_all(p)?;
-                    }
+    async fn extract_java(&self, zip_path: &Path, dest: &Path, java_version: u8) -> Result<()> {
+        fs::create_dir_all(dest)?;
+        let required = zip_uncompressed_size(zip_path)?;
+        if let Some(available) = available_space(dest) {
+            if available < required + EXTRACT_SPACE_MARGIN {
+                return Err(not_enough_space_error(java_version, dest, required, Some(available)));
+            }
+        }
+        
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let zip_path = zip_path.to_path_buf();
+        let staging = dest.join(format!(".extract-java{}", java_version));
+        let _ = fs::remove_dir_all(&staging);
+        let staging_dir = staging.clone();
+        
+        let task = tokio::task::spawn_blocking(move || {
+            let mut last_percent = None;
+            extract_zip(&zip_path, &staging_dir, |done, total| {
+                let percent = done * 100 / total.max(1);
+                if last_percent != Some(percent) {
+                    last_percent = Some(percent);
+                    let _ = tx.send(percent);
                 }
-                let mut outfile = fs::File::create(&outpath)?;
-                std::io::copy(&mut file, &mut outfile)?;
+            })
+        });
+        
+        while let Some(percent) = rx.recv().await {
+            self.report_progress(
+                InstallStep::ExtractingJava { version: java_version, percent: percent.min(100) as u8 },
+                0.05 + 0.05 * (percent as f32 / 100.0)
+            );
+        }
+        
+        if let Err(e) = task.await? {
+            let _ = fs::remove_dir_all(&staging);
+            if is_storage_full(&e) {
+                return Err(not_enough_space_error(java_version, dest, required, available_space(dest)));
             }
+            return Err(e);
         }
         
+        for entry in fs::read_dir(&staging)?.flatten() {
+            let target = dest.join(entry.file_name());
+            if target.exists() {
+                let _ = fs::remove_dir_all(&target);
+            }
+            fs::rename(entry.path(), &target)?;
+        }
+        let _ = fs::remove_dir_all(&staging);
         Ok(())
     }
 
     async fn download_version_info(&self) -> Result<VersionInfo> {
-        let mc_version = self.version.minecraft_version();
+        let mc_version = self.config.minecraft_version.as_str();
         
         let manifest: VersionManifest = self.client
-            .get(VERSION_MANIFEST_URL)
+            .get(&self.config.version_manifest_url)
             .send()
             .await?
             .json()
@@ -353,28 +1009,34 @@ impl MinecraftInstaller {
         
         let json_path = versions_dir.join(format!("{}.json", mc_version));
         let json_content = serde_json::to_string_pretty(&version_info)?;
-        fs::write(&json_path, json_content)?;
+        write_json_file(&json_path, &json_content)?;
 
         Ok(version_info)
     }
 
-    async fn download_client(&self, version_info: &VersionInfo) -> Result<()> {
-        let mc_version = self.version.minecraft_version();
+    fn plan_client(&self, version_info: &VersionInfo) -> Result<Vec<PendingDownload>> {
+        let mc_version = self.config.minecraft_version.as_str();
         let versions_dir = self.game_dir.join("versions").join(mc_version);
         fs::create_dir_all(&versions_dir)?;
         
         let jar_path = versions_dir.join(format!("{}.jar", mc_version));
         
         if jar_path.exists() {
-            return Ok(());
+            return Ok(Vec::new());
         }
 
-        self.download_file(&version_info.downloads.client.url, &jar_path).await?;
-        Ok(())
+        Ok(vec![PendingDownload {
+            url: version_info.downloads.client.url.clone(),
+            path: jar_path,
+            size: version_info.downloads.client.size,
+            required: true,
+            sha256: None,
+        }])
     }
 
-    async fn download_libraries(&self, version_info: &VersionInfo) -> Result<()> {
+    fn plan_libraries(&self, version_info: &VersionInfo) -> Result<Vec<PendingDownload>> {
         let libraries_dir = self.game_dir.join("libraries");
+        let mut pending = Vec::new();
         
         for library in &version_info.libraries {
             if !self.should_use_library(library) {
@@ -393,12 +1055,71 @@ impl MinecraftInstaller {
                         fs::create_dir_all(parent)?;
                     }
 
-                    let _ = self.download_file(&artifact.url, &lib_path).await;
+                    pending.push(PendingDownload {
+                        url: artifact.url.clone(),
+                        path: lib_path,
+                        size: artifact.size,
+                        required: false,
+                        sha256: None,
+                    });
                 }
             }
         }
 
-        Ok(())
+        Ok(pending)
+    }
+
+    async fn download_all(&self, files: Vec<PendingDownload>, kind: DownloadKind, start: f32, end: f32) -> Result<()> {
+        if files.is_empty() {
+            return Ok(());
+        }
+        
+        let progress = ByteProgress::default();
+        progress.total.store(files.iter().map(|f| f.size).sum(), Ordering::Relaxed);
+        let progress = &progress;
+        
+        let mut downloads = stream::iter(files)
+            .map(|file| async move {
+                let mut result = self.download_tracked(&file.url, &file.path, file.size == 0, Some(progress)).await;
+                if let (Ok(()), Some(expected)) = (&result, &file.sha256) {
+                    if !file_sha256(&file.path).is_some_and(|actual| actual.eq_ignore_ascii_case(expected)) {
+                        let _ = fs::remove_file(&file.path);
+                        result = Err(anyhow!("Контрольная сумма не совпадает: {}", file.path.display()));
+                    }
+                }
+                (file.required, result)
+            })
+            .buffer_unordered(MAX_CONCURRENT_DOWNLOADS);
+        
+        let mut ticker = tokio::time::interval(std::time::Duration::from_millis(250));
+        let mut required_error = None;
+        loop {
+            tokio::select! {
+                next = downloads.next() => match next {
+                    Some((required, Err(e))) if required || is_folder_access_error(&e) => {
+                        required_error.get_or_insert(e);
+                    }
+                    Some(_) => {}
+                    None => break,
+                },
+                _ = ticker.tick() => self.report_bytes(kind, progress, start, end),
+            }
+        }
+        self.report_bytes(kind, progress, start, end);
+        
+        match required_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    fn report_bytes(&self, kind: DownloadKind, progress: &ByteProgress, start: f32, end: f32) {
+        let done = progress.done.load(Ordering::Relaxed);
+        let total = progress.total.load(Ordering::Relaxed).max(done).max(1);
+        self.report_progress(
+            InstallStep::Downloading { kind, done, total },
+            start + (end - start) * (done as f32 / total as f32)
+        );
     }
 
     fn should_use_library(&self, library: &Library) -> bool {
@@ -418,7 +1139,7 @@ impl MinecraftInstaller {
         true
     }
 
-    async fn download_assets(&self, version_info: &VersionInfo) -> Result<()> {
+    async fn plan_assets(&self, version_info: &VersionInfo) -> Result<Vec<PendingDownload>> {
         let indexes_dir = self.game_dir.join("assets").join("indexes");
         let objects_dir = self.game_dir.join("assets").join("objects");
         fs::create_dir_all(&indexes_dir)?;
@@ -433,6 +1154,7 @@ impl MinecraftInstaller {
         let index_content = fs::read_to_string(&index_path)?;
         let asset_index: AssetIndex = serde_json::from_str(&index_content)?;
 
+        let mut pending = Vec::new();
         for (_name, object) in &asset_index.objects {
             let hash_prefix = &object.hash[..2];
             let object_dir = objects_dir.join(hash_prefix);
@@ -445,64 +1167,154 @@ impl MinecraftInstaller {
             }
 
             let url = format!(
-                "https://resources.download.minecraft.net/{}/{}",
-                hash_prefix, object.hash
+                "{}/{}/{}",
+                self.config.resources_url, hash_prefix, object.hash
             );
 
-            let _ = self.download_file(&url, &object_path).await;
+            pending.push(PendingDownload {
+                url,
+                path: object_path,
+                size: object.size,
+                required: false,
+                sha256: None,
+            });
         }
 
+        Ok(pending)
+    }
+
+    fn materialize_legacy_assets(&self, version_info: &VersionInfo) -> Result<()> {
+        let index_id = &version_info.asset_index.id;
+        let assets_dir = self.game_dir.join("assets");
+        let index_path = assets_dir.join("indexes").join(format!("{}.json", index_id));
+        let asset_index: AssetIndex = serde_json::from_str(&fs::read_to_string(&index_path)?)?;
+        
+        let target_dir = match legacy_assets_dir(&self.game_dir, index_id, &asset_index) {
+            Some(dir) => dir,
+            None => return Ok(()),
+        };
+        
+        let objects_dir = assets_dir.join("objects");
+        let total = asset_index.objects.len();
+        for (i, (name, object)) in asset_index.objects.iter().enumerate() {
+            let target = target_dir.join(name);
+            let source = objects_dir.join(&object.hash[..2]).join(&object.hash);
+            if target.exists() || !source.exists() {
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            if fs::hard_link(&source, &target).is_err() {
+                fs::copy(&source, &target)?;
+            }
+            if i % 250 == 0 {
+                self.report_progress(InstallStep::PreparingAssets { done: i, total }, 0.85);
+            }
+        }
         Ok(())
     }
 
-    async fn install_fabric(&self) -> Result<()> {
-        let mc_version = self.version.minecraft_version();
-        let loader_version = self.version.fabric_loader_version();
+    async fn plan_fabric(&self) -> Result<(serde_json::Value, Vec<PendingDownload>)> {
+        let mc_version = self.config.minecraft_version.as_str();
+        let loader_version = self.config.loader_version.as_str();
         
         let fabric_profile_url = format!(
             "{}/v2/versions/loader/{}/{}/profile/json",
-            FABRIC_META_URL, mc_version, loader_version
+            self.config.fabric_meta_url, mc_version, loader_version
         );
 
-        let fabric_profile: serde_json::Value = self.client
+        let response = self.client
             .get(&fabric_profile_url)
             .send()
-            .await?
-            .json()
             .await?;
+        
+        if !response.status().is_success() {
+            return Err(anyhow!("Не удалось получить профиль Fabric: {}", response.status()));
+        }
+        
+        let fabric_profile: serde_json::Value = response.json().await?;
 
-        let fabric_version_id = format!("fabric-loader-{}-{}", loader_version, mc_version);
-        let fabric_dir = self.game_dir.join("versions").join(&fabric_version_id);
-        fs::create_dir_all(&fabric_dir)?;
+        let mut pending = Vec::new();
+        for library in self.fabric_libraries(&fabric_profile) {
+            if library.path.exists() {
+                continue;
+            }
+            if let Some(parent) = library.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            pending.push(library);
+        }
 
-        let json_path = fabric_dir.join(format!("{}.json", fabric_version_id));
-        fs::write(&json_path, serde_json::to_string_pretty(&fabric_profile)?)?;
+        Ok((fabric_profile, pending))
+    }
 
+    fn fabric_libraries(&self, fabric_profile: &serde_json::Value) -> Vec<PendingDownload> {
+        let mut libraries_out = Vec::new();
         if let Some(libraries) = fabric_profile.get("libraries").and_then(|l| l.as_array()) {
             for lib in libraries {
-                if let (Some(name), Some(url)) = (
-                    lib.get("name").and_then(|n| n.as_str()),
-                    lib.get("url").and_then(|u| u.as_str()),
-                ) {
-                    let path = self.maven_name_to_path(name);
-                    let lib_path = self.game_dir.join("libraries").join(&path);
-                    
-                    if lib_path.exists() {
-                        continue;
-                    }
-
-                    if let Some(parent) = lib_path.parent() {
-                        fs::create_dir_all(parent)?;
-                    }
-
-                    let full_url = format!("{}{}", url, path);
-                    let _ = self.download_file(&full_url, &lib_path).await;
-                }
+                let name = match lib.get("name").and_then(|n| n.as_str()) {
+                    Some(name) => name,
+                    None => continue,
+                };
+                let artifact = lib.get("downloads").and_then(|d| d.get("artifact"));
+                let path = artifact
+                    .and_then(|a| a.get("path"))
+                    .and_then(|p| p.as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| maven_name_to_path(name));
+                let url = match artifact.and_then(|a| a.get("url")).and_then(|u| u.as_str()) {
+                    Some(url) => url.to_string(),
+                    None => maven_url(lib.get("url").and_then(|u| u.as_str()).unwrap_or(&self.config.maven_url), &path),
+                };
+                let size = artifact
+                    .and_then(|a| a.get("size"))
+                    .or_else(|| lib.get("size"))
+                    .and_then(|s| s.as_u64())
+                    .unwrap_or(0);
+                
+                libraries_out.push(PendingDownload {
+                    url,
+                    path: self.game_dir.join("libraries").join(&path),
+                    size,
+                    required: true,
+                    sha256: None,
+                });
             }
         }
+        libraries_out
+    }
 
-        self.create_default_options()?;
+    fn verify_fabric_libraries(&self, fabric_profile: &serde_json::Value) -> Result<()> {
+        let missing: Vec<String> = self.fabric_libraries(fabric_profile)
+            .into_iter()
+            .filter(|library| !library.path.exists())
+            .map(|library| library.path.file_name().unwrap_or_default().to_string_lossy().to_string())
+            .collect();
+        
+        if !missing.is_empty() {
+            return Err(anyhow!("Fabric установлен не полностью, отсутствуют: {}", missing.join(", ")));
+        }
+        if !self.fabric_loader_jar().exists() {
+            return Err(anyhow!("Fabric Loader {} не найден после установки", self.config.loader_version));
+        }
+        Ok(())
+    }
+
+    fn fabric_loader_jar(&self) -> PathBuf {
+        let loader_version = self.config.loader_version.as_str();
+        self.game_dir
+            .join("libraries")
+            .join(maven_name_to_path(&format!("net.fabricmc:fabric-loader:{}", loader_version)))
+    }
 
+    fn write_fabric_profile(&self, fabric_profile: &serde_json::Value) -> Result<()> {
+        let fabric_version_id = format!("fabric-loader-{}-{}", self.config.loader_version, self.config.minecraft_version);
+        let fabric_dir = self.game_dir.join("versions").join(&fabric_version_id);
+        fs::create_dir_all(&fabric_dir)?;
+
+        let json_path = fabric_dir.join(format!("{}.json", fabric_version_id));
+        write_json_file(&json_path, &serde_json::to_string_pretty(fabric_profile)?)?;
         Ok(())
     }
 
@@ -545,37 +1357,970 @@ resourcePacks:["vanilla"]
         Ok(())
     }
     
-    fn maven_name_to_path(&self, name: &str) -> String {
-        let parts: Vec<&str> = name.split(':').collect();
-        if parts.len() >= 3 {
-            let group = parts[0].replace('.', "/");
-            let artifact = parts[1];
-            let version = parts[2];
-            format!("{}/{}/{}/{}-{}.jar", group, artifact, version, artifact, version)
-        } else {
-            name.to_string()
-        }
+    async fn download_file(&self, url: &str, path: &Path) -> Result<()> {
+        self.download_tracked(url, path, false, None).await
     }
 
-    async fn download_file(&self, url: &str, path: &Path) -> Result<()> {
-        let response = self.client
-            .get(url)
-            .header("User-Agent", "ByStep-Launcher")
-            .send()
-            .await?;
+    async fn download_tracked(&self, url: &str, path: &Path, size_unknown: bool, progress: Option<&ByteProgress>) -> Result<()> {
+        let _slot = acquire_host_slot(url).await;
+        let mut resume_from = resume_offset(path);
+        let request = |resume_from: u64| {
+            let request = self.client
+                .get(url)
+                .header("User-Agent", "ByStep-Launcher");
+            match resume_from {
+                0 => request,
+                from => request.header("Range", format!("bytes={}-", from)),
+            }
+        };
+        let mut response = request(resume_from).send().await?;
         
+        if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+            let _ = fs::remove_file(part_path(path));
+            let _ = fs::remove_file(part_len_path(path));
+            resume_from = 0;
+            response = request(resume_from).send().await?;
+        }
         if !response.status().is_success() {
             return Err(anyhow!("Failed to download: {}", url));
         }
         
-        let mut file = fs::File::create(path)?;
-        let mut stream = response.bytes_stream();
+        if let (true, Some(progress), Some(length)) = (size_unknown, progress, response.content_length()) {
+            let resumed = if response.status() == reqwest::StatusCode::PARTIAL_CONTENT { resume_from } else { 0 };
+            progress.total.fetch_add(length + resumed, Ordering::Relaxed);
+        }
+        
+        let part_path = part_path(path);
+        let resumed = if response.status() == reqwest::StatusCode::PARTIAL_CONTENT { resume_from } else { 0 };
+        if let Some(progress) = progress {
+            progress.done.fetch_add(resumed, Ordering::Relaxed);
+        }
+        let mut written = resumed;
+        let expected_len = response.content_length().map(|length| resumed + length);
+        let result: Result<()> = async {
+            let file = if resumed > 0 {
+                let mut file = fs::OpenOptions::new().write(true).open(&part_path)?;
+                file.seek(SeekFrom::Start(resumed))?;
+                file
+            } else {
+                retry_file_op(path, || fs::File::create(&part_path))?
+            };
+            let mut part = PartFile { file, len_path: None, written, recorded: written };
+            if let Some(expected_len) = expected_len.filter(|len| *len >= PREALLOCATE_MIN_LEN) {
+                part.len_path = Some(part_len_path(path));
+                part.record_written()?;
+                part.file.set_len(expected_len).map_err(|e| match e.kind() {
+                    std::io::ErrorKind::StorageFull => anyhow::Error::new(e).context(format!(
+                        "Недостаточно места на диске для {} ({:.1} МБ)",
+                        path.display(),
+                        expected_len as f64 / 1_048_576.0
+                    )),
+                    _ => e.into(),
+                })?;
+            }
+            let mut stream = response.bytes_stream();
+
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk?;
+                throttle_download(chunk.len()).await;
+                part.file.write_all(&chunk)?;
+                written += chunk.len() as u64;
+                part.written = written;
+                if part.len_path.is_some() && written - part.recorded >= PART_LEN_RECORD_STEP {
+                    part.record_written()?;
+                }
+                if let Some(progress) = progress {
+                    progress.done.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+                }
+            }
+            Ok(())
+        }.await;
+        
+        if let Err(e) = result {
+            if let Some(progress) = progress {
+                progress.done.fetch_sub(written, Ordering::Relaxed);
+            }
+            return Err(e);
+        }
+        
+        retry_file_op(path, || fs::rename(&part_path, path))?;
+        let _ = fs::remove_file(part_len_path(path));
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum InstallPhase {
+    Client,
+    Libraries,
+    Assets,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct InstallMarker {
+    minecraft_version: String,
+    completed: Vec<InstallPhase>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StagedContent {
+    files: HashMap<String, Vec<String>>,
+}
+
+struct RemoteFile {
+    name: String,
+    url: String,
+    size: u64,
+    sha256: Option<String>,
+}
+
+impl RemoteFile {
+    fn is_current(&self, dir: &Path) -> bool {
+        let path = dir.join(&self.name);
+        match &self.sha256 {
+            Some(expected) => file_sha256(&path).is_some_and(|actual| actual.eq_ignore_ascii_case(expected)),
+            None => path.exists(),
+        }
+    }
 
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
-            file.write_all(&chunk)?;
+    fn into_pending(self, dir: &Path) -> PendingDownload {
+        PendingDownload {
+            path: dir.join(&self.name),
+            url: self.url,
+            size: self.size,
+            required: false,
+            sha256: self.sha256,
         }
+    }
+}
+
+struct PendingDownload {
+    url: String,
+    path: PathBuf,
+    size: u64,
+    required: bool,
+    sha256: Option<String>,
+}
 
+struct PartFile {
+    file: fs::File,
+    len_path: Option<PathBuf>,
+    written: u64,
+    recorded: u64,
+}
+
+impl PartFile {
+    fn record_written(&mut self) -> std::io::Result<()> {
+        if let Some(len_path) = &self.len_path {
+            self.file.sync_data()?;
+            fs::write(len_path, self.written.to_string())?;
+            self.recorded = self.written;
+        }
         Ok(())
     }
 }
+
+impl Drop for PartFile {
+    fn drop(&mut self) {
+        let _ = self.file.set_len(self.written);
+        let _ = self.record_written();
+    }
+}
+
+#[derive(Default)]
+struct ByteProgress {
+    done: AtomicU64,
+    total: AtomicU64,
+}
+
+fn content_dir_name(kind: DownloadKind) -> &'static str {
+    match kind {
+        DownloadKind::Shaderpacks => "shaderpacks",
+        DownloadKind::Resourcepacks => "resourcepacks",
+        _ => "mods",
+    }
+}
+
+fn content_path(folder: &str, kind: DownloadKind) -> String {
+    let sub = match kind {
+        DownloadKind::Shaderpacks => "shaderpacks",
+        DownloadKind::Resourcepacks => "resourcepacks",
+        _ => return folder.to_string(),
+    };
+    if folder.is_empty() {
+        format!("{}/", sub)
+    } else {
+        format!("{}/{}", folder, sub)
+    }
+}
+
+fn maven_name_to_path(name: &str) -> String {
+    let (coords, extension) = name.split_once('@').unwrap_or((name, "jar"));
+    let parts: Vec<&str> = coords.split(':').collect();
+    if parts.len() >= 3 {
+        let group = parts[0].replace('.', "/");
+        let artifact = parts[1];
+        let version = parts[2];
+        let file_name = match parts.get(3).filter(|classifier| !classifier.is_empty()) {
+            Some(classifier) => format!("{}-{}-{}.{}", artifact, version, classifier, extension),
+            None => format!("{}-{}.{}", artifact, version, extension),
+        };
+        format!("{}/{}/{}/{}", group, artifact, version, file_name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn header_u64(response: &reqwest::Response, name: &str) -> Option<u64> {
+    response.headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+}
+
+fn retry_after(response: &reqwest::Response) -> Option<std::time::Duration> {
+    header_u64(response, "retry-after").map(std::time::Duration::from_secs)
+}
+
+fn is_github_rate_limited(response: &reqwest::Response) -> bool {
+    matches!(response.status(), reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::TOO_MANY_REQUESTS)
+        && header_u64(response, "x-ratelimit-remaining") == Some(0)
+}
+
+fn github_rate_limit(response: &reqwest::Response) -> GitHubRateLimited {
+    GitHubRateLimited {
+        reset_at: header_u64(response, "x-ratelimit-reset")
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs as i64, 0))
+            .map(|time| time.with_timezone(&chrono::Local).format("%H:%M").to_string()),
+    }
+}
+
+#[derive(Debug)]
+pub struct GitHubRateLimited {
+    pub reset_at: Option<String>,
+}
+
+impl std::fmt::Display for GitHubRateLimited {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.reset_at {
+            Some(time) => write!(f, "Превышен лимит запросов к GitHub, синхронизация пропущена. Лимит сбросится в {}", time),
+            None => write!(f, "Превышен лимит запросов к GitHub, синхронизация пропущена"),
+        }
+    }
+}
+
+impl std::error::Error for GitHubRateLimited {}
+
+pub fn is_rate_limited(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| cause.is::<GitHubRateLimited>())
+}
+
+fn record_synced_shaderpacks(shaderpacks_dir: &Path, quality: ShaderQuality, names: Vec<String>) {
+    let path = shaderpacks_dir.join(SYNCED_SHADERPACKS_FILE);
+    let mut synced: HashMap<ShaderQuality, Vec<String>> = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+    synced.insert(quality, names);
+    if let Ok(json) = serde_json::to_string_pretty(&synced) {
+        let _ = fs::write(&path, json);
+    }
+}
+
+pub fn synced_shaderpacks(game_dir: &Path, quality: ShaderQuality) -> Vec<String> {
+    fs::read_to_string(game_dir.join("shaderpacks").join(SYNCED_SHADERPACKS_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str::<HashMap<ShaderQuality, Vec<String>>>(&content).ok())
+        .and_then(|mut synced| synced.remove(&quality))
+        .unwrap_or_default()
+}
+
+pub fn version_asset_index_id(game_dir: &Path, mc_version: &str) -> Option<String> {
+    let path = game_dir.join("versions").join(mc_version).join(format!("{}.json", mc_version));
+    let content = fs::read_to_string(path).ok()?;
+    let version_info: VersionInfo = serde_json::from_str(&content).ok()?;
+    Some(version_info.asset_index.id)
+}
+
+fn fabric_profile_valid(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .is_some_and(|profile| profile.get("mainClass").is_some_and(|main| main.is_string()))
+}
+
+fn write_json_file(path: &Path, content: &str) -> Result<()> {
+    let tmp = part_path(path);
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn remove_unlisted(dir: &Path, names: &[String], managed_extensions: &[&str]) {
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if managed_extensions.iter().any(|ext| file_name.ends_with(ext)) && !names.contains(&file_name) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+fn content_outdated(dir: &Path, files: &[RemoteFile], managed_extensions: &[&str]) -> bool {
+    if files.iter().any(|file| !file.is_current(dir)) {
+        return true;
+    }
+    fs::read_dir(dir)
+        .map(|entries| entries.flatten().any(|entry| {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            managed_extensions.iter().any(|ext| file_name.ends_with(ext))
+                && !files.iter().any(|file| file.name == file_name)
+        }))
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifyReport {
+    pub checked: usize,
+    pub corrupted: usize,
+    pub fixed: usize,
+}
+
+enum ExpectedHash {
+    Sha1(String),
+    Sha256(String),
+    Size(u64),
+}
+
+impl ExpectedHash {
+    fn matches(&self, path: &Path) -> bool {
+        match self {
+            ExpectedHash::Sha1(expected) => file_sha1(path).is_some_and(|actual| actual.eq_ignore_ascii_case(expected)),
+            ExpectedHash::Sha256(expected) => file_sha256(path).is_some_and(|actual| actual.eq_ignore_ascii_case(expected)),
+            ExpectedHash::Size(size) => fs::metadata(path).is_ok_and(|m| m.is_file() && (*size == 0 || m.len() == *size)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContentVersions {
+    pub mods: Option<String>,
+    pub shaderpacks: Option<String>,
+    pub resourcepacks: Option<String>,
+}
+
+pub fn content_versions(game_dir: &Path) -> ContentVersions {
+    ContentVersions {
+        mods: content_fingerprint(&game_dir.join("mods"), &[".jar", ".zip"]),
+        shaderpacks: content_fingerprint(&game_dir.join("shaderpacks"), &[".zip"]),
+        resourcepacks: content_fingerprint(&game_dir.join("resourcepacks"), &[".zip"]),
+    }
+}
+
+fn content_fingerprint(dir: &Path, extensions: &[&str]) -> Option<String> {
+    let mut files: Vec<(String, String)> = fs::read_dir(dir).ok()?
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !extensions.iter().any(|ext| name.ends_with(ext)) {
+                return None;
+            }
+            Some((name, file_sha256(&entry.path())?))
+        })
+        .collect();
+    if files.is_empty() {
+        return None;
+    }
+    files.sort();
+    
+    let mut hasher = Sha256::new();
+    for (name, hash) in &files {
+        hasher.update(format!("{}:{}\n", name, hash).as_bytes());
+    }
+    Some(format!("{:x}", hasher.finalize())[..CONTENT_FINGERPRINT_LEN].to_string())
+}
+
+fn file_sha1(path: &Path) -> Option<String> {
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = Sha1::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+pub fn file_sha256(path: &Path) -> Option<String> {
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+fn is_plain_file_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['/', '\\']) && name != "." && name != ".."
+}
+
+fn maven_url(base: &str, path: &str) -> String {
+    if base.ends_with(".jar") {
+        return base.to_string();
+    }
+    format!("{}/{}", base.trim_end_matches('/'), path.trim_start_matches('/'))
+}
+
+pub fn legacy_assets_dir(game_dir: &Path, index_id: &str, asset_index: &AssetIndex) -> Option<PathBuf> {
+    if asset_index.map_to_resources {
+        Some(game_dir.join("resources"))
+    } else if asset_index.is_virtual {
+        Some(game_dir.join("assets").join("virtual").join(index_id))
+    } else {
+        None
+    }
+}
+
+fn is_access_error(error: &std::io::Error) -> bool {
+    is_lock_error(error) || error.kind() == std::io::ErrorKind::PermissionDenied
+}
+
+fn is_lock_error(error: &std::io::Error) -> bool {
+    matches!(error.raw_os_error(), Some(ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION))
+}
+
+pub fn is_folder_access_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| cause.downcast_ref::<std::io::Error>().is_some_and(is_access_error))
+}
+
+fn retry_file_op<T>(path: &Path, mut op: impl FnMut() -> std::io::Result<T>) -> Result<T> {
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(e) if is_access_error(&e) && attempt < FILE_OP_ATTEMPTS => {
+                attempt += 1;
+                std::thread::sleep(FILE_OP_RETRY_DELAY);
+            }
+            Err(e) if is_lock_error(&e) => {
+                return Err(e).with_context(|| format!("Файл {} занят другим процессом. Скорее всего, игра ещё запущена: закройте её и повторите", path.display()));
+            }
+            Err(e) if is_access_error(&e) => {
+                return Err(e).with_context(|| format!("Нет прав на запись в {}. Снимите с папки атрибут «Только чтение» или проверьте права доступа", path.display()));
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+fn part_len_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part.len");
+    path.with_file_name(name)
+}
+
+fn resume_offset(path: &Path) -> u64 {
+    let part_len = fs::metadata(part_path(path)).map(|m| m.len()).unwrap_or(0);
+    fs::read_to_string(part_len_path(path))
+        .ok()
+        .and_then(|written| written.trim().parse::<u64>().ok())
+        .map_or(part_len, |written| written.min(part_len))
+}
+
+fn part_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+fn java_runs(java_exe: &Path) -> bool {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    
+    std::process::Command::new(java_exe)
+        .arg("-version")
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+fn zip_uncompressed_size(zip_path: &Path) -> Result<u64> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(zip_path)?)?;
+    let mut total = 0;
+    for i in 0..archive.len() {
+        total += archive.by_index_raw(i)?.size();
+    }
+    Ok(total)
+}
+
+fn available_space(path: &Path) -> Option<u64> {
+    let path = path.canonicalize().ok()?;
+    sysinfo::Disks::new_with_refreshed_list()
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+fn is_storage_full(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause.downcast_ref::<std::io::Error>()
+            .is_some_and(|io| io.kind() == std::io::ErrorKind::StorageFull)
+    })
+}
+
+fn not_enough_space_error(java_version: u8, dest: &Path, required: u64, available: Option<u64>) -> anyhow::Error {
+    let required_mb = (required + EXTRACT_SPACE_MARGIN) / (1024 * 1024);
+    match available {
+        Some(available) => anyhow!(
+            "Недостаточно места для распаковки Java {}: нужно {} МБ, свободно {} МБ ({})",
+            java_version,
+            required_mb,
+            available / (1024 * 1024),
+            dest.display()
+        ),
+        None => anyhow!(
+            "Недостаточно места для распаковки Java {}: нужно {} МБ ({})",
+            java_version,
+            required_mb,
+            dest.display()
+        ),
+    }
+}
+
+fn extract_zip(zip_path: &Path, dest: &Path, mut on_progress: impl FnMut(usize, usize)) -> Result<()> {
+    let file = fs::File::open(zip_path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
+    let total = archive.len();
+    
+    for i in 0..total {
+        on_progress(i, total);
+        let mut file = archive.by_index(i)?;
+        let outpath = match file.enclosed_name() {
+            Some(path) => dest.join(path),
+            None => continue,
+        };
+        
+        if file.is_dir() {
+            fs::create_dir_all(&outpath)?;
+            continue;
+        }
+        
+        if let Some(p) = outpath.parent() {
+            if !p.exists() {
+                fs::create_dir_all(p)?;
+            }
+        }
+        
+        if file.is_symlink() {
+            let mut target = String::new();
+            std::io::Read::read_to_string(&mut file, &mut target)?;
+            if symlink_stays_inside(dest, &outpath, Path::new(&target)) {
+                #[cfg(unix)]
+                {
+                    let _ = fs::remove_file(&outpath);
+                    std::os::unix::fs::symlink(&target, &outpath)?;
+                }
+            }
+            continue;
+        }
+        
+        let mut outfile = fs::File::create(&outpath)?;
+        std::io::copy(&mut file, &mut outfile)?;
+        
+        #[cfg(unix)]
+        if let Some(mode) = file.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&outpath, fs::Permissions::from_mode(mode & 0o777))?;
+        }
+    }
+    on_progress(total, total);
+    
+    Ok(())
+}
+
+fn symlink_stays_inside(dest: &Path, link: &Path, target: &Path) -> bool {
+    use std::path::Component;
+    
+    if target.is_absolute() {
+        return false;
+    }
+    let mut resolved = match link.parent() {
+        Some(parent) => parent.to_path_buf(),
+        None => return false,
+    };
+    for component in target.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return false;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return false,
+        }
+    }
+    resolved.starts_with(dest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    const CLIENT_JAR: &[u8] = b"client jar";
+    const LOGGING_JAR: &[u8] = b"logging jar";
+    const LOADER_JAR: &[u8] = b"fabric loader jar";
+    const INTERMEDIARY_JAR: &[u8] = b"intermediary jar";
+    const SODIUM_JAR: &[u8] = b"sodium jar";
+    const LANG_HASH: &str = "0a1b2c3d4e5f60718293a4b5c6d7e8f901234567";
+    const ICON_HASH: &str = "ff00ee11dd22cc33bb44aa5566778899aabbccdd";
+
+    async fn serve(server: &MockServer, route: &str, body: impl Into<Vec<u8>>) {
+        Mock::given(method("GET"))
+            .and(path(route))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(body))
+            .mount(server)
+            .await;
+    }
+
+    async fn serve_json(server: &MockServer, route: &str, body: serde_json::Value) {
+        serve(server, route, body.to_string()).await;
+    }
+
+    struct RangedBody(Vec<u8>);
+
+    impl Respond for RangedBody {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let start = request.headers.get("Range")
+                .and_then(|range| range.to_str().ok())
+                .and_then(|range| range.strip_prefix("bytes="))
+                .and_then(|range| range.trim_end_matches('-').parse::<usize>().ok());
+            match start {
+                None => ResponseTemplate::new(200).set_body_bytes(self.0.clone()),
+                Some(start) if start >= self.0.len() => ResponseTemplate::new(416),
+                Some(start) => ResponseTemplate::new(206).set_body_bytes(&self.0[start..]),
+            }
+        }
+    }
+
+    async fn serve_ranged(server: &MockServer, route: &str, body: impl Into<Vec<u8>>) {
+        Mock::given(method("GET"))
+            .and(path(route))
+            .respond_with(RangedBody(body.into()))
+            .mount(server)
+            .await;
+    }
+
+    fn range_requests(requests: &[Request]) -> Vec<String> {
+        requests.iter()
+            .filter_map(|request| request.headers.get("Range"))
+            .map(|range| range.to_str().unwrap_or_default().to_string())
+            .collect()
+    }
+
+    async fn mock_content_server() -> MockServer {
+        let server = MockServer::start().await;
+        let base = server.uri();
+
+        serve_json(&server, "/mc/version_manifest_v2.json", serde_json::json!({
+            "versions": [
+                { "id": "1.21.1", "url": format!("{}/v1/packages/1.21.1.json", base) },
+                { "id": "1.20.1", "url": format!("{}/v1/packages/1.20.1.json", base) },
+            ]
+        })).await;
+        serve_json(&server, "/v1/packages/1.20.1.json", serde_json::json!({
+            "id": "1.20.1",
+            "mainClass": "net.minecraft.client.main.Main",
+            "assetIndex": { "id": "5", "url": format!("{}/v1/packages/5.json", base), "sha1": "" },
+            "downloads": {
+                "client": { "url": format!("{}/v1/objects/client.jar", base), "sha1": "", "size": CLIENT_JAR.len() }
+            },
+            "libraries": [
+                {
+                    "name": "com.mojang:logging:1.1.1",
+                    "downloads": { "artifact": {
+                        "path": "com/mojang/logging/1.1.1/logging-1.1.1.jar",
+                        "url": format!("{}/libraries/com/mojang/logging/1.1.1/logging-1.1.1.jar", base),
+                        "sha1": "",
+                        "size": LOGGING_JAR.len()
+                    } }
+                },
+                {
+                    "name": "org.lwjgl:lwjgl:3.3.1:natives-macos",
+                    "rules": [{ "action": "allow", "os": { "name": "osx" } }],
+                    "downloads": { "artifact": {
+                        "path": "org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-natives-macos.jar",
+                        "url": format!("{}/libraries/org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-natives-macos.jar", base),
+                        "sha1": "",
+                        "size": 1
+                    } }
+                }
+            ]
+        })).await;
+        serve(&server, "/v1/objects/client.jar", CLIENT_JAR).await;
+        serve(&server, "/libraries/com/mojang/logging/1.1.1/logging-1.1.1.jar", LOGGING_JAR).await;
+
+        serve_json(&server, "/v1/packages/5.json", serde_json::json!({
+            "objects": {
+                "minecraft/lang/ru_ru.json": { "hash": LANG_HASH, "size": 2 },
+                "icons/icon_16x16.png": { "hash": ICON_HASH, "size": 4 }
+            }
+        })).await;
+        serve(&server, &format!("/resources/0a/{}", LANG_HASH), &b"{}"[..]).await;
+        serve(&server, &format!("/resources/ff/{}", ICON_HASH), &b"\x89PNG"[..]).await;
+
+        serve_json(&server, "/fabric/v2/versions/loader/1.20.1/0.16.10/profile/json", serde_json::json!({
+            "id": "fabric-loader-0.16.10-1.20.1",
+            "inheritsFrom": "1.20.1",
+            "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient",
+            "libraries": [
+                { "name": "net.fabricmc:fabric-loader:0.16.10", "url": format!("{}/maven/", base) },
+                { "name": "net.fabricmc:intermediary:1.20.1", "url": format!("{}/maven/", base) }
+            ]
+        })).await;
+        serve(&server, "/maven/net/fabricmc/fabric-loader/0.16.10/fabric-loader-0.16.10.jar", LOADER_JAR).await;
+        serve(&server, "/maven/net/fabricmc/intermediary/1.20.1/intermediary-1.20.1.jar", INTERMEDIARY_JAR).await;
+
+        serve_json(&server, "/github/contents/1.20.1-fabric", serde_json::json!([
+            { "name": "sodium.jar", "size": SODIUM_JAR.len(), "type": "file", "download_url": null },
+            { "name": "README.md", "size": 6, "type": "file", "download_url": null },
+            { "name": "shaderpacks", "size": 0, "type": "dir", "download_url": null }
+        ])).await;
+        serve(&server, "/raw/1.20.1-fabric/sodium.jar", SODIUM_JAR).await;
+
+        server
+    }
+
+    fn runtime_with_java() -> tempfile::TempDir {
+        let runtime_dir = tempfile::tempdir().unwrap();
+        let java_exe = runtime_dir.path().join("java-17").join("bin").join("java.exe");
+        fs::create_dir_all(java_exe.parent().unwrap()).unwrap();
+        fs::write(&java_exe, b"").unwrap();
+        runtime_dir
+    }
+
+    fn mock_config(server: &MockServer, runtime_dir: &Path) -> InstallerConfig {
+        let base = server.uri();
+        InstallerConfig {
+            minecraft_version: "1.20.1".to_string(),
+            loader_version: "0.16.10".to_string(),
+            java_version: 17,
+            content_folder: "1.20.1-fabric".to_string(),
+            version_manifest_url: format!("{}/mc/version_manifest_v2.json", base),
+            fabric_meta_url: format!("{}/fabric", base),
+            maven_url: format!("{}/maven/", base),
+            mods_api_base: format!("{}/github/contents", base),
+            mods_raw_base: format!("{}/raw", base),
+            resources_url: format!("{}/resources", base),
+            runtime_dir: runtime_dir.to_path_buf(),
+            content_manifest_url: None,
+            mods_sync_policy: SyncPolicy::Mirror,
+            shaderpacks_sync_policy: SyncPolicy::Mirror,
+            resourcepacks_sync_policy: SyncPolicy::Merge,
+            use_system_java: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn install_simple_lays_out_game_directory() {
+        let server = mock_content_server().await;
+        let game_dir = tempfile::tempdir().unwrap();
+        let runtime_dir = runtime_with_java();
+
+        let installer = MinecraftInstaller::with_config(
+            game_dir.path().to_path_buf(),
+            GameVersion::Fabric1_20_1,
+            mock_config(&server, runtime_dir.path()),
+        );
+        assert!(!installer.is_installed().await);
+
+        installer.install_simple().await.unwrap();
+
+        let root = game_dir.path();
+        let read = |relative: &str| fs::read(root.join(relative)).unwrap_or_else(|e| panic!("{}: {}", relative, e));
+        assert_eq!(read("versions/1.20.1/1.20.1.jar"), CLIENT_JAR);
+        assert!(root.join("versions/1.20.1/1.20.1.json").is_file());
+        assert_eq!(read("libraries/com/mojang/logging/1.1.1/logging-1.1.1.jar"), LOGGING_JAR);
+        assert!(!root.join("libraries/org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-natives-macos.jar").exists());
+        assert!(root.join("assets/indexes/5.json").is_file());
+        assert_eq!(read(&format!("assets/objects/0a/{}", LANG_HASH)), b"{}");
+        assert!(root.join(format!("assets/objects/ff/{}", ICON_HASH)).is_file());
+        assert_eq!(read("libraries/net/fabricmc/fabric-loader/0.16.10/fabric-loader-0.16.10.jar"), LOADER_JAR);
+        assert_eq!(read("libraries/net/fabricmc/intermediary/1.20.1/intermediary-1.20.1.jar"), INTERMEDIARY_JAR);
+        assert!(root.join("versions/fabric-loader-0.16.10-1.20.1/fabric-loader-0.16.10-1.20.1.json").is_file());
+        assert_eq!(read("mods/sodium.jar"), SODIUM_JAR);
+        assert!(!root.join("mods/README.md").exists());
+        assert!(root.join("mods").join(LOCAL_MODS_FOLDER).is_dir());
+        assert!(root.join("options.txt").is_file());
+        assert!(!root.join(INSTALL_MARKER_FILE).exists());
+        assert!(installer.is_installed().await);
+    }
+
+    #[tokio::test]
+    async fn truncated_version_json_is_not_installed() {
+        let server = mock_content_server().await;
+        let game_dir = tempfile::tempdir().unwrap();
+        let runtime_dir = runtime_with_java();
+
+        let installer = MinecraftInstaller::with_config(
+            game_dir.path().to_path_buf(),
+            GameVersion::Fabric1_20_1,
+            mock_config(&server, runtime_dir.path()),
+        );
+        installer.install_simple().await.unwrap();
+        assert!(installer.is_installed().await);
+
+        let version_json = game_dir.path().join("versions/1.20.1/1.20.1.json");
+        let content = fs::read(&version_json).unwrap();
+        fs::write(&version_json, &content[..content.len() / 2]).unwrap();
+        assert!(!installer.is_installed().await);
+
+        fs::write(&version_json, &content).unwrap();
+        let fabric_json = game_dir.path().join("versions/fabric-loader-0.16.10-1.20.1/fabric-loader-0.16.10-1.20.1.json");
+        let content = fs::read(&fabric_json).unwrap();
+        fs::write(&fabric_json, &content[..content.len() / 2]).unwrap();
+        assert!(!installer.is_installed().await);
+    }
+
+    #[tokio::test]
+    async fn concurrent_installs_into_one_directory_are_serialized() {
+        let server = mock_content_server().await;
+        let game_dir = tempfile::tempdir().unwrap();
+        let runtime_dir = runtime_with_java();
+
+        let waited = Arc::new(AtomicU64::new(0));
+        let installer = || {
+            let waited = waited.clone();
+            MinecraftInstaller::with_config(
+                game_dir.path().to_path_buf(),
+                GameVersion::Fabric1_20_1,
+                mock_config(&server, runtime_dir.path()),
+            )
+            .with_progress(move |step, _| {
+                if matches!(step, InstallStep::WaitingForInstall) {
+                    waited.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+        };
+        let (first, second) = (installer(), installer());
+
+        let (first_result, second_result) = tokio::join!(first.install_simple(), second.install_simple());
+        first_result.unwrap();
+        second_result.unwrap();
+
+        assert!(waited.load(Ordering::SeqCst) >= 1);
+        assert_eq!(fs::read(game_dir.path().join("versions/1.20.1/1.20.1.jar")).unwrap(), CLIENT_JAR);
+        assert_eq!(fs::read(game_dir.path().join("mods/sodium.jar")).unwrap(), SODIUM_JAR);
+        assert!(!game_dir.path().join(INSTALL_MARKER_FILE).exists());
+        assert!(first.is_installed().await);
+    }
+
+    #[tokio::test]
+    async fn staged_content_is_applied_on_next_launch() {
+        let server = mock_content_server().await;
+        let game_dir = tempfile::tempdir().unwrap();
+        let runtime_dir = tempfile::tempdir().unwrap();
+        let root = game_dir.path();
+        fs::create_dir_all(root.join("mods")).unwrap();
+        fs::write(root.join("mods/retired.jar"), b"retired").unwrap();
+
+        let installer = MinecraftInstaller::with_config(
+            root.to_path_buf(),
+            GameVersion::Fabric1_20_1,
+            mock_config(&server, runtime_dir.path()),
+        );
+        let staged = installer.stage_content_updates(ShaderQuality::Off, false, false).await.unwrap();
+        assert_eq!(staged, vec![DownloadKind::Mods]);
+        assert!(!root.join("mods/sodium.jar").exists());
+        assert!(root.join("mods/retired.jar").exists());
+
+        assert_eq!(installer.apply_staged_content().unwrap(), vec![DownloadKind::Mods]);
+        assert_eq!(fs::read(root.join("mods/sodium.jar")).unwrap(), SODIUM_JAR);
+        assert!(!root.join("mods/retired.jar").exists());
+        assert!(root.join("mods").join(REMOVED_MODS_FOLDER).join("retired.jar").exists());
+        assert!(!root.join(STAGING_FOLDER).exists());
+        assert!(installer.apply_staged_content().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn unsatisfiable_range_restarts_download_from_scratch() {
+        let server = MockServer::start().await;
+        serve_ranged(&server, "/v1/objects/client.jar", CLIENT_JAR).await;
+        let game_dir = tempfile::tempdir().unwrap();
+        let runtime_dir = tempfile::tempdir().unwrap();
+        let installer = MinecraftInstaller::with_config(
+            game_dir.path().to_path_buf(),
+            GameVersion::Fabric1_20_1,
+            mock_config(&server, runtime_dir.path()),
+        );
+
+        let target = game_dir.path().join("client.jar");
+        fs::write(part_path(&target), b"stale partial download that is longer than the file").unwrap();
+        installer.download_file(&format!("{}/v1/objects/client.jar", server.uri()), &target).await.unwrap();
+
+        assert_eq!(fs::read(&target).unwrap(), CLIENT_JAR);
+        assert!(!part_path(&target).exists());
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(range_requests(&requests).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn preallocated_part_resumes_from_recorded_offset() {
+        let body: Vec<u8> = (0..3 * 1024 * 1024u32).map(|i| (i % 251) as u8).collect();
+        let server = MockServer::start().await;
+        serve_ranged(&server, "/java.zip", body.clone()).await;
+        let game_dir = tempfile::tempdir().unwrap();
+        let runtime_dir = tempfile::tempdir().unwrap();
+        let installer = MinecraftInstaller::with_config(
+            game_dir.path().to_path_buf(),
+            GameVersion::Fabric1_20_1,
+            mock_config(&server, runtime_dir.path()),
+        );
+
+        let target = game_dir.path().join("java.zip");
+        let written = 1_600_000;
+        let mut part = body[..written].to_vec();
+        part.resize(body.len(), 0);
+        fs::write(part_path(&target), part).unwrap();
+        fs::write(part_len_path(&target), "1048576").unwrap();
+
+        installer.download_file(&format!("{}/java.zip", server.uri()), &target).await.unwrap();
+
+        assert!(fs::read(&target).unwrap() == body);
+        assert!(!part_path(&target).exists());
+        assert!(!part_len_path(&target).exists());
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(range_requests(&requests), ["bytes=1048576-"]);
+    }
+
+    #[test]
+    fn maven_coordinates_map_to_repository_paths() {
+        let cases = [
+            ("net.fabricmc:fabric-loader:0.14.21", "net/fabricmc/fabric-loader/0.14.21/fabric-loader-0.14.21.jar"),
+            ("net.fabricmc:intermediary:1.20.1", "net/fabricmc/intermediary/1.20.1/intermediary-1.20.1.jar"),
+            ("net.fabricmc:sponge-mixin:0.12.5+mixin.0.8.5", "net/fabricmc/sponge-mixin/0.12.5+mixin.0.8.5/sponge-mixin-0.12.5+mixin.0.8.5.jar"),
+            ("org.ow2.asm:asm:9.5", "org/ow2/asm/asm/9.5/asm-9.5.jar"),
+            ("org.lwjgl:lwjgl:3.3.1:natives-windows", "org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-natives-windows.jar"),
+            ("net.fabricmc:fabric-loader:0.16.5", "net/fabricmc/fabric-loader/0.16.5/fabric-loader-0.16.5.jar"),
+            ("net.fabricmc:intermediary:1.21.1", "net/fabricmc/intermediary/1.21.1/intermediary-1.21.1.jar"),
+            ("net.fabricmc:sponge-mixin:0.15.3+mixin.0.8.7", "net/fabricmc/sponge-mixin/0.15.3+mixin.0.8.7/sponge-mixin-0.15.3+mixin.0.8.7.jar"),
+            ("org.ow2.asm:asm-tree:9.7.1", "org/ow2/asm/asm-tree/9.7.1/asm-tree-9.7.1.jar"),
+            ("org.lwjgl:lwjgl-glfw:3.3.3:natives-windows-x86", "org/lwjgl/lwjgl-glfw/3.3.3/lwjgl-glfw-3.3.3-natives-windows-x86.jar"),
+            ("org.lwjgl:lwjgl:3.3.3:natives-windows@jar", "org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3-natives-windows.jar"),
+            ("net.fabricmc:yarn:1.20.1+build.10@jar", "net/fabricmc/yarn/1.20.1+build.10/yarn-1.20.1+build.10.jar"),
+            ("de.oceanlabs.mcp:mcp_config:1.20.1-20230612.114412@zip", "de/oceanlabs/mcp/mcp_config/1.20.1-20230612.114412/mcp_config-1.20.1-20230612.114412.zip"),
+        ];
+
+        for (name, expected) in cases {
+            assert_eq!(maven_name_to_path(name), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn malformed_maven_coordinates_are_returned_unchanged() {
+        assert_eq!(maven_name_to_path("just-a-file.jar"), "just-a-file.jar");
+        assert_eq!(maven_name_to_path("group:artifact"), "group:artifact");
+    }
+}
@@ -1,10 +1,54 @@
 use anyhow::{anyhow, Result};
-use sha2::{Sha256, Digest};
+use md5::{Md5, Digest};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::fs;
 use std::process::Stdio;
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
 
-use super::version::{GameVersion, ShaderQuality};
+use super::installer::{legacy_assets_dir, synced_shaderpacks, version_asset_index_id, LOCAL_MODS_FOLDER};
+use super::types::AssetIndex;
+use super::version::{GameVersion, GarbageCollector, QuickPlay, ShaderQuality};
+use super::nbt::{add_server_entry, read_servers_dat};
+
+const SYSTEM_JAVA_VENDORS: &[&str] = &[
+    "Java",
+    "Eclipse Adoptium",
+    "Microsoft",
+    "Zulu",
+    "BellSoft",
+    "Amazon Corretto",
+];
+
+#[derive(Debug, Clone)]
+pub struct LaunchOptions {
+    pub ram_mb: u32,
+    pub optimized_flags: bool,
+    pub gc: GarbageCollector,
+    pub quick_play: QuickPlay,
+    pub env_vars: Vec<(String, String)>,
+    pub loader_version: String,
+    pub use_system_java: bool,
+}
+
+const AIKAR_FLAGS: &[&str] = &[
+    "-XX:+UnlockExperimentalVMOptions",
+    "-XX:+DisableExplicitGC",
+    "-XX:+AlwaysPreTouch",
+    "-XX:G1NewSizePercent=30",
+    "-XX:G1MaxNewSizePercent=40",
+    "-XX:G1HeapRegionSize=8M",
+    "-XX:G1ReservePercent=20",
+    "-XX:G1HeapWastePercent=5",
+    "-XX:G1MixedGCCountTarget=4",
+    "-XX:InitiatingHeapOccupancyPercent=15",
+    "-XX:G1MixedGCLiveThresholdPercent=90",
+    "-XX:G1RSetUpdatingPauseTimePercent=5",
+    "-XX:SurvivorRatio=32",
+    "-XX:+PerfDisableSharedMem",
+    "-XX:MaxTenuringThreshold=1",
+];
 
 pub fn get_game_directory() -> PathBuf {
     directories::ProjectDirs::from("com", "bystep", "minecraft")
@@ -22,32 +66,126 @@ pub fn get_versioned_game_directory(version: GameVersion) -> PathBuf {
 }
 
 pub fn generate_offline_uuid(nickname: &str) -> String {
-    let mut hasher = Sha256::new();
+    let mut hasher = Md5::new();
     hasher.update(format!("OfflinePlayer:{}", nickname));
-    let result = hasher.finalize();
-    format!(
-        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
-        u32::from_be_bytes([result[0], result[1], result[2], result[3]]),
-        u16::from_be_bytes([result[4], result[5]]),
-        u16::from_be_bytes([result[6], result[7]]),
-        u16::from_be_bytes([result[8], result[9]]),
-        u64::from_be_bytes([0, 0, result[10], result[11], result[12], result[13], result[14], result[15]])
-    )
+    let hash: [u8; 16] = hasher.finalize().into();
+    uuid::Builder::from_md5_bytes(hash).into_uuid().hyphenated().to_string()
 }
 
-pub fn find_java(game_dir: &Path, version: GameVersion) -> Result<PathBuf> {
+pub fn find_java(game_dir: &Path, version: GameVersion, use_system_java: bool) -> Result<PathBuf> {
     let java_version = version.java_version();
     let base_dir = get_game_directory();
     let java_dir = base_dir.join("runtime").join(format!("java-{}", java_version));
     let java_exe = java_dir.join("bin").join("java.exe");
     
+    if use_system_java {
+        if let Some(system_java) = find_system_java(java_version) {
+            return Ok(system_java);
+        }
+    }
+    
     if java_exe.exists() {
-        return Ok(java_exe);
+        return match java_major_version(&java_exe) {
+            Some(major) if major != java_version => Err(anyhow!(
+                "Установлена Java {}, а для {} нужна Java {}",
+                major, version.display_name(), java_version
+            )),
+            _ => Ok(java_exe),
+        };
     }
     
     Err(anyhow!("Java {} not found", java_version))
 }
 
+pub fn find_system_java(java_version: u8) -> Option<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(home) = std::env::var_os("JAVA_HOME") {
+        candidates.push(PathBuf::from(home).join("bin").join("java.exe"));
+    }
+    if let Some(path) = std::env::var_os("PATH") {
+        candidates.extend(std::env::split_paths(&path).map(|dir| dir.join("java.exe")));
+    }
+    for root in ["ProgramFiles", "ProgramW6432"] {
+        let Some(root) = std::env::var_os(root) else { continue };
+        for vendor in SYSTEM_JAVA_VENDORS {
+            if let Ok(entries) = fs::read_dir(Path::new(&root).join(vendor)) {
+                candidates.extend(entries.flatten().map(|entry| entry.path().join("bin").join("java.exe")));
+            }
+        }
+    }
+    
+    let mut seen = HashSet::new();
+    candidates.into_iter()
+        .filter(|java| java.is_file() && seen.insert(java.clone()))
+        .find(|java| java_major_version(java) == Some(java_version))
+}
+
+pub fn java_is_64bit(java_exe: &Path) -> Option<bool> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let output = std::process::Command::new(java_exe)
+        .args(["-XshowSettings:properties", "-version"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("sun.arch.data.model = ").map(|model| model.trim() == "64"))
+}
+
+pub fn is_64bit_os() -> bool {
+    cfg!(target_pointer_width = "64")
+        || std::env::var("PROCESSOR_ARCHITEW6432").is_ok()
+        || std::env::var("PROCESSOR_ARCHITECTURE").is_ok_and(|arch| arch.ends_with("64"))
+}
+
+type JavaVersionCache = HashMap<PathBuf, (Option<SystemTime>, Option<u8>)>;
+
+pub fn java_major_version(java_exe: &Path) -> Option<u8> {
+    static CACHE: OnceLock<Mutex<JavaVersionCache>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    let modified = fs::metadata(java_exe).and_then(|m| m.modified()).ok();
+    
+    if let Some((cached_modified, major)) = cache.lock().ok()?.get(java_exe) {
+        if *cached_modified == modified {
+            return *major;
+        }
+    }
+    
+    let major = java_version_line(java_exe).and_then(|line| parse_java_major(&line));
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert(java_exe.to_path_buf(), (modified, major));
+    }
+    major
+}
+
+fn parse_java_major(version_line: &str) -> Option<u8> {
+    let version = version_line.split('"').nth(1)?;
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    let first: u8 = parts.next()?.parse().ok()?;
+    if first == 1 {
+        parts.next()?.parse().ok()
+    } else {
+        Some(first)
+    }
+}
+
+pub fn java_version_line(java_exe: &Path) -> Option<String> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let output = std::process::Command::new(java_exe)
+        .arg("-version")
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
+}
+
 fn collect_jars(dir: &Path, jars: &mut Vec<String>) -> Result<()> {
     if dir.is_dir() {
         for entry in fs::read_dir(dir)? {
@@ -55,7 +193,7 @@ fn collect_jars(dir: &Path, jars: &mut Vec<String>) -> Result<()> {
             let path = entry.path();
             if path.is_dir() {
                 collect_jars(&path, jars)?;
-            } else if path.extension().map_or(false, |ext| ext == "jar") {
+            } else if path.extension().is_some_and(|ext| ext == "jar") {
                 jars.push(path.display().to_string());
             }
         }
@@ -66,29 +204,61 @@ fn collect_jars(dir: &Path, jars: &mut Vec<String>) -> Result<()> {
 pub fn build_launch_command(
     game_dir: &Path,
     nickname: &str,
-    ram_gb: u32,
     server_address: Option<&str>,
     version: GameVersion,
+    options: &LaunchOptions,
 ) -> Result<std::process::Command> {
     use std::os::windows::process::CommandExt;
     const CREATE_NO_WINDOW: u32 = 0x08000000;
     
     let mc_version = version.minecraft_version();
-    let java_path = find_java(game_dir, version)?;
+    let java_path = find_java(game_dir, version, options.use_system_java)?;
     
     let mut cmd = std::process::Command::new(java_path);
     
     cmd.creation_flags(CREATE_NO_WINDOW);
     
-    cmd.arg(format!("-Xmx{}G", ram_gb));
-    cmd.arg(format!("-Xms{}G", ram_gb.min(2)));
-    cmd.arg("-XX:+UseG1GC");
-    cmd.arg("-XX:+ParallelRefProcEnabled");
-    cmd.arg("-XX:MaxGCPauseMillis=200");
+    for (name, value) in &options.env_vars {
+        let name = name.trim();
+        if is_valid_env_var_name(name) {
+            cmd.env(name, value);
+        }
+    }
+    
+    cmd.arg(format!("-Xmx{}M", options.ram_mb));
+    if options.optimized_flags {
+        cmd.arg(format!("-Xms{}M", options.ram_mb));
+    } else {
+        cmd.arg(format!("-Xms{}M", options.ram_mb.min(2048)));
+    }
+    match options.gc.resolve(version.java_version()) {
+        GarbageCollector::JvmDefault => {}
+        GarbageCollector::G1 => {
+            cmd.arg("-XX:+UseG1GC");
+            cmd.arg("-XX:+ParallelRefProcEnabled");
+            cmd.arg("-XX:MaxGCPauseMillis=200");
+            if options.optimized_flags {
+                cmd.args(AIKAR_FLAGS);
+            }
+        }
+        GarbageCollector::Zgc => {
+            cmd.arg("-XX:+UseZGC");
+            if version.java_version() >= 21 {
+                cmd.arg("-XX:+ZGenerational");
+            }
+        }
+        GarbageCollector::Shenandoah => {
+            cmd.arg("-XX:+UseShenandoahGC");
+        }
+    }
     
     let natives_dir = game_dir.join("natives");
     fs::create_dir_all(&natives_dir)?;
     cmd.arg(format!("-Djava.library.path={}", natives_dir.display()));
+    let local_mods_dir = game_dir.join("mods").join(LOCAL_MODS_FOLDER);
+    if local_mods_dir.is_dir() {
+        cmd.arg(format!("-Dfabric.addMods={}", local_mods_dir.display()));
+    }
     cmd.arg("-Dminecraft.launcher.brand=ByStep");
     cmd.arg("-Dminecraft.launcher.version=1.1.1");
     
@@ -107,105 +277,71 @@ pub fn build_launch_command(
     cmd.arg("-cp");
     cmd.arg(classpath.join(";"));
     
-    let version_json_path = game_dir
-        .join("versions")
-        .join(mc_version)
-        .join(format!("{}.json", mc_version));
-    
-    let asset_index_id = if version_json_path.exists() {
-        let content = fs::read_to_string(&version_json_path).unwrap_or_default();
-        if let Ok(info) = serde_json::from_str::<serde_json::Value>(&content) {
-            info.get("assetIndex")
-                .and_then(|ai| ai.get("id"))
-                .and_then(|id| id.as_str())
-                .unwrap_or(mc_version)
-                .to_string()
-        } else {
-            mc_version.to_string()
-        }
-    } else {
-        mc_version.to_string()
-    };
+    let asset_index_id = version_asset_index_id(game_dir, mc_version)
+        .ok_or_else(|| anyhow!("Файл версии {} повреждён или отсутствует", mc_version))?;
     
-    let fabric_version_id = format!("fabric-loader-{}-{}", version.fabric_loader_version(), mc_version);
+    let fabric_version_id = format!("fabric-loader-{}-{}", options.loader_version, mc_version);
     cmd.arg("net.fabricmc.loader.impl.launch.knot.KnotClient");
     
     cmd.arg("--username").arg(nickname);
     cmd.arg("--version").arg(&fabric_version_id);
     cmd.arg("--gameDir").arg(game_dir);
-    cmd.arg("--assetsDir").arg(game_dir.join("assets"));
+    let assets_dir = fs::read_to_string(game_dir.join("assets").join("indexes").join(format!("{}.json", asset_index_id)))
+        .ok()
+        .and_then(|content| serde_json::from_str::<AssetIndex>(&content).ok())
+        .and_then(|index| legacy_assets_dir(game_dir, &asset_index_id, &index))
+        .unwrap_or_else(|| game_dir.join("assets"));
+    cmd.arg("--assetsDir").arg(assets_dir);
     cmd.arg("--assetIndex").arg(&asset_index_id);
     cmd.arg("--uuid").arg(generate_offline_uuid(nickname));
     cmd.arg("--accessToken").arg("0");
     cmd.arg("--userType").arg("legacy");
     
-    if let Some(server) = server_address {
-        if !server.is_empty() {
-            let _ = create_servers_dat(game_dir, server);
-            let parts: Vec<&str> = server.split(':').collect();
-            cmd.arg("--server").arg(parts[0]);
-            if parts.len() > 1 {
-                cmd.arg("--port").arg(parts[1]);
-            }
+    cmd.args(server_args(server_address, &options.quick_play));
+    if let QuickPlay::World(world) = &options.quick_play {
+        if game_dir.join("saves").join(world).join("level.dat").exists() {
+            cmd.arg("--quickPlaySingleplayer").arg(world);
         }
     }
     
     Ok(cmd)
 }
 
-pub fn create_servers_dat(game_dir: &Path, server_address: &str) -> Result<()> {
-    let servers_path = game_dir.join("servers.dat");
+fn server_args(server_address: Option<&str>, quick_play: &QuickPlay) -> Vec<String> {
+    let Some(server) = server_address.filter(|server| !server.is_empty()) else {
+        return Vec::new();
+    };
+    if matches!(quick_play, QuickPlay::World(_)) {
+        return Vec::new();
+    }
     
+    let parts: Vec<&str> = server.split(':').collect();
+    let mut args = vec!["--server".to_string(), parts[0].to_string()];
+    if parts.len() > 1 {
+        args.extend(["--port".to_string(), parts[1].to_string()]);
+    }
+    if *quick_play == QuickPlay::Server {
+        args.extend(["--quickPlayMultiplayer".to_string(), server.to_string()]);
+    }
+    args
+}
+
+pub fn is_valid_env_var_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains('=') && !name.contains('\0')
+}
+
+pub fn create_servers_dat(game_dir: &Path, server_address: &str) -> Result<()> {
     let parts: Vec<&str> = server_address.split(':').collect();
     let ip = parts[0];
     let port = if parts.len() > 1 { parts[1] } else { "25565" };
     let full_address = format!("{}:{}", ip, port);
     
-    let mut data = Vec::new();
-    
-    data.push(0x0A);
-    data.push(0x00);
-    data.push(0x00);
-    
-    data.push(0x09);
-    let servers_name = b"servers";
-    data.push(0x00);
-    data.push(servers_name.len() as u8);
-    data.extend_from_slice(servers_name);
-    
-    data.push(0x0A);
-    data.extend_from_slice(&1i32.to_be_bytes());
-    
-    data.push(0x08);
-    let name_key = b"name";
-    data.push(0x00);
-    data.push(name_key.len() as u8);
-    data.extend_from_slice(name_key);
-    let server_name = "ByStep Server";
-    let name_bytes = server_name.as_bytes();
-    data.extend_from_slice(&(name_bytes.len() as u16).to_be_bytes());
-    data.extend_from_slice(name_bytes);
-    
-    data.push(0x08);
-    let ip_key = b"ip";
-    data.push(0x00);
-    data.push(ip_key.len() as u8);
-    data.extend_from_slice(ip_key);
-    let ip_bytes = full_address.as_bytes();
-    data.extend_from_slice(&(ip_bytes.len() as u16).to_be_bytes());
-    data.extend_from_slice(ip_bytes);
-    
-    data.push(0x01);
-    let hidden_key = b"hidden";
-    data.push(0x00);
-    data.push(hidden_key.len() as u8);
-    data.extend_from_slice(hidden_key);
-    data.push(0x00);
-    
-    data.push(0x00);
-    data.push(0x00);
-    
-    fs::write(&servers_path, &data)?;
+    add_server_entry(game_dir, "ByStep Server", &full_address)?;
+    
+    let servers = read_servers_dat(game_dir)?;
+    if !servers.iter().any(|server| server.ip == full_address) {
+        return Err(anyhow!("servers.dat записан, но сервер {} в нём не найден", full_address));
+    }
     
     Ok(())
 }
@@ -308,7 +444,38 @@ onboardAccessibility:false
     Ok(())
 }
 
-pub fn configure_shaders(game_dir: &Path, quality: ShaderQuality, _version: GameVersion) -> Result<()> {
+pub fn detect_worlds(game_dir: &Path) -> Vec<String> {
+    let mut worlds: Vec<String> = fs::read_dir(game_dir.join("saves"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().join("level.dat").exists())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    worlds.sort_by_key(|name| name.to_lowercase());
+    worlds
+}
+
+pub fn detect_shaderpacks(game_dir: &Path) -> Vec<String> {
+    let mut packs: Vec<String> = fs::read_dir(game_dir.join("shaderpacks"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| {
+                    let path = entry.path();
+                    path.is_dir() || path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+                })
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    packs.sort_by_key(|name| name.to_lowercase());
+    packs
+}
+
+pub fn configure_shaders(game_dir: &Path, quality: ShaderQuality, preferred_pack: Option<&str>, _version: GameVersion) -> Result<bool> {
     let _ = create_default_options(game_dir);
     
     let iris_config_path = game_dir.join("config").join("iris.properties");
@@ -317,10 +484,18 @@ pub fn configure_shaders(game_dir: &Path, quality: ShaderQuality, _version: Game
         fs::create_dir_all(parent)?;
     }
     
-    let (shaderpack, enable_shaders) = match quality {
-        ShaderQuality::Off => ("", false),
-        ShaderQuality::On => ("ComplementaryUnbound_r5.6.1.zip", true),
+    let available = detect_shaderpacks(game_dir);
+    let synced = synced_shaderpacks(game_dir, quality);
+    let selected = match preferred_pack {
+        Some(wanted) => available.iter().find(|name| name.as_str() == wanted),
+        None => available.iter().find(|name| synced.contains(name)).or_else(|| available.first()),
+    };
+    
+    let (shaderpack, enable_shaders) = match (quality, selected) {
+        (ShaderQuality::Off, _) | (_, None) => ("", false),
+        (_, Some(name)) => (name.as_str(), true),
     };
+    let pack_missing = quality != ShaderQuality::Off && selected.is_none();
     
     let iris_config = format!(
         "shaderPack={}\nenableShaders={}\n",
@@ -330,5 +505,38 @@ pub fn configure_shaders(game_dir: &Path, quality: ShaderQuality, _version: Game
     
     fs::write(&iris_config_path, iris_config)?;
     
-    Ok(())
+    Ok(pack_missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_uuid_matches_vanilla() {
+        assert_eq!(generate_offline_uuid("Notch"), "b50ad385-829d-3141-a216-7e7d7539ba7f");
+        assert_eq!(generate_offline_uuid("jeb_"), "a762f560-4fce-3236-812a-b80efff0b62b");
+    }
+
+    #[test]
+    fn offline_uuid_is_name_based_v3() {
+        let uuid = uuid::Uuid::parse_str(&generate_offline_uuid("Steve")).unwrap();
+
+        assert_eq!(uuid.get_version_num(), 3);
+        assert_eq!(uuid.get_variant(), uuid::Variant::RFC4122);
+        assert_eq!(uuid.as_bytes()[6] >> 4, 0x3);
+        assert_eq!(uuid.as_bytes()[8] >> 6, 0b10);
+    }
+
+    #[test]
+    fn server_args_follow_quick_play() {
+        assert_eq!(server_args(Some("play.example.com:25566"), &QuickPlay::Menu), ["--server", "play.example.com", "--port", "25566"]);
+        assert_eq!(
+            server_args(Some("play.example.com"), &QuickPlay::Server),
+            ["--server", "play.example.com", "--quickPlayMultiplayer", "play.example.com"]
+        );
+        assert!(server_args(Some("play.example.com"), &QuickPlay::World("New World".into())).is_empty());
+        assert!(server_args(Some(""), &QuickPlay::Menu).is_empty());
+        assert!(server_args(None, &QuickPlay::Server).is_empty());
+    }
 }
@@ -0,0 +1,142 @@
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::installer::file_sha256;
+use super::launcher::get_game_directory;
+use super::version::GameVersion;
+
+const LEGACY_DIR_NAME: &str = ".bystep-minecraft";
+
+pub fn find_legacy_data_dir() -> Option<PathBuf> {
+    let current = get_game_directory();
+    let mut candidates = Vec::new();
+    if let Ok(dir) = std::env::current_dir() {
+        candidates.push(dir.join(LEGACY_DIR_NAME));
+    }
+    if let Some(dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(Path::to_path_buf)) {
+        candidates.push(dir.join(LEGACY_DIR_NAME));
+    }
+    
+    candidates.into_iter().find(|dir| *dir != current && looks_like_data_dir(dir))
+}
+
+pub fn data_dir_size(dir: &Path) -> u64 {
+    list_files(dir).map(|files| files.iter().map(|(_, size)| size).sum()).unwrap_or(0)
+}
+
+pub fn migrate_data_dir(from: &Path, to: &Path, mut on_progress: impl FnMut(u64, u64)) -> Result<()> {
+    if !to.exists() {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if fs::rename(from, to).is_ok() {
+            return Ok(());
+        }
+    }
+    
+    let files = list_files(from)
+        .map_err(|e| anyhow!("Не удалось прочитать старую папку, она сохранена: {:#}", e))?;
+    let total = files.iter().map(|(_, size)| size).sum::<u64>() * 2;
+    let mut done = 0;
+    
+    for (relative, size) in &files {
+        let target = to.join(relative);
+        if target.exists() {
+            if !same_file_contents(&from.join(relative), &target) {
+                return Err(anyhow!("Файл {} уже есть в новой папке и отличается, старая папка сохранена", relative.display()));
+            }
+        } else {
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(from.join(relative), &target)?;
+        }
+        done += size;
+        on_progress(done, total);
+    }
+    
+    for (relative, size) in &files {
+        if !same_file_contents(&from.join(relative), &to.join(relative)) {
+            return Err(anyhow!("Файл {} скопирован с ошибкой, старая папка сохранена", relative.display()));
+        }
+        done += size;
+        on_progress(done, total);
+    }
+    
+    fs::remove_dir_all(from)?;
+    Ok(())
+}
+
+fn same_file_contents(source: &Path, target: &Path) -> bool {
+    let sizes = (fs::metadata(source).map(|m| m.len()).ok(), fs::metadata(target).map(|m| m.len()).ok());
+    matches!(sizes, (Some(source_len), Some(target_len)) if source_len == target_len)
+        && file_sha256(source).is_some_and(|hash| file_sha256(target).as_ref() == Some(&hash))
+}
+
+fn looks_like_data_dir(dir: &Path) -> bool {
+    dir.join("runtime").is_dir()
+        || GameVersion::all().iter().any(|version| dir.join(version.mods_folder()).join("versions").is_dir())
+}
+
+fn list_files(root: &Path) -> Result<Vec<(PathBuf, u64)>> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(&dir).with_context(|| format!("{}", dir.display()))?;
+        for entry in entries {
+            let entry = entry.with_context(|| format!("{}", dir.display()))?;
+            let path = entry.path();
+            let file_type = entry.file_type().with_context(|| format!("{}", path.display()))?;
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() {
+                let size = entry.metadata().with_context(|| format!("{}", path.display()))?.len();
+                files.push((path.strip_prefix(root)?.to_path_buf(), size));
+            } else {
+                return Err(anyhow!("{}: ссылки не переносятся", path.display()));
+            }
+        }
+    }
+    
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &[u8]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn merges_into_existing_dir_and_removes_source() {
+        let root = tempfile::tempdir().unwrap();
+        let (from, to) = (root.path().join("old"), root.path().join("new"));
+        write(&from.join("runtime/java-17/release"), b"17");
+        write(&from.join("1.20.1-fabric/options.txt"), b"lang:ru_ru");
+        write(&to.join("runtime/java-17/release"), b"17");
+
+        let mut progress = Vec::new();
+        migrate_data_dir(&from, &to, |done, total| progress.push((done, total))).unwrap();
+
+        assert!(!from.exists());
+        assert_eq!(fs::read(to.join("1.20.1-fabric/options.txt")).unwrap(), b"lang:ru_ru");
+        assert_eq!(progress.last(), Some(&(24, 24)));
+    }
+
+    #[test]
+    fn conflicting_file_keeps_source() {
+        let root = tempfile::tempdir().unwrap();
+        let (from, to) = (root.path().join("old"), root.path().join("new"));
+        write(&from.join("1.20.1-fabric/servers.dat"), b"old list");
+        write(&to.join("1.20.1-fabric/servers.dat"), b"new list");
+
+        assert!(migrate_data_dir(&from, &to, |_, _| {}).is_err());
+        assert_eq!(fs::read(from.join("1.20.1-fabric/servers.dat")).unwrap(), b"old list");
+        assert_eq!(fs::read(to.join("1.20.1-fabric/servers.dat")).unwrap(), b"new list");
+    }
+}
@@ -2,12 +2,35 @@ mod version;
 mod types;
 mod installer;
 mod launcher;
+mod cleanup;
+mod mods;
+mod http;
+mod options;
+mod progress;
+mod migration;
+mod nbt;
 
-pub use version::{GameVersion, ShaderQuality};
-pub use installer::MinecraftInstaller;
+pub use version::{GameVersion, GarbageCollector, QuickPlay, ShaderQuality, SyncPolicy};
+pub use progress::{DownloadKind, InstallStep};
+pub use installer::{ContentVersions, InstallerConfig, MinecraftInstaller, VerifyReport, LOCAL_MODS_FOLDER, content_versions, is_folder_access_error, is_rate_limited, REMOVED_MODS_FOLDER, validate_content_source_url, validate_fabric_loader_version};
+pub use cleanup::{list_log_files, remove_old_logs, sweep_stale_files, LogFile, KEEP_LOG_FILES};
+pub use migration::{data_dir_size, find_legacy_data_dir, migrate_data_dir};
+pub use http::{api_client, download_client, is_network_error, set_bandwidth_limit, set_proxy_url, set_timeouts, validate_proxy_url, check_connectivity, DEFAULT_CONNECT_TIMEOUT_SECS, DEFAULT_DOWNLOAD_TIMEOUT_SECS};
+pub use mods::{DuplicateMod, ModInfo, find_duplicate_mods, is_newer_version, scan_installed_mods};
+pub use nbt::read_servers_dat;
+pub use options::{detect_resourcepacks, enabled_resourcepacks, write_enabled_resourcepacks};
 pub use launcher::{
     get_game_directory,
     get_versioned_game_directory,
     build_launch_command,
+    create_servers_dat,
+    LaunchOptions,
     configure_shaders,
+    detect_shaderpacks,
+    detect_worlds,
+    find_java,
+    is_valid_env_var_name,
+    java_version_line,
+    java_is_64bit,
+    is_64bit_os,
 };
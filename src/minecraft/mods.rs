@@ -0,0 +1,255 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+#[derive(Debug, Clone)]
+pub struct ModInfo {
+    pub name: String,
+    pub version: Option<String>,
+    pub incompatibility: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct FabricModJson {
+    id: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    depends: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateMod {
+    pub id: String,
+    pub name: String,
+    pub keep: PathBuf,
+    pub older: Vec<PathBuf>,
+}
+
+type CacheKey = (PathBuf, SystemTime, u64);
+
+static METADATA_CACHE: Mutex<Option<HashMap<CacheKey, Option<FabricModJson>>>> = Mutex::new(None);
+
+pub fn scan_installed_mods(mods_dir: &Path, mc_version: &str, loader_version: &str) -> Vec<ModInfo> {
+    let entries = match fs::read_dir(mods_dir) {
+        Ok(e) => e,
+        Err(_) => return Vec::new(),
+    };
+    
+    let mut mods: Vec<ModInfo> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(".jar"))
+        .map(|entry| {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            match cached_metadata(&path) {
+                Some(meta) => ModInfo {
+                    incompatibility: find_incompatibility(&meta.depends, mc_version, loader_version),
+                    name: meta.name.unwrap_or(meta.id),
+                    version: meta.version,
+                },
+                None => ModInfo {
+                    name: file_name.trim_end_matches(".jar").to_string(),
+                    version: None,
+                    incompatibility: None,
+                },
+            }
+        })
+        .collect();
+    
+    mods.sort_by_key(|m| m.name.to_lowercase());
+    mods
+}
+
+pub fn find_duplicate_mods(dirs: &[PathBuf]) -> Vec<DuplicateMod> {
+    let mut by_id: HashMap<String, Vec<(PathBuf, FabricModJson, SystemTime)>> = HashMap::new();
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_file() || !entry.file_name().to_string_lossy().ends_with(".jar") {
+                continue;
+            }
+            if let Some(meta) = cached_metadata(&path) {
+                let modified = entry.metadata().and_then(|m| m.modified()).unwrap_or(SystemTime::UNIX_EPOCH);
+                by_id.entry(meta.id.clone()).or_default().push((path, meta, modified));
+            }
+        }
+    }
+    
+    let mut duplicates: Vec<DuplicateMod> = by_id.into_iter()
+        .filter(|(_, jars)| jars.len() > 1)
+        .map(|(id, mut jars)| {
+            jars.sort_by(|(_, a, a_modified), (_, b, b_modified)| {
+                let by_version = match (a.version.as_deref().and_then(parse_version), b.version.as_deref().and_then(parse_version)) {
+                    (Some(a), Some(b)) => compare_versions(&b, &a),
+                    _ => std::cmp::Ordering::Equal,
+                };
+                by_version.then(b_modified.cmp(a_modified))
+            });
+            let mut paths = jars.iter().map(|(path, _, _)| path.clone());
+            DuplicateMod {
+                name: jars[0].1.name.clone().unwrap_or_else(|| id.clone()),
+                id,
+                keep: paths.next().unwrap_or_default(),
+                older: paths.collect(),
+            }
+        })
+        .collect();
+    duplicates.sort_by_key(|d| d.name.to_lowercase());
+    duplicates
+}
+
+fn cached_metadata(path: &Path) -> Option<FabricModJson> {
+    let metadata = path.metadata().ok()?;
+    let key = (path.to_path_buf(), metadata.modified().ok()?, metadata.len());
+    
+    if let Ok(guard) = METADATA_CACHE.lock() {
+        if let Some(cached) = guard.as_ref().and_then(|cache| cache.get(&key)) {
+            return cached.clone();
+        }
+    }
+    
+    let parsed = read_fabric_mod_json(path);
+    if let Ok(mut guard) = METADATA_CACHE.lock() {
+        guard.get_or_insert_with(HashMap::new).insert(key, parsed.clone());
+    }
+    parsed
+}
+
+fn read_fabric_mod_json(path: &Path) -> Option<FabricModJson> {
+    let file = fs::File::open(path).ok()?;
+    let mut archive = zip::ZipArchive::new(file).ok()?;
+    let mut entry = archive.by_name("fabric.mod.json").ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn find_incompatibility(
+    depends: &HashMap<String, serde_json::Value>,
+    mc_version: &str,
+    loader_version: &str,
+) -> Option<String> {
+    let checks = [
+        ("minecraft", "Minecraft", mc_version),
+        ("fabricloader", "Fabric Loader", loader_version),
+    ];
+    for (key, label, actual) in checks {
+        if let Some(requirement) = depends.get(key) {
+            if !requirement_matches(requirement, actual) {
+                return Some(format!("{} {}", label, describe_requirement(requirement)));
+            }
+        }
+    }
+    None
+}
+
+fn describe_requirement(requirement: &serde_json::Value) -> String {
+    match requirement {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(items) => items.iter()
+            .filter_map(|v| v.as_str())
+            .collect::<Vec<_>>()
+            .join(" | "),
+        _ => String::new(),
+    }
+}
+
+fn requirement_matches(requirement: &serde_json::Value, actual: &str) -> bool {
+    match requirement {
+        serde_json::Value::String(s) => predicate_matches(s, actual),
+        serde_json::Value::Array(items) => {
+            let predicates: Vec<&str> = items.iter().filter_map(|v| v.as_str()).collect();
+            predicates.is_empty() || predicates.iter().any(|p| predicate_matches(p, actual))
+        }
+        _ => true,
+    }
+}
+
+fn predicate_matches(predicate: &str, actual: &str) -> bool {
+    let actual = match parse_version(actual) {
+        Some(v) => v,
+        None => return true,
+    };
+    predicate.split_whitespace().all(|term| term_matches(term, &actual))
+}
+
+fn term_matches(term: &str, actual: &[u64]) -> bool {
+    if term == "*" {
+        return true;
+    }
+    
+    let (op, version) = [">=", "<=", ">", "<", "=", "~", "^"]
+        .iter()
+        .find_map(|op| term.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("", term));
+    
+    if version.split('.').any(|part| part == "x" || part == "X" || part == "*") {
+        let prefix: Vec<&str> = version.split('.')
+            .take_while(|part| !matches!(*part, "x" | "X" | "*"))
+            .collect();
+        return match prefix.iter().map(|p| p.parse::<u64>()).collect::<Result<Vec<_>, _>>() {
+            Ok(prefix) => actual.starts_with(&prefix),
+            Err(_) => true,
+        };
+    }
+    
+    let wanted = match parse_version(version) {
+        Some(v) => v,
+        None => return true,
+    };
+    let ordering = compare_versions(actual, &wanted);
+    
+    match op {
+        ">=" => ordering.is_ge(),
+        "<=" => ordering.is_le(),
+        ">" => ordering.is_gt(),
+        "<" => ordering.is_lt(),
+        "~" => {
+            let mut upper: Vec<u64> = wanted.iter().take(2).copied().collect();
+            if let Some(last) = upper.last_mut() {
+                *last += 1;
+            }
+            ordering.is_ge() && compare_versions(actual, &upper).is_lt()
+        }
+        "^" => {
+            let upper = vec![wanted.first().copied().unwrap_or(0) + 1];
+            ordering.is_ge() && compare_versions(actual, &upper).is_lt()
+        }
+        _ => ordering.is_eq(),
+    }
+}
+
+pub fn is_newer_version(candidate: &str, baseline: &str) -> bool {
+    match (parse_version(candidate), parse_version(baseline)) {
+        (Some(candidate), Some(baseline)) => compare_versions(&candidate, &baseline).is_gt(),
+        _ => false,
+    }
+}
+
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let core = version.split(['-', '+']).next()?;
+    core.split('.').map(|part| part.parse().ok()).collect()
+}
+
+fn compare_versions(a: &[u64], b: &[u64]) -> std::cmp::Ordering {
+    let len = a.len().max(b.len());
+    for i in 0..len {
+        let left = a.get(i).copied().unwrap_or(0);
+        let right = b.get(i).copied().unwrap_or(0);
+        if left != right {
+            return left.cmp(&right);
+        }
+    }
+    std::cmp::Ordering::Equal
+}
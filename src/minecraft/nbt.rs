@@ -0,0 +1,203 @@
+use anyhow::{bail, Context, Result};
+use fastnbt::Value;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[derive(Debug, Clone)]
+pub struct ServerEntry {
+    pub name: String,
+    pub ip: String,
+    pub hidden: bool,
+}
+
+fn is_gzip(data: &[u8]) -> bool {
+    data.starts_with(&GZIP_MAGIC)
+}
+
+fn gzip(data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+fn gunzip(data: &[u8]) -> Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut decoded)?;
+    Ok(decoded)
+}
+
+fn decode(data: &[u8]) -> Result<HashMap<String, Value>> {
+    let data = if is_gzip(data) {
+        gunzip(data).context("не удалось распаковать gzip")?
+    } else {
+        data.to_vec()
+    };
+    match fastnbt::from_bytes(&data)? {
+        Value::Compound(root) => Ok(root),
+        _ => bail!("корневой тег должен быть Compound"),
+    }
+}
+
+fn string_field<'a>(entry: &'a Value, key: &str) -> Option<&'a str> {
+    match entry {
+        Value::Compound(fields) => match fields.get(key) {
+            Some(Value::String(value)) => Some(value),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub fn read_servers_dat(game_dir: &Path) -> Result<Vec<ServerEntry>> {
+    let path = game_dir.join("servers.dat");
+    let data = fs::read(&path).with_context(|| format!("Не удалось прочитать {}", path.display()))?;
+    let root = decode(&data).context("servers.dat повреждён")?;
+    let servers = match root.get("servers") {
+        Some(Value::List(servers)) => servers,
+        Some(_) => bail!("servers.dat: поле servers не является списком"),
+        None => return Ok(Vec::new()),
+    };
+    Ok(servers.iter()
+        .filter_map(|server| {
+            let hidden = match server {
+                Value::Compound(fields) => matches!(fields.get("hidden"), Some(Value::Byte(value)) if *value != 0),
+                _ => false,
+            };
+            Some(ServerEntry {
+                name: string_field(server, "name").unwrap_or_default().to_string(),
+                ip: string_field(server, "ip")?.to_string(),
+                hidden,
+            })
+        })
+        .collect())
+}
+
+pub fn add_server_entry(game_dir: &Path, name: &str, ip: &str) -> Result<()> {
+    let path = game_dir.join("servers.dat");
+    let existing = fs::read(&path).ok();
+    let compressed = existing.as_deref().is_some_and(is_gzip);
+    let mut root = existing.as_deref()
+        .map(decode)
+        .transpose()
+        .context("servers.dat повреждён, список серверов не обновлён")?
+        .unwrap_or_default();
+
+    let servers = match root.entry("servers".to_string()).or_insert_with(|| Value::List(Vec::new())) {
+        Value::List(servers) => servers,
+        _ => bail!("servers.dat: поле servers не является списком"),
+    };
+    if servers.iter().any(|server| string_field(server, "ip") == Some(ip)) {
+        return Ok(());
+    }
+    match servers.iter().position(|server| string_field(server, "name") == Some(name)) {
+        Some(index) => {
+            if let Value::Compound(fields) = &mut servers[index] {
+                fields.insert("ip".to_string(), Value::String(ip.to_string()));
+            }
+        }
+        None => servers.insert(0, Value::Compound(HashMap::from([
+            ("name".to_string(), Value::String(name.to_string())),
+            ("ip".to_string(), Value::String(ip.to_string())),
+            ("hidden".to_string(), Value::Byte(0)),
+        ]))),
+    }
+
+    let mut data = fastnbt::to_bytes(&Value::Compound(root))?;
+    if compressed {
+        data = gzip(&data)?;
+    }
+    fs::write(&path, data).with_context(|| format!("Не удалось записать {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(name: &str, ip: &str, extra: &[(&str, Value)]) -> Value {
+        let mut fields = HashMap::from([
+            ("name".to_string(), Value::String(name.to_string())),
+            ("ip".to_string(), Value::String(ip.to_string())),
+        ]);
+        fields.extend(extra.iter().map(|(key, value)| (key.to_string(), value.clone())));
+        Value::Compound(fields)
+    }
+
+    fn write_servers(game_dir: &Path, servers: Vec<Value>, compressed: bool) {
+        let root = Value::Compound(HashMap::from([("servers".to_string(), Value::List(servers))]));
+        let mut data = fastnbt::to_bytes(&root).unwrap();
+        if compressed {
+            data = gzip(&data).unwrap();
+        }
+        fs::write(game_dir.join("servers.dat"), data).unwrap();
+    }
+
+    fn read_root(game_dir: &Path) -> HashMap<String, Value> {
+        decode(&fs::read(game_dir.join("servers.dat")).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn creates_servers_dat_with_our_entry() {
+        let game_dir = tempfile::tempdir().unwrap();
+        add_server_entry(game_dir.path(), "ByStep Server", "play.example.net:25565").unwrap();
+
+        let data = fs::read(game_dir.path().join("servers.dat")).unwrap();
+        assert!(!is_gzip(&data));
+        let servers = read_servers_dat(game_dir.path()).unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "ByStep Server");
+        assert_eq!(servers[0].ip, "play.example.net:25565");
+        assert!(!servers[0].hidden);
+
+        add_server_entry(game_dir.path(), "ByStep Server", "play.example.net:25565").unwrap();
+        assert_eq!(fs::read(game_dir.path().join("servers.dat")).unwrap(), data);
+    }
+
+    #[test]
+    fn keeps_user_servers_and_unknown_fields() {
+        let game_dir = tempfile::tempdir().unwrap();
+        write_servers(game_dir.path(), vec![
+            server("Hypixel", "mc.hypixel.net", &[("icon", Value::String("iVBORw0KGgo=".into())), ("acceptTextures", Value::Byte(1))]),
+            server("ByStep Server", "10.0.0.1:25565", &[("hidden", Value::Byte(0)), ("icon", Value::String("AAAA".into()))]),
+        ], false);
+
+        add_server_entry(game_dir.path(), "ByStep Server", "play.example.net:25565").unwrap();
+
+        let root = read_root(game_dir.path());
+        let Some(Value::List(servers)) = root.get("servers") else { panic!("servers list missing") };
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0], server("Hypixel", "mc.hypixel.net", &[("icon", Value::String("iVBORw0KGgo=".into())), ("acceptTextures", Value::Byte(1))]));
+        assert_eq!(servers[1], server("ByStep Server", "play.example.net:25565", &[("hidden", Value::Byte(0)), ("icon", Value::String("AAAA".into()))]));
+    }
+
+    #[test]
+    fn adds_our_server_first_and_keeps_gzip() {
+        let game_dir = tempfile::tempdir().unwrap();
+        write_servers(game_dir.path(), vec![server("Local", "localhost", &[])], true);
+
+        add_server_entry(game_dir.path(), "ByStep Server", "play.example.net:25565").unwrap();
+
+        assert!(is_gzip(&fs::read(game_dir.path().join("servers.dat")).unwrap()));
+        let servers = read_servers_dat(game_dir.path()).unwrap();
+        let ips: Vec<&str> = servers.iter().map(|server| server.ip.as_str()).collect();
+        assert_eq!(ips, ["play.example.net:25565", "localhost"]);
+    }
+
+    #[test]
+    fn unreadable_servers_dat_is_left_untouched() {
+        let game_dir = tempfile::tempdir().unwrap();
+        fs::write(game_dir.path().join("servers.dat"), b"not nbt").unwrap();
+
+        assert!(add_server_entry(game_dir.path(), "ByStep Server", "play.example.net:25565").is_err());
+
+        assert_eq!(fs::read(game_dir.path().join("servers.dat")).unwrap(), b"not nbt");
+        assert!(!game_dir.path().join("servers.dat_old").exists());
+    }
+}
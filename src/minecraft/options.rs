@@ -0,0 +1,86 @@
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+
+use super::launcher::create_default_options;
+
+const RESOURCE_PACKS_KEY: &str = "resourcePacks";
+const VANILLA_PACK: &str = "vanilla";
+const FILE_PACK_PREFIX: &str = "file/";
+
+fn read_option(game_dir: &Path, key: &str) -> Option<String> {
+    let content = fs::read_to_string(game_dir.join("options.txt")).ok()?;
+    content
+        .lines()
+        .find_map(|line| line.split_once(':').filter(|(k, _)| *k == key).map(|(_, v)| v.to_string()))
+}
+
+pub fn set_option(game_dir: &Path, key: &str, value: &str) -> Result<()> {
+    let options_path = game_dir.join("options.txt");
+    let content = fs::read_to_string(&options_path).unwrap_or_default();
+    let new_line = format!("{}:{}", key, value);
+
+    let mut replaced = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| match line.split_once(':') {
+            Some((k, _)) if k == key && !replaced => {
+                replaced = true;
+                new_line.clone()
+            }
+            _ => line.to_string(),
+        })
+        .collect();
+    if !replaced {
+        lines.push(new_line);
+    }
+
+    fs::write(&options_path, lines.join("\n") + "\n")?;
+    Ok(())
+}
+
+pub fn detect_resourcepacks(game_dir: &Path) -> Vec<String> {
+    let mut packs: Vec<String> = fs::read_dir(game_dir.join("resourcepacks"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| {
+                    let path = entry.path();
+                    path.is_dir() || path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+                })
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    packs.sort_by_key(|name| name.to_lowercase());
+    packs
+}
+
+fn read_pack_list(game_dir: &Path) -> Vec<String> {
+    read_option(game_dir, RESOURCE_PACKS_KEY)
+        .and_then(|value| serde_json::from_str::<Vec<String>>(&value).ok())
+        .unwrap_or_default()
+}
+
+pub fn enabled_resourcepacks(game_dir: &Path) -> Vec<String> {
+    read_pack_list(game_dir)
+        .into_iter()
+        .filter_map(|entry| entry.strip_prefix(FILE_PACK_PREFIX).map(str::to_string))
+        .collect()
+}
+
+pub fn write_enabled_resourcepacks(game_dir: &Path, packs: &[String]) -> Result<()> {
+    fs::create_dir_all(game_dir)?;
+    create_default_options(game_dir)?;
+
+    let mut entries: Vec<String> = read_pack_list(game_dir)
+        .into_iter()
+        .filter(|entry| !entry.starts_with(FILE_PACK_PREFIX))
+        .collect();
+    if !entries.iter().any(|entry| entry == VANILLA_PACK) {
+        entries.insert(0, VANILLA_PACK.to_string());
+    }
+    entries.extend(packs.iter().map(|pack| format!("{}{}", FILE_PACK_PREFIX, pack)));
+
+    set_option(game_dir, RESOURCE_PACKS_KEY, &serde_json::to_string(&entries)?)
+}
@@ -0,0 +1,36 @@
+use super::version::GameVersion;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DownloadKind {
+    Game,
+    Fabric,
+    Mods,
+    Shaderpacks,
+    Resourcepacks,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstallStep {
+    Preparing,
+    CheckingInstall,
+    WaitingForInstall,
+    Installing(GameVersion),
+    UpdatingLoader(String),
+    CheckingJava,
+    DownloadingJava(u8),
+    ExtractingJava { version: u8, percent: u8 },
+    FetchingVersionInfo,
+    ResumingInstall,
+    InstallingFabric,
+    FetchingFabricProfile,
+    CheckingMods,
+    ModsUnpublished(GameVersion),
+    ModsRemoved(Vec<String>),
+    Downloading { kind: DownloadKind, done: u64, total: u64 },
+    PreparingAssets { done: usize, total: usize },
+    VerifyingFiles { done: usize, total: usize },
+    SyncingContent,
+    ConfiguringShaders,
+    Launching,
+    Complete,
+}
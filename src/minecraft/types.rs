@@ -4,11 +4,36 @@ use std::collections::HashMap;
 #[derive(Debug, Deserialize)]
 pub struct GitHubFile {
     pub name: String,
+    #[serde(default)]
+    pub size: u64,
     pub download_url: Option<String>,
     #[serde(rename = "type")]
     pub file_type: String,
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct ContentManifest {
+    #[serde(default)]
+    pub mods: Vec<ManifestFile>,
+    #[serde(default)]
+    pub shaderpacks: Vec<ManifestFile>,
+    #[serde(default)]
+    pub shaderpacks_low: Vec<ManifestFile>,
+    #[serde(default)]
+    pub shaderpacks_high: Vec<ManifestFile>,
+    #[serde(default)]
+    pub resourcepacks: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ManifestFile {
+    pub name: String,
+    pub url: Option<String>,
+    #[serde(default)]
+    pub size: u64,
+    pub sha256: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct VersionManifest {
     pub versions: Vec<VersionEntry>,
@@ -84,9 +109,15 @@ pub struct OsRule {
 #[derive(Debug, Deserialize)]
 pub struct AssetIndex {
     pub objects: HashMap<String, AssetObject>,
+    #[serde(default, rename = "virtual")]
+    pub is_virtual: bool,
+    #[serde(default)]
+    pub map_to_resources: bool,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct AssetObject {
     pub hash: String,
+    #[serde(default)]
+    pub size: u64,
 }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 pub enum GameVersion {
     Fabric1_20_1,
     #[default]
@@ -48,22 +48,100 @@ impl GameVersion {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 pub enum ShaderQuality {
     Off,
+    Low,
     #[default]
-    On,
+    High,
 }
 
 impl ShaderQuality {
     pub fn display_name(&self) -> &'static str {
         match self {
             ShaderQuality::Off => "Выкл",
-            ShaderQuality::On => "Вкл",
+            ShaderQuality::Low => "Низкие",
+            ShaderQuality::High => "Высокие",
+        }
+    }
+
+    pub fn folder_name(&self) -> Option<&'static str> {
+        match self {
+            ShaderQuality::Off => None,
+            ShaderQuality::Low => Some("low"),
+            ShaderQuality::High => Some("high"),
         }
     }
 
     pub fn all() -> Vec<ShaderQuality> {
-        vec![ShaderQuality::Off, ShaderQuality::On]
+        vec![ShaderQuality::Off, ShaderQuality::Low, ShaderQuality::High]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub enum GarbageCollector {
+    JvmDefault,
+    #[default]
+    G1,
+    Zgc,
+    Shenandoah,
+}
+
+impl GarbageCollector {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            GarbageCollector::JvmDefault => "По умолчанию",
+            GarbageCollector::G1 => "G1",
+            GarbageCollector::Zgc => "ZGC",
+            GarbageCollector::Shenandoah => "Shenandoah",
+        }
+    }
+
+    pub fn min_java_version(&self) -> u8 {
+        match self {
+            GarbageCollector::JvmDefault | GarbageCollector::G1 => 8,
+            GarbageCollector::Zgc => 15,
+            GarbageCollector::Shenandoah => 12,
+        }
+    }
+
+    pub fn supported_by(&self, java_version: u8) -> bool {
+        java_version >= self.min_java_version()
     }
+
+    pub fn resolve(&self, java_version: u8) -> GarbageCollector {
+        if self.supported_by(java_version) { *self } else { GarbageCollector::G1 }
+    }
+
+    pub fn all() -> Vec<GarbageCollector> {
+        vec![GarbageCollector::JvmDefault, GarbageCollector::G1, GarbageCollector::Zgc, GarbageCollector::Shenandoah]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub enum SyncPolicy {
+    #[default]
+    Merge,
+    Mirror,
+}
+
+impl SyncPolicy {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SyncPolicy::Merge => "Слияние",
+            SyncPolicy::Mirror => "Точная копия",
+        }
+    }
+
+    pub fn all() -> Vec<SyncPolicy> {
+        vec![SyncPolicy::Merge, SyncPolicy::Mirror]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub enum QuickPlay {
+    Menu,
+    #[default]
+    Server,
+    World(String),
 }